@@ -0,0 +1,77 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use game::{primitives::{AccountIds, ChessGame, get_game_rules}, snapshot::{self, SnapshotFormat}};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use shakmaty::{Position, san::San};
+use shared::primitives::GameType;
+
+/// Plays random legal moves until the game reaches `plies`, retrying with a new seed when a
+/// game ends early. Captures are mostly avoided so games don't die of insufficient material.
+fn random_game(plies: usize) -> ChessGame {
+    let mut rng = StdRng::seed_from_u64(plies as u64);
+
+    loop {
+        let mut chess_game = ChessGame::from_scratch(
+            GameType::Correspondence1d,
+            AccountIds { w: uuid::Uuid::new_v4().to_string(), b: uuid::Uuid::new_v4().to_string() },
+            "{}".to_string(),
+            get_game_rules(GameType::Correspondence1d),
+        );
+
+        while chess_game.moves.len() < plies {
+            let legal_moves = chess_game.chess.legal_moves();
+            let quiet_moves: Vec<_> = legal_moves.iter().filter(|m| !m.is_capture()).collect();
+
+            let game_move = match quiet_moves.choose(&mut rng) {
+                Some(game_move) if rng.gen_bool(0.9) => *game_move,
+                _ => match legal_moves.choose(&mut rng) {
+                    Some(game_move) => game_move,
+                    None => break,
+                },
+            };
+
+            let san = San::from_move(&chess_game.chess, game_move).to_string();
+            let account_id = chess_game.turn_account_id().to_string();
+
+            if chess_game.make_move(&account_id, &san).is_err() {
+                break;
+            }
+        }
+
+        if chess_game.moves.len() == plies && chess_game.check_game_result().ok().flatten().is_none() {
+            return chess_game;
+        }
+    }
+}
+
+/// A move request as the repository sees it: load the stored game, apply one move, store it.
+fn bench_move_round_trip(c: &mut Criterion) {
+    let mut group = c.benchmark_group("move_round_trip");
+
+    for plies in [100, 200, 400] {
+        let chess_game = random_game(plies);
+        let game_move = chess_game.chess.legal_moves()
+            .iter()
+            .map(|m| San::from_move(&chess_game.chess, m).to_string())
+            .next()
+            .expect("game has a legal move");
+
+        for format in [SnapshotFormat::Json, SnapshotFormat::Binary] {
+            let stored = snapshot::encode(&chess_game, format);
+            let account_id = chess_game.turn_account_id().to_string();
+            println!("{} plies, {}: {} bytes", plies, format.to_str(), stored.len());
+
+            group.bench_with_input(BenchmarkId::new(format.to_str(), plies), &stored, |b, stored| {
+                b.iter(|| {
+                    let mut chess_game = snapshot::decode(stored).unwrap();
+                    chess_game.make_move(&account_id, &game_move).unwrap();
+                    snapshot::encode(&chess_game, format)
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_move_round_trip);
+criterion_main!(benches);
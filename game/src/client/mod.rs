@@ -1,2 +1,3 @@
 pub mod redis;
 pub mod nats;
+pub mod ranking;
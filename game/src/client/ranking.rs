@@ -0,0 +1,20 @@
+use shared::generated::ranking_service::ranking_service_client::RankingServiceClient;
+use tonic::transport::Channel;
+
+use crate::config::RankingServiceConfig;
+
+pub type RankingGrpcClient = RankingServiceClient<Channel>;
+
+#[derive(Clone)]
+pub struct RankingClient {}
+
+impl RankingClient {
+    /// Connects lazily, so the game service can start before matchmaking.
+    pub fn connect(config: &RankingServiceConfig) -> Result<RankingGrpcClient, shared::error::ChessmartApiError> {
+        let channel = Channel::from_shared(config.ranking_service_url.clone())
+            .map_err(|e| shared::error::ChessmartApiError::GrpcError(e.to_string()))?
+            .connect_lazy();
+
+        Ok(RankingServiceClient::new(channel))
+    }
+}
@@ -1,6 +1,8 @@
 use std::net::SocketAddr;
 use clap::Parser;
 
+use crate::snapshot::SnapshotFormat;
+
 #[derive(Parser, Debug)]
 #[command(arg_required_else_help(true))]
 pub struct Opts {
@@ -15,6 +17,12 @@ pub struct Opts {
 
     #[clap(flatten)]
     pub nats: NatsConfig,
+
+    #[clap(flatten)]
+    pub storage: StorageConfig,
+
+    #[clap(flatten)]
+    pub ranking_service: RankingServiceConfig,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -33,6 +41,11 @@ pub struct RedisConfig {
 
     #[clap(long, env = "REDIS_DATABASE_NAME")]
     pub database_name: String,
+
+    /// Fails every script and transaction whose keys would land in different Redis Cluster slots,
+    /// to catch a broken key layout on a single Redis
+    #[clap(long, env = "REDIS_CHECK_KEY_SLOTS")]
+    pub check_key_slots: bool,
 }
 
 impl Default for RedisConfig {
@@ -43,6 +56,7 @@ impl Default for RedisConfig {
             port: 6379,
             host: "127.0.0.1".to_owned(),
             database_name: "".to_owned(),
+            check_key_slots: false,
         }
     }
 }
@@ -90,6 +104,8 @@ pub struct ApiConfig {
     pub tracing: Option<TracingConfig>,
     pub redis: RedisConfig,
     pub nats: NatsConfig,
+    pub storage: StorageConfig,
+    pub ranking_service: RankingServiceConfig,
 }
 
 impl From<Opts> for ApiConfig {
@@ -99,6 +115,8 @@ impl From<Opts> for ApiConfig {
             tracing: opts.tracing,
             redis: opts.redis,
             nats: opts.nats,
+            storage: opts.storage,
+            ranking_service: opts.ranking_service,
         }
     }
 }
@@ -118,12 +136,16 @@ impl ApiConfig {
         tracing: Option<TracingConfig>,
         redis: RedisConfig,
         nats: NatsConfig,
+        storage: StorageConfig,
+        ranking_service: RankingServiceConfig,
     ) -> Self {
         Self {
             server,
             tracing,
             redis,
             nats,
+            storage,
+            ranking_service,
         }
     }
 }
@@ -158,3 +180,45 @@ impl NatsConfig {
     )
   }
 }
+
+#[derive(Debug, Clone, Parser)]
+pub struct StorageConfig {
+    /// Encoding used when writing game state to redis, both formats are always readable
+    #[clap(long, value_enum, default_value = "binary", env = "GAME_SNAPSHOT_FORMAT")]
+    pub snapshot_format: SnapshotFormat,
+
+    /// Where the authoritative game state lives, redis alone or the per-game event subjects
+    #[clap(long, value_enum, default_value = "redis", env = "GAME_STATE_MODE")]
+    pub game_state_mode: GameStateMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GameStateMode {
+    /// The redis hash is the only copy of a game
+    Redis,
+    /// Games are folded from their JetStream events, redis is a rebuildable cache
+    EventSourced,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            snapshot_format: SnapshotFormat::Binary,
+            game_state_mode: GameStateMode::Redis,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct RankingServiceConfig {
+    #[clap(long, default_value = "http://[::1]:50052", env = "RANKING_SERVICE_URL")]
+    pub ranking_service_url: String,
+}
+
+impl Default for RankingServiceConfig {
+    fn default() -> Self {
+        Self {
+            ranking_service_url: "http://[::1]:50052".to_owned(),
+        }
+    }
+}
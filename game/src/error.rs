@@ -31,10 +31,40 @@ pub enum GameServiceError {
 
     #[error("{0}")]
     UnknownGameTypeError(String),
+
+    #[error("Invalid game snapshot: {0}")]
+    InvalidSnapshotError(String),
+
+    #[error("Event store error: {0}")]
+    EventStoreError(String),
+
+    #[error("{0}")]
+    InvalidArgumentError(String),
+
+    #[error("Challenge not found: id={0}")]
+    ChallengeNotFoundError(String),
+
+    #[error("{0}")]
+    ChallengeError(String),
+
+    #[error("{0}")]
+    RematchError(String),
+
+    #[error("Ranked games are closed until the next season starts")]
+    NoActiveSeasonError,
+
+    #[error("Ranking service error: {0}")]
+    RankingServiceError(String),
 }
 
 impl From<GameServiceError> for tonic::Status {
     fn from(error: GameServiceError) -> Self {
-        tonic::Status::invalid_argument(error.to_string())
+        match error {
+            // Lets clients tell a lost race from an invalid move and refresh their state
+            GameServiceError::ConcurrentMoveError(_) => tonic::Status::aborted(error.to_string()),
+            // Lets matchmaking tell a game that is over or gone from a failed lookup
+            GameServiceError::GameNotFoundError(_) => tonic::Status::not_found(error.to_string()),
+            _ => tonic::Status::invalid_argument(error.to_string()),
+        }
     }
 }
@@ -0,0 +1,211 @@
+//! Events of the event-sourced game state.
+//!
+//! Every game has its own subject (`chessmart.game.<id>.events`) that holds the full history of
+//! the game. Folding the events of a subject in order rebuilds the game, which is how the redis
+//! cache is repopulated after a miss.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shakmaty::uci::UciMove;
+use shared::primitives::GameType;
+use std::str::FromStr;
+
+use crate::{error::GameServiceError, primitives::{AccountIds, ChessGame, GameClocks, GameResult, GameRules}};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum GameStateEvent {
+    GameCreated {
+        game_id: String,
+        game_type: String,
+        account_ids: AccountIds,
+        metadata: String,
+        game_rules: GameRules,
+        game_clocks: GameClocks,
+        #[serde(default)]
+        ranked: bool,
+    },
+    MoveMade {
+        account_id: String,
+        /// Move in UCI notation
+        r#move: String,
+        at: DateTime<Utc>,
+        /// Clocks of both sides (ms) once the move was made
+        clocks: ClockSnapshot,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_move_id: Option<String>,
+    },
+    Resigned {
+        account_id: String,
+        at: DateTime<Utc>,
+    },
+    /// Final event of a game, including flag falls and correspondence timeouts.
+    GameEnded {
+        result: GameResult,
+        at: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClockSnapshot {
+    pub w: u64,
+    pub b: u64,
+}
+
+impl GameStateEvent {
+    pub fn subject(game_id: &str) -> String {
+        format!("chessmart.game.{}.events", game_id)
+    }
+
+    pub fn game_created(chess_game: &ChessGame) -> Self {
+        GameStateEvent::GameCreated {
+            game_id: chess_game.id.clone(),
+            game_type: chess_game.game_type.to_str().to_owned(),
+            account_ids: chess_game.account_ids.clone(),
+            metadata: chess_game.metadata.clone(),
+            game_rules: chess_game.game_rules.clone(),
+            game_clocks: chess_game.game_clocks.clone(),
+            ranked: chess_game.ranked,
+        }
+    }
+
+    /// Records the last move of `chess_game`.
+    pub fn move_made(chess_game: &ChessGame, account_id: &str, at: DateTime<Utc>) -> Self {
+        GameStateEvent::MoveMade {
+            account_id: account_id.to_owned(),
+            r#move: chess_game.moves.last().map(|game_move| game_move.to_string()).unwrap_or_default(),
+            at,
+            clocks: ClockSnapshot { w: chess_game.game_clocks.w, b: chess_game.game_clocks.b },
+            client_move_id: chess_game.client_move_id_at(account_id, chess_game.seq()).map(str::to_owned),
+        }
+    }
+}
+
+impl ChessGame {
+    /// Rebuilds a game from its events. Returns `None` for games that have ended.
+    pub fn from_events(events: &[GameStateEvent]) -> Result<Option<Self>, GameServiceError> {
+        let mut events = events.iter();
+
+        let mut chess_game = match events.next() {
+            Some(GameStateEvent::GameCreated { game_id, game_type, account_ids, metadata, game_rules, game_clocks, ranked }) => {
+                let mut chess_game = ChessGame::new(
+                    game_id.clone(),
+                    shakmaty::Chess::new(),
+                    GameType::from_str(game_type)
+                        .map_err(|e| GameServiceError::UnknownGameTypeError(e.to_string()))?,
+                    account_ids.clone(),
+                    metadata.clone(),
+                    game_rules.clone(),
+                    game_clocks.clone(),
+                );
+                chess_game.ranked = *ranked;
+
+                chess_game
+            }
+            Some(_) => return Err(GameServiceError::InvalidSnapshotError(
+                "game events don't start with game-created".to_string()
+            )),
+            None => return Ok(None),
+        };
+
+        for event in events {
+            if let GameStateEvent::GameEnded { .. } = event {
+                return Ok(None);
+            }
+
+            chess_game.apply_event(event)?;
+        }
+
+        Ok(Some(chess_game))
+    }
+
+    fn apply_event(&mut self, event: &GameStateEvent) -> Result<(), GameServiceError> {
+        match event {
+            GameStateEvent::MoveMade { account_id, r#move, at, clocks, client_move_id } => {
+                if self.turn_account_id() != account_id {
+                    return Err(GameServiceError::TurnError(
+                        format!("Game {}: recorded move {} out of turn", self.id, r#move)
+                    ));
+                }
+
+                let game_move = UciMove::from_str(r#move)
+                    .map_err(|e| GameServiceError::InvalidSnapshotError(e.to_string()))?
+                    .to_move(&self.chess)
+                    .map_err(|e| GameServiceError::InvalidMove(e.to_string()))?;
+
+                self.play(&game_move, *at)?;
+                self.game_clocks.w = clocks.w;
+                self.game_clocks.b = clocks.b;
+
+                if let Some(client_move_id) = client_move_id {
+                    self.record_client_move(account_id, client_move_id);
+                }
+            }
+            GameStateEvent::Resigned { account_id, .. } => {
+                self.resign(account_id)?;
+            }
+            GameStateEvent::GameCreated { .. } | GameStateEvent::GameEnded { .. } => {
+                return Err(GameServiceError::InvalidSnapshotError(
+                    format!("Game {}: unexpected event in the middle of the game", self.id)
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{GameOutcome, GameOverReason, get_game_rules};
+
+    fn recorded_game() -> (ChessGame, Vec<GameStateEvent>) {
+        let mut chess_game = ChessGame::from_scratch(
+            GameType::Blitz3_2,
+            AccountIds { w: "white".to_string(), b: "black".to_string() },
+            "".to_string(),
+            get_game_rules(GameType::Blitz3_2)
+        );
+        let mut events = vec![GameStateEvent::game_created(&chess_game)];
+
+        for (account_id, game_move) in [("white", "e4"), ("black", "c5"), ("white", "Nf3"), ("black", "d6")] {
+            let at = Utc::now();
+            chess_game.make_move_at(account_id, game_move, at).unwrap();
+            chess_game.record_client_move(account_id, game_move);
+            events.push(GameStateEvent::move_made(&chess_game, account_id, at));
+        }
+
+        // Events go through JSON on their way to the stream
+        let events = events.iter()
+            .map(|event| serde_json::from_slice(&serde_json::to_vec(event).unwrap()).unwrap())
+            .collect();
+
+        (chess_game, events)
+    }
+
+    #[test]
+    fn test_folding_events_rebuilds_game() {
+        let (chess_game, events) = recorded_game();
+
+        let rebuilt = ChessGame::from_events(&events).unwrap().unwrap();
+
+        assert_eq!(rebuilt.to_string(), chess_game.to_string());
+    }
+
+    #[test]
+    fn test_ended_game_is_not_rebuilt() {
+        let (_, mut events) = recorded_game();
+        events.push(GameStateEvent::Resigned { account_id: "white".to_string(), at: Utc::now() });
+        events.push(GameStateEvent::GameEnded {
+            result: GameResult {
+                outcome: GameOutcome::Black,
+                winner_account_id: Some("black".to_string()),
+                reason: GameOverReason::Resignation,
+            },
+            at: Utc::now(),
+        });
+
+        assert!(ChessGame::from_events(&events).unwrap().is_none());
+    }
+}
@@ -0,0 +1,311 @@
+use std::str::FromStr;
+
+use crate::{primitives::{ChallengeColor, CreateChallengeDto, CreateGameDto, MakeMoveDto, MakeMoveResult}, state::state::AppState};
+
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod events;
+pub mod jobs;
+pub mod primitives;
+pub mod repositories;
+pub mod services;
+pub mod snapshot;
+pub mod state;
+pub mod utils;
+
+pub struct GameGatewayService {
+    state: AppState,
+}
+
+impl GameGatewayService {
+    pub fn new(
+        state: AppState
+    ) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl shared::GameService for GameGatewayService {
+    async fn create_game(
+        &self,
+        request: tonic::Request<shared::CreateGameRequest>,
+    ) -> Result<tonic::Response<shared::CreateGameResponse>, tonic::Status> {
+        let shared::CreateGameRequest {
+            account_id0,
+            account_id1,
+            game_type,
+            metadata,
+            ranked,
+            white_account_id,
+        } = request.into_inner();
+
+        let game_type = shared::primitives::GameType::from_str(&game_type)?;
+
+        let chess_game = crate::services::game_service::create_game(
+            &self.state,
+            CreateGameDto {
+                account_id0,
+                account_id1,
+                game_type,
+                metadata,
+                ranked,
+                white_account_id,
+            }
+        ).await?;
+
+        Ok(tonic::Response::new(shared::CreateGameResponse{
+            game_id: chess_game.id.clone(),
+            game_repr: chess_game.to_string()
+        }))
+    }
+
+    async fn get_game_state(
+        &self,
+        request: tonic::Request<shared::GetGameStateRequest>,
+    ) -> Result<tonic::Response<shared::GetGameStateResponse>, tonic::Status> {
+        let shared::GetGameStateRequest { game_id } = request.into_inner();
+
+        let chess_game = crate::services::game_service::get_game(
+            &self.state,
+            game_id.as_str()
+        ).await?;
+
+        Ok(tonic::Response::new(shared::GetGameStateResponse {
+            game_repr: chess_game.to_string()
+        }))
+    }
+
+    async fn check_game_result(
+        &self,
+        request: tonic::Request<shared::CheckGameResultRequest>,
+    ) -> Result<tonic::Response<shared::CheckGameResultResponse>, tonic::Status> {
+        let shared::CheckGameResultRequest { game_id } = request.into_inner();
+
+        let mut chess_game = crate::services::game_service::get_game(
+            &self.state,
+            game_id.as_str()
+        ).await?;
+
+        crate::services::game_service::check_game_result(
+            &self.state,
+            &mut chess_game
+        ).await?;
+
+        Ok(tonic::Response::new(shared::CheckGameResultResponse {}))
+    }
+
+    async fn make_move(
+        &self,
+        request: tonic::Request<shared::MakeMoveRequest>,
+    ) -> Result<tonic::Response<shared::MakeMoveResponse>, tonic::Status> {
+        let shared::MakeMoveRequest {
+            game_id,
+            r#move,
+            account_id,
+            client_move_id,
+            expected_seq,
+        } = request.into_inner();
+
+        let MakeMoveResult { chess_game, seq, duplicate } = crate::services::game_service::make_move(
+            &self.state,
+            MakeMoveDto {
+                game_id,
+                account_id,
+                game_move: r#move,
+                client_move_id,
+                expected_seq,
+            }
+        ).await?;
+
+        Ok(tonic::Response::new(shared::MakeMoveResponse {
+            game_repr: chess_game.to_string(),
+            seq,
+            duplicate,
+        }))
+    }
+
+    async fn resign(
+        &self,
+        request: tonic::Request<shared::ResignRequest>,
+    ) -> Result<tonic::Response<shared::ResignResponse>, tonic::Status> {
+        let shared::ResignRequest { game_id, account_id } = request.into_inner();
+
+        let chess_game = crate::services::game_service::resign(
+            &self.state,
+            &game_id,
+            &account_id,
+        ).await?;
+
+        Ok(tonic::Response::new(shared::ResignResponse {
+            game_repr: chess_game.to_string(),
+        }))
+    }
+
+    async fn get_my_turn_games(
+        &self,
+        request: tonic::Request<shared::GetMyTurnGamesRequest>,
+    ) -> Result<tonic::Response<shared::GetMyTurnGamesResponse>, tonic::Status> {
+        let shared::GetMyTurnGamesRequest { account_id } = request.into_inner();
+
+        let chess_games = crate::services::game_service::get_my_turn_games(
+            &self.state,
+            &account_id,
+        ).await?;
+
+        Ok(tonic::Response::new(shared::GetMyTurnGamesResponse {
+            game_reprs: chess_games.iter().map(|chess_game| chess_game.to_string()).collect(),
+        }))
+    }
+
+    async fn create_challenge(
+        &self,
+        request: tonic::Request<shared::CreateChallengeRequest>,
+    ) -> Result<tonic::Response<shared::CreateChallengeResponse>, tonic::Status> {
+        let shared::CreateChallengeRequest {
+            account_id,
+            challenged_account_id,
+            game_type,
+            color,
+            ranked,
+        } = request.into_inner();
+
+        let game_type = shared::primitives::GameType::from_str(&game_type)?;
+        let color = ChallengeColor::from_str(&color)?;
+
+        let challenge = crate::services::challenge_service::create_challenge(
+            &self.state,
+            CreateChallengeDto {
+                account_id,
+                challenged_account_id,
+                game_type,
+                color,
+                ranked,
+            }
+        ).await?;
+
+        Ok(tonic::Response::new(shared::CreateChallengeResponse {
+            challenge: Some(shared::Challenge::from(challenge)),
+        }))
+    }
+
+    async fn get_challenge(
+        &self,
+        request: tonic::Request<shared::GetChallengeRequest>,
+    ) -> Result<tonic::Response<shared::GetChallengeResponse>, tonic::Status> {
+        let shared::GetChallengeRequest { challenge_id } = request.into_inner();
+
+        let challenge = crate::services::challenge_service::get_challenge(
+            &self.state,
+            &challenge_id,
+        ).await?;
+
+        Ok(tonic::Response::new(shared::GetChallengeResponse {
+            challenge: Some(shared::Challenge::from(challenge)),
+        }))
+    }
+
+    async fn get_challenges(
+        &self,
+        request: tonic::Request<shared::GetChallengesRequest>,
+    ) -> Result<tonic::Response<shared::GetChallengesResponse>, tonic::Status> {
+        let shared::GetChallengesRequest { account_id } = request.into_inner();
+
+        let challenges = crate::services::challenge_service::get_challenges(
+            &self.state,
+            &account_id,
+        ).await?;
+
+        Ok(tonic::Response::new(shared::GetChallengesResponse {
+            challenges: challenges.into_iter().map(shared::Challenge::from).collect(),
+        }))
+    }
+
+    async fn accept_challenge(
+        &self,
+        request: tonic::Request<shared::AcceptChallengeRequest>,
+    ) -> Result<tonic::Response<shared::AcceptChallengeResponse>, tonic::Status> {
+        let shared::AcceptChallengeRequest { account_id, challenge_id } = request.into_inner();
+
+        let (challenge, chess_game) = crate::services::challenge_service::accept_challenge(
+            &self.state,
+            &account_id,
+            &challenge_id,
+        ).await?;
+
+        Ok(tonic::Response::new(shared::AcceptChallengeResponse {
+            challenge: Some(shared::Challenge::from(challenge)),
+            game_repr: chess_game.to_string(),
+        }))
+    }
+
+    async fn decline_challenge(
+        &self,
+        request: tonic::Request<shared::DeclineChallengeRequest>,
+    ) -> Result<tonic::Response<shared::DeclineChallengeResponse>, tonic::Status> {
+        let shared::DeclineChallengeRequest { account_id, challenge_id } = request.into_inner();
+
+        let challenge = crate::services::challenge_service::decline_challenge(
+            &self.state,
+            &account_id,
+            &challenge_id,
+        ).await?;
+
+        Ok(tonic::Response::new(shared::DeclineChallengeResponse {
+            challenge: Some(shared::Challenge::from(challenge)),
+        }))
+    }
+
+    async fn cancel_challenge(
+        &self,
+        request: tonic::Request<shared::CancelChallengeRequest>,
+    ) -> Result<tonic::Response<shared::CancelChallengeResponse>, tonic::Status> {
+        let shared::CancelChallengeRequest { account_id, challenge_id } = request.into_inner();
+
+        let challenge = crate::services::challenge_service::cancel_challenge(
+            &self.state,
+            &account_id,
+            &challenge_id,
+        ).await?;
+
+        Ok(tonic::Response::new(shared::CancelChallengeResponse {
+            challenge: Some(shared::Challenge::from(challenge)),
+        }))
+    }
+
+    async fn offer_rematch(
+        &self,
+        request: tonic::Request<shared::OfferRematchRequest>,
+    ) -> Result<tonic::Response<shared::OfferRematchResponse>, tonic::Status> {
+        let shared::OfferRematchRequest { account_id, game_id } = request.into_inner();
+
+        let rematch = crate::services::rematch_service::offer_rematch(
+            &self.state,
+            &account_id,
+            &game_id,
+        ).await?;
+
+        Ok(tonic::Response::new(shared::OfferRematchResponse {
+            expires_at: rematch.expires_at.timestamp_millis(),
+        }))
+    }
+
+    async fn accept_rematch(
+        &self,
+        request: tonic::Request<shared::AcceptRematchRequest>,
+    ) -> Result<tonic::Response<shared::AcceptRematchResponse>, tonic::Status> {
+        let shared::AcceptRematchRequest { account_id, game_id } = request.into_inner();
+
+        let (_, chess_game) = crate::services::rematch_service::accept_rematch(
+            &self.state,
+            &account_id,
+            &game_id,
+        ).await?;
+
+        Ok(tonic::Response::new(shared::AcceptRematchResponse {
+            game_id: chess_game.id.clone(),
+            game_repr: chess_game.to_string(),
+        }))
+    }
+}
@@ -1,13 +1,16 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use shakmaty::{Chess, Color, Move, Position, san::San};
+use shakmaty::{CastlingMode, Chess, Color, EnPassantMode, Move, Position, fen::Fen, san::San, uci::UciMove};
 use shared::{error::ChessmartApiError, primitives::GameType};
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 use crate::error::GameServiceError;
 
 const MAX_MOVES: u64 = 300;
 
+/// How long after a game ends its players can still agree on a rematch.
+const REMATCH_WINDOW_SECONDS: i64 = 60;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountIds {
     pub w: String,
@@ -16,8 +19,12 @@ pub struct AccountIds {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameRules {
-    time_limit_ms: u64,
-    time_increase_per_turn_ms: u64,
+    pub(crate) time_limit_ms: u64,
+    pub(crate) time_increase_per_turn_ms: u64,
+    /// Set for correspondence games, where each side gets a fixed number of days per move
+    /// instead of a running clock.
+    #[serde(default)]
+    pub(crate) days_per_move: Option<u64>,
 }
 
 impl GameRules {
@@ -25,16 +32,32 @@ impl GameRules {
         Self {
             time_limit_ms,
             time_increase_per_turn_ms,
+            days_per_move: None,
+        }
+    }
+
+    pub fn correspondence(days_per_move: u64) -> Self {
+        Self {
+            time_limit_ms: days(days_per_move),
+            time_increase_per_turn_ms: 0,
+            days_per_move: Some(days_per_move),
         }
     }
+
+    pub fn is_correspondence(&self) -> bool {
+        self.days_per_move.is_some()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameClocks {
     pub w: u64,
     pub b: u64,
-    start_timestamp: DateTime<Utc>,
-    last_move_timestamp: Option<i32>,
+    pub(crate) start_timestamp: DateTime<Utc>,
+    pub(crate) last_move_timestamp: Option<i32>,
+    /// Deadline for the side to move in correspondence games.
+    #[serde(default)]
+    pub move_deadline: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone)]
@@ -47,10 +70,39 @@ pub struct ChessGame {
     pub game_rules: GameRules,
     pub game_clocks: GameClocks,
     pub resigned_color: Option<ColorWrapper>,
+    pub ranked: bool,
+    /// Moves played so far, kept as history next to the current position.
+    pub moves: Vec<UciMove>,
+    pub(crate) seq: u64,
+    /// Client move ids of every move each side made, used to recognise retried moves.
+    pub(crate) client_moves: ClientMoves,
+    /// Sequence of the last event of an event-sourced game, not part of the snapshot.
+    pub(crate) event_seq: Option<u64>,
+}
+
+/// Seq produced by each client move id of a side, for the whole game.
+pub type SideClientMoves = BTreeMap<String, u64>;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientMoves {
+    pub w: SideClientMoves,
+    pub b: SideClientMoves,
+}
+
+impl ClientMoves {
+    pub fn side(&self, account_ids: &AccountIds, account_id: &str) -> Option<&SideClientMoves> {
+        if account_id == account_ids.w {
+            Some(&self.w)
+        } else if account_id == account_ids.b {
+            Some(&self.b)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct ColorWrapper(Color);
+pub struct ColorWrapper(pub(crate) Color);
 
 impl From<Color> for ColorWrapper {
     fn from(color: Color) -> Self {
@@ -113,7 +165,17 @@ pub struct JsonRepr {
     pub game_rules: GameRules,
     pub game_clocks: GameClocks,
     pub resigned_color: Option<ColorWrapper>,
+    /// Current position. Older representations without it are rebuilt by replaying SAN moves.
+    #[serde(default)]
+    pub fen: Option<String>,
+    /// Moves played so far, in UCI notation (SAN in older representations).
+    #[serde(default)]
+    pub moves: Vec<String>,
     pub seq: u64,
+    #[serde(default)]
+    pub client_moves: ClientMoves,
+    #[serde(default)]
+    pub ranked: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -192,6 +254,11 @@ impl ChessGame {
             game_rules,
             game_clocks,
             resigned_color: None,
+            ranked: false,
+            moves: Vec::new(),
+            seq: 0,
+            client_moves: ClientMoves::default(),
+            event_seq: None,
         }
     }
 
@@ -201,7 +268,9 @@ impl ChessGame {
         metadata: String,
         game_rules: GameRules
     ) -> Self {
-        let GameRules { time_limit_ms, .. } = game_rules.clone();
+        let GameRules { time_limit_ms, days_per_move, .. } = game_rules.clone();
+        let start_timestamp = Utc::now();
+        let move_deadline = days_per_move.map(|days| start_timestamp + Duration::days(days as i64));
 
         Self::new(
             uuid::Uuid::new_v4().to_string(),
@@ -213,14 +282,51 @@ impl ChessGame {
             GameClocks {
                 w: time_limit_ms,
                 b: time_limit_ms,
-                start_timestamp: Utc::now(),
+                start_timestamp,
                 last_move_timestamp: None,
+                move_deadline,
             }
         )
     }
 
     pub fn seq(&self) -> u64 {
-        self.chess.fullmoves().get() as u64
+        self.seq
+    }
+
+    /// Seq produced by the move `account_id` submitted with `client_move_id`, if it was applied.
+    pub fn find_client_move(&self, account_id: &str, client_move_id: &str) -> Option<u64> {
+        self.client_moves.side(&self.account_ids, account_id)?
+            .get(client_move_id)
+            .copied()
+    }
+
+    /// Client move id of the move `account_id` made at `seq`, if it was submitted with one.
+    pub fn client_move_id_at(&self, account_id: &str, seq: u64) -> Option<&str> {
+        self.client_moves.side(&self.account_ids, account_id)?
+            .iter()
+            .find(|(_, client_move_seq)| **client_move_seq == seq)
+            .map(|(client_move_id, _)| client_move_id.as_str())
+    }
+
+    /// Remembers the id of the move `account_id` has just made.
+    pub fn record_client_move(&mut self, account_id: &str, client_move_id: &str) {
+        let client_moves = if account_id == self.account_ids.w {
+            &mut self.client_moves.w
+        } else if account_id == self.account_ids.b {
+            &mut self.client_moves.b
+        } else {
+            return;
+        };
+
+        client_moves.insert(client_move_id.to_owned(), self.seq);
+    }
+
+    /// Account id of the player whose turn it is.
+    pub fn turn_account_id(&self) -> &str {
+        match self.chess.turn() {
+            Color::Black => self.account_ids.b.as_str(),
+            Color::White => self.account_ids.w.as_str(),
+        }
     }
 
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
@@ -236,7 +342,11 @@ impl ChessGame {
             game_rules: self.game_rules.clone(),
             game_clocks: self.game_clocks.clone(),
             resigned_color: self.resigned_color,
+            fen: Some(self.fen()),
+            moves: self.moves.iter().map(|game_move| game_move.to_string()).collect(),
             seq: self.seq(),
+            client_moves: self.client_moves.clone(),
+            ranked: self.ranked,
         };
 
         serde_json::to_string(&json_repr)
@@ -246,6 +356,10 @@ impl ChessGame {
         self.to_json().unwrap_or_else(|_| "{}".to_string())
     }
 
+    pub fn fen(&self) -> String {
+        Fen::from_position(self.chess.clone(), EnPassantMode::Legal).to_string()
+    }
+
     pub fn from_string(game_repr: &str) -> Result<Self, GameServiceError> {
         let json_repr = serde_json::from_str::<JsonRepr>(game_repr)?;
         let JsonRepr {
@@ -255,19 +369,67 @@ impl ChessGame {
             metadata,
             game_rules,
             game_clocks,
-            resigned_color, ..} = json_repr;
+            resigned_color,
+            fen,
+            moves,
+            seq,
+            client_moves,
+            ranked } = json_repr;
+
+        let (chess, moves) = match fen {
+            Some(fen) => {
+                let moves = moves.iter()
+                    .map(|game_move| game_move.parse::<UciMove>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| GameServiceError::InvalidSnapshotError(e.to_string()))?;
+
+                (Self::chess_from_fen(&fen)?, moves)
+            }
+            None => Self::replay_san_moves(&moves)?,
+        };
+
         Ok(Self {
             id,
             game_type: GameType::from_str(&game_type).map_err(|e: ChessmartApiError| GameServiceError::UnknownGameTypeError(e.to_string()) )?,
             account_ids,
-            chess: Chess::new(),
+            chess,
             metadata,
             game_rules,
             game_clocks,
-            resigned_color
+            resigned_color,
+            ranked,
+            moves,
+            seq,
+            client_moves,
+            event_seq: None,
         })
     }
 
+    pub(crate) fn chess_from_fen(fen: &str) -> Result<Chess, GameServiceError> {
+        let fen: Fen = fen.parse()
+            .map_err(|e: shakmaty::fen::ParseFenError| GameServiceError::InvalidSnapshotError(e.to_string()))?;
+
+        fen.into_position(CastlingMode::Standard)
+            .map_err(|e| GameServiceError::InvalidSnapshotError(e.to_string()))
+    }
+
+    /// Rebuilds the position of a representation written before the FEN was stored.
+    fn replay_san_moves(moves: &[String]) -> Result<(Chess, Vec<UciMove>), GameServiceError> {
+        let mut chess = Chess::new();
+        let mut uci_moves = Vec::with_capacity(moves.len());
+
+        for game_move in moves {
+            let san: San = game_move.parse()
+                .map_err(|e: shakmaty::san::ParseSanError| GameServiceError::UnexpectedError(e.to_string()))?;
+            let game_move = san.to_move(&chess)
+                .map_err(|e| GameServiceError::UnexpectedError(e.to_string()))?;
+            uci_moves.push(game_move.to_uci(CastlingMode::Standard));
+            chess.play_unchecked(&game_move);
+        }
+
+        Ok((chess, uci_moves))
+    }
+
     // fn get_pgn(&self) -> String {
     //     // Use shakmaty's PGN export functionality
     //     // The exact method depends on how you're using shakmaty
@@ -288,6 +450,10 @@ impl ChessGame {
     // }
 
     fn update_clock(&mut self) {
+        if self.game_rules.is_correspondence() {
+            return;
+        }
+
         let turn_color = self.chess.turn();
         let now = Utc::now().timestamp() as u64;
 
@@ -296,6 +462,7 @@ impl ChessGame {
             start_timestamp,
             b,
             w,
+            move_deadline,
         } = self.game_clocks;
 
         let start_time = match last_move_timestamp {
@@ -319,6 +486,7 @@ impl ChessGame {
             b: b_updated,
             last_move_timestamp,
             start_timestamp,
+            move_deadline,
         };
         self.game_clocks = game_clocks;
     }
@@ -419,12 +587,7 @@ impl ChessGame {
         }
 
         // Clock timeout (must be the last check)
-        let game_clock = match turn_color {
-            Color::Black => self.game_clocks.b,
-            Color::White => self.game_clocks.w
-        };
-
-        if game_clock == 0 {
+        if self.is_flagged() {
             let winner_color = Self::get_other_color(turn_color);
 
             let AccountIds { b, w } = self.account_ids.clone();
@@ -456,6 +619,22 @@ impl ChessGame {
         Ok(None)
     }
 
+    /// Player who lost a real-time game on time without having made a single move.
+    pub fn abandoned_account_id(&self, result: &GameResult) -> Option<&str> {
+        if self.game_rules.is_correspondence() {
+            return None;
+        }
+
+        // White moves first, so white made every other move starting with the first one
+        let (account_id, moves_made) = match result.reason {
+            GameOverReason::BlackTimeout => (&self.account_ids.w, self.moves.len().div_ceil(2)),
+            GameOverReason::WhiteTimeout => (&self.account_ids.b, self.moves.len() / 2),
+            _ => return None,
+        };
+
+        (moves_made == 0).then_some(account_id.as_str())
+    }
+
     fn get_other_color(color: Color) -> Color {
         match color {
             Color::Black => Color::White,
@@ -463,7 +642,32 @@ impl ChessGame {
         }
     }
 
-    pub fn make_move(&self, account_id: &str, game_move: &str) -> Result<(Chess, Move), GameServiceError> {
+    /// Whether the side to move ran out of time, either on the clock or past its correspondence
+    /// move deadline.
+    fn is_flagged(&self) -> bool {
+        if self.game_rules.is_correspondence() {
+            return self.game_clocks.move_deadline
+                .is_some_and(|deadline| Utc::now() >= deadline);
+        }
+
+        let game_clock = match self.chess.turn() {
+            Color::Black => self.game_clocks.b,
+            Color::White => self.game_clocks.w
+        };
+
+        game_clock == 0
+    }
+
+    pub fn make_move(&mut self, account_id: &str, game_move: &str) -> Result<Move, GameServiceError> {
+        self.make_move_at(account_id, game_move, Utc::now())
+    }
+
+    pub fn make_move_at(
+        &mut self,
+        account_id: &str,
+        game_move: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Move, GameServiceError> {
         let turn = self.chess.turn();
 
         if self.is_game_over() {
@@ -486,15 +690,30 @@ impl ChessGame {
         let game_move = san.to_move(&self.chess)
             .map_err(|e| GameServiceError::UnexpectedError(e.to_string()))?;
 
-        let new_position = self.chess.clone().play(&game_move)
+        self.play(&game_move, at)?;
+
+        Ok(game_move)
+    }
+
+    /// Plays an already validated move made at `at`.
+    pub(crate) fn play(&mut self, game_move: &Move, at: DateTime<Utc>) -> Result<(), GameServiceError> {
+        let new_position = self.chess.clone().play(game_move)
             .map_err(|e| GameServiceError::UnexpectedError(e.to_string()))?;
-        Ok((new_position, game_move))
+
+        self.chess = new_position;
+        self.moves.push(game_move.to_uci(CastlingMode::Standard));
+        self.seq += 1;
+
+        if let Some(days_per_move) = self.game_rules.days_per_move {
+            self.game_clocks.move_deadline = Some(at + Duration::days(days_per_move as i64));
+        }
+
+        Ok(())
     }
 
     fn is_game_over(&self) -> bool {
         self.chess.is_game_over() ||
-            self.game_clocks.w == 0 ||
-            self.game_clocks.b == 0 ||
+            self.is_flagged() ||
             self.chess.fullmoves().get() as u64 >= MAX_MOVES ||
             self.resigned_color.is_some()
     }
@@ -518,6 +737,7 @@ impl ChessGame {
         };
 
         self.resigned_color = resigned_color;
+        self.seq += 1;
 
         Ok(())
     }
@@ -534,6 +754,210 @@ pub struct CreateGameDto {
     pub account_id1: String,
     pub game_type: shared::primitives::GameType,
     pub metadata: String,
+    pub ranked: bool,
+    /// Fixed white player, one of the two accounts. Colors are random when unset.
+    pub white_account_id: Option<String>,
+}
+
+/// Color the challenger plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChallengeColor {
+    White,
+    Black,
+    Random,
+}
+
+impl ChallengeColor {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ChallengeColor::White => "white",
+            ChallengeColor::Black => "black",
+            ChallengeColor::Random => "random",
+        }
+    }
+}
+
+impl FromStr for ChallengeColor {
+    type Err = GameServiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "white" => Ok(ChallengeColor::White),
+            "black" => Ok(ChallengeColor::Black),
+            "random" | "" => Ok(ChallengeColor::Random),
+            _ => Err(GameServiceError::InvalidArgumentError(format!("Unknown color: {}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChallengeStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Cancelled,
+    Expired,
+}
+
+impl ChallengeStatus {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ChallengeStatus::Pending => "pending",
+            ChallengeStatus::Accepted => "accepted",
+            ChallengeStatus::Declined => "declined",
+            ChallengeStatus::Cancelled => "cancelled",
+            ChallengeStatus::Expired => "expired",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Challenge {
+    pub id: String,
+    pub challenger_account_id: String,
+    /// Unset for open challenges, which anyone holding the invite link can accept
+    pub challenged_account_id: Option<String>,
+    pub game_type: GameType,
+    pub color: ChallengeColor,
+    pub ranked: bool,
+    pub status: ChallengeStatus,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_by: Option<String>,
+    pub game_id: Option<String>,
+}
+
+impl Challenge {
+    pub fn new(
+        challenger_account_id: String,
+        challenged_account_id: Option<String>,
+        game_type: GameType,
+        color: ChallengeColor,
+        ranked: bool,
+    ) -> Self {
+        let created_at = Utc::now();
+        // Correspondence players don't wait at the board, so their challenges stay open longer
+        let ttl = if get_game_rules(game_type).is_correspondence() {
+            Duration::days(7)
+        } else {
+            Duration::minutes(30)
+        };
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            challenger_account_id,
+            challenged_account_id,
+            game_type,
+            color,
+            ranked,
+            status: ChallengeStatus::Pending,
+            created_at,
+            expires_at: created_at + ttl,
+            accepted_by: None,
+            game_id: None,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.status == ChallengeStatus::Expired ||
+            (self.status == ChallengeStatus::Pending && Utc::now() >= self.expires_at)
+    }
+
+    /// White player of the game once `opponent_account_id` accepts, `None` for random colors.
+    pub fn white_account_id(&self, opponent_account_id: &str) -> Option<String> {
+        match self.color {
+            ChallengeColor::White => Some(self.challenger_account_id.clone()),
+            ChallengeColor::Black => Some(opponent_account_id.to_owned()),
+            ChallengeColor::Random => None,
+        }
+    }
+}
+
+impl From<Challenge> for shared::Challenge {
+    fn from(challenge: Challenge) -> Self {
+        Self {
+            id: challenge.id,
+            challenger_account_id: challenge.challenger_account_id,
+            challenged_account_id: challenge.challenged_account_id,
+            game_type: challenge.game_type.to_str().to_owned(),
+            color: challenge.color.to_str().to_owned(),
+            ranked: challenge.ranked,
+            status: challenge.status.to_str().to_owned(),
+            expires_at: challenge.expires_at.timestamp_millis(),
+            game_id: challenge.game_id,
+        }
+    }
+}
+
+/// Rematch window of a game that just ended.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rematch {
+    pub game_id: String,
+    pub account_ids: AccountIds,
+    pub game_type: GameType,
+    pub ranked: bool,
+    pub expires_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub offered_by: Option<String>,
+    #[serde(skip)]
+    pub new_game_id: Option<String>,
+}
+
+impl Rematch {
+    pub fn new(chess_game: &ChessGame) -> Self {
+        Self {
+            game_id: chess_game.id.clone(),
+            account_ids: chess_game.account_ids.clone(),
+            game_type: chess_game.game_type,
+            ranked: chess_game.ranked,
+            expires_at: Utc::now() + Duration::seconds(REMATCH_WINDOW_SECONDS),
+            offered_by: None,
+            new_game_id: None,
+        }
+    }
+
+    pub fn is_player(&self, account_id: &str) -> bool {
+        self.account_ids.w == account_id || self.account_ids.b == account_id
+    }
+
+    pub fn opponent_account_id(&self, account_id: &str) -> &str {
+        if self.account_ids.w == account_id {
+            &self.account_ids.b
+        } else {
+            &self.account_ids.w
+        }
+    }
+
+    /// Colors are swapped for the rematch.
+    pub fn white_account_id(&self) -> &str {
+        &self.account_ids.b
+    }
+}
+
+pub struct CreateChallengeDto {
+    pub account_id: String,
+    pub challenged_account_id: Option<String>,
+    pub game_type: GameType,
+    pub color: ChallengeColor,
+    pub ranked: bool,
+}
+
+pub struct MakeMoveDto {
+    pub game_id: String,
+    pub account_id: String,
+    pub game_move: String,
+    pub client_move_id: Option<String>,
+    pub expected_seq: Option<u64>,
+}
+
+pub struct MakeMoveResult {
+    pub chess_game: ChessGame,
+    /// Seq of the game once the move was applied
+    pub seq: u64,
+    /// The move was a retry of an already applied move
+    pub duplicate: bool,
 }
 
 fn minutes(minutes: u64) -> u64 {
@@ -544,6 +968,10 @@ fn seconds(seconds: u64) -> u64 {
     seconds * 1000
 }
 
+fn days(days: u64) -> u64 {
+    days * 24 * 60 * 60 * 1000
+}
+
 pub fn get_game_rules(game_type: GameType) -> GameRules {
     match game_type {
         GameType::Rapid10_0 => GameRules::new(
@@ -570,5 +998,132 @@ pub fn get_game_rules(game_type: GameType) -> GameRules {
             minutes(1),
             0
         ),
+        GameType::Correspondence1d => GameRules::correspondence(1),
+        GameType::Correspondence3d => GameRules::correspondence(3),
+        GameType::Correspondence7d => GameRules::correspondence(7),
+        GameType::Correspondence14d => GameRules::correspondence(14),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_game(game_type: GameType) -> ChessGame {
+        ChessGame::from_scratch(
+            game_type,
+            AccountIds { w: "white".to_string(), b: "black".to_string() },
+            "".to_string(),
+            get_game_rules(game_type)
+        )
+    }
+
+    #[test]
+    fn test_moves_survive_serialization() {
+        let mut game = new_game(GameType::Blitz5_0);
+        game.make_move("white", "e4").unwrap();
+        game.make_move("black", "e5").unwrap();
+
+        let restored = ChessGame::from_string(&game.to_string()).unwrap();
+
+        let moves: Vec<String> = restored.moves.iter().map(|m| m.to_string()).collect();
+        assert_eq!(moves, vec!["e2e4", "e7e5"]);
+        assert_eq!(restored.seq(), 2);
+        assert_eq!(restored.turn_account_id(), "white");
+    }
+
+    #[test]
+    fn test_correspondence_move_resets_deadline() {
+        let mut game = new_game(GameType::Correspondence3d);
+        game.game_clocks.move_deadline = Some(Utc::now() - Duration::hours(1));
+        assert!(game.make_move("white", "e4").is_err());
+
+        game.game_clocks.move_deadline = Some(Utc::now() + Duration::hours(1));
+        game.make_move("white", "e4").unwrap();
+
+        let deadline = game.game_clocks.move_deadline.unwrap();
+        assert!(deadline > Utc::now() + Duration::days(2));
+        assert_eq!(game.turn_account_id(), "black");
+    }
+
+    #[test]
+    fn test_correspondence_timeout() {
+        let mut game = new_game(GameType::Correspondence1d);
+        game.game_clocks.move_deadline = Some(Utc::now() - Duration::seconds(1));
+
+        let result = game.check_game_result().unwrap().unwrap();
+
+        assert_eq!(result.outcome, GameOutcome::Black);
+        assert_eq!(result.winner_account_id.as_deref(), Some("black"));
+    }
+
+    #[test]
+    fn test_abandoned_account_id() {
+        let mut game = new_game(GameType::Blitz5_0);
+        game.make_move("white", "e4").unwrap();
+
+        // Black flags without having moved
+        game.game_clocks.b = 0;
+        let result = game.check_game_result().unwrap().unwrap();
+        assert_eq!(game.abandoned_account_id(&result), Some("black"));
+
+        let mut game = new_game(GameType::Blitz5_0);
+        game.make_move("white", "e4").unwrap();
+        game.make_move("black", "e5").unwrap();
+
+        // White flags after having moved
+        game.game_clocks.w = 0;
+        let result = game.check_game_result().unwrap().unwrap();
+        assert_eq!(game.abandoned_account_id(&result), None);
+    }
+
+    #[test]
+    fn test_client_moves_are_remembered_per_side() {
+        let mut game = new_game(GameType::Blitz5_0);
+        game.make_move("white", "e4").unwrap();
+        game.record_client_move("white", "move-1");
+        game.make_move("black", "e5").unwrap();
+        game.record_client_move("black", "move-1");
+
+        game.make_move("white", "Nf3").unwrap();
+        game.record_client_move("white", "move-2");
+
+        // A late retry of an older move is still recognised after later moves
+        assert_eq!(game.find_client_move("white", "move-1"), Some(1));
+        assert_eq!(game.find_client_move("white", "move-2"), Some(3));
+        assert_eq!(game.find_client_move("black", "move-1"), Some(2));
+        assert_eq!(game.find_client_move("white", "move-3"), None);
+        assert_eq!(game.client_move_id_at("white", 3), Some("move-2"));
+
+        assert_eq!(serde_json::from_str::<ClientMoves>(&serde_json::to_string(&game.client_moves).unwrap()).unwrap(), game.client_moves);
+    }
+
+    #[test]
+    fn test_challenge_colors() {
+        let challenge = |color: &str| Challenge::new(
+            "challenger".to_string(),
+            None,
+            GameType::Rapid10_0,
+            ChallengeColor::from_str(color).unwrap(),
+            false,
+        );
+
+        assert_eq!(challenge("white").white_account_id("opponent").as_deref(), Some("challenger"));
+        assert_eq!(challenge("black").white_account_id("opponent").as_deref(), Some("opponent"));
+        assert_eq!(challenge("").white_account_id("opponent"), None);
+        assert!(ChallengeColor::from_str("purple").is_err());
+    }
+
+    #[test]
+    fn test_rematch_swaps_colors() {
+        let mut game = new_game(GameType::Blitz3_2);
+        game.ranked = true;
+
+        let rematch = Rematch::new(&game);
+
+        assert_eq!(rematch.white_account_id(), "black");
+        assert_eq!(rematch.opponent_account_id("black"), "white");
+        assert!(rematch.ranked);
+        assert!(!rematch.is_player("spectator"));
     }
 }
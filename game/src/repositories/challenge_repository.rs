@@ -0,0 +1,194 @@
+use redis::AsyncCommands;
+
+use crate::{error::GameServiceError, primitives::{Challenge, ChallengeStatus}, repositories::redis_keys, state::state::AppState};
+
+const UPDATE_CHALLENGE_SCRIPT: &str = include_str!("lua-scripts/update-challenge.lua");
+
+/// Sorted set of pending challenges, scored by their expiry (ms).
+const CHALLENGE_EXPIRIES_KEY: &str = "game:challenges:expiries";
+
+/// How long a challenge stays readable once it is no longer pending, e.g. for its invite link.
+const SETTLED_CHALLENGE_TTL_SECONDS: i64 = 86400;
+
+fn get_challenge_key(challenge_id: &str) -> String {
+    format!("game:challenge:{}", challenge_id)
+}
+
+fn get_account_challenges_key(account_id: &str) -> String {
+    format!("game:account:{}:challenges", account_id)
+}
+
+fn challenge_ttl_seconds(challenge: &Challenge) -> i64 {
+    (challenge.expires_at - chrono::Utc::now()).num_seconds().max(0) + SETTLED_CHALLENGE_TTL_SECONDS
+}
+
+pub async fn store_challenge(
+    state: &AppState,
+    challenge: &Challenge,
+) -> Result<(), GameServiceError> {
+    let [challenge_key] = store_challenge_keys(&challenge.id);
+    redis_keys::check_same_slot(&[&challenge_key])?;
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let _: () = redis::pipe()
+        .atomic()
+        .hset(&challenge_key, "challengeRepr", serde_json::to_string(challenge)?)
+        .hset(&challenge_key, "status", challenge.status.to_str())
+        .expire(&challenge_key, challenge_ttl_seconds(challenge))
+        .query_async(&mut connection)
+        .await?;
+
+    add_pending_challenge(&mut connection, challenge).await
+}
+
+/// Keys of the transaction storing a challenge.
+pub fn store_challenge_keys(challenge_id: &str) -> [String; 1] {
+    [get_challenge_key(challenge_id)]
+}
+
+/// Keys of update-challenge.lua.
+pub fn update_challenge_keys(challenge_id: &str) -> [String; 1] {
+    [get_challenge_key(challenge_id)]
+}
+
+/// Lists a pending challenge and schedules its expiry. Each key is written on its own, since
+/// they are in different cluster slots.
+async fn add_pending_challenge(
+    connection: &mut redis::aio::MultiplexedConnection,
+    challenge: &Challenge,
+) -> Result<(), GameServiceError> {
+    let mut pipeline = redis::pipe();
+    pipeline
+        .zadd(CHALLENGE_EXPIRIES_KEY, &challenge.id, challenge.expires_at.timestamp_millis())
+        .sadd(get_account_challenges_key(&challenge.challenger_account_id), &challenge.id);
+
+    if let Some(challenged_account_id) = &challenge.challenged_account_id {
+        pipeline.sadd(get_account_challenges_key(challenged_account_id), &challenge.id);
+    }
+
+    let _: () = pipeline
+        .query_async(connection)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn find_challenge(
+    state: &AppState,
+    challenge_id: &str,
+) -> Result<Option<Challenge>, GameServiceError> {
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let challenge_repr: Option<String> = connection
+        .hget(get_challenge_key(challenge_id), "challengeRepr")
+        .await?;
+
+    match challenge_repr {
+        Some(repr) => Ok(Some(serde_json::from_str(&repr)?)),
+        None => Ok(None),
+    }
+}
+
+/// Moves a challenge out of `expected_status`, failing if another request got there first.
+/// Leaving `pending` takes it off the pending lists, going back to it puts it on them again.
+pub async fn update_challenge(
+    state: &AppState,
+    challenge: &Challenge,
+    expected_status: ChallengeStatus,
+) -> Result<(), GameServiceError> {
+    let challenge_key = get_challenge_key(&challenge.id);
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let script = redis::Script::new(UPDATE_CHALLENGE_SCRIPT);
+    let result: i32 = redis_keys::prepare_invoke(&script, &update_challenge_keys(&challenge.id))?
+        .arg(expected_status.to_str())
+        .arg(challenge.status.to_str())
+        .arg(serde_json::to_string(challenge)?)
+        .invoke_async(&mut connection)
+        .await?;
+
+    if result != 1 {
+        return Err(GameServiceError::ChallengeError(
+            format!("Challenge {} is no longer {}", challenge.id, expected_status.to_str())
+        ));
+    }
+
+    // The lists are updated after the status, their readers skip challenges that aren't pending
+    if expected_status == ChallengeStatus::Pending {
+        let mut pipeline = redis::pipe();
+        pipeline
+            .expire(&challenge_key, SETTLED_CHALLENGE_TTL_SECONDS)
+            .zrem(CHALLENGE_EXPIRIES_KEY, &challenge.id)
+            .srem(get_account_challenges_key(&challenge.challenger_account_id), &challenge.id);
+
+        if let Some(challenged_account_id) = &challenge.challenged_account_id {
+            pipeline.srem(get_account_challenges_key(challenged_account_id), &challenge.id);
+        }
+
+        let _: () = pipeline
+            .query_async(&mut connection)
+            .await?;
+    } else if challenge.status == ChallengeStatus::Pending {
+        // A challenge released after a failed accept is listed and expires again
+        let _: () = connection
+            .expire(&challenge_key, challenge_ttl_seconds(challenge))
+            .await?;
+
+        add_pending_challenge(&mut connection, challenge).await?;
+    }
+
+    Ok(())
+}
+
+/// Ids of the pending challenges sent or received by an account.
+pub async fn find_account_challenge_ids(
+    state: &AppState,
+    account_id: &str,
+) -> Result<Vec<String>, GameServiceError> {
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let challenge_ids: Vec<String> = connection
+        .smembers(get_account_challenges_key(account_id))
+        .await?;
+
+    Ok(challenge_ids)
+}
+
+pub async fn remove_account_challenge_id(
+    state: &AppState,
+    account_id: &str,
+    challenge_id: &str,
+) -> Result<(), GameServiceError> {
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let _: () = connection
+        .srem(get_account_challenges_key(account_id), challenge_id)
+        .await?;
+
+    Ok(())
+}
+
+/// Ids of pending challenges whose expiry has passed.
+pub async fn find_expired_challenge_ids(
+    state: &AppState,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<String>, GameServiceError> {
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let challenge_ids: Vec<String> = connection
+        .zrangebyscore(CHALLENGE_EXPIRIES_KEY, "-inf", now.timestamp_millis())
+        .await?;
+
+    Ok(challenge_ids)
+}
+
+pub async fn remove_challenge_expiry(
+    state: &AppState,
+    challenge_id: &str,
+) -> Result<(), GameServiceError> {
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let _: () = connection.zrem(CHALLENGE_EXPIRIES_KEY, challenge_id).await?;
+
+    Ok(())
+}
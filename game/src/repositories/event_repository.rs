@@ -0,0 +1,100 @@
+use async_nats::jetstream::{self, consumer::DeliverPolicy, context::PublishErrorKind, message::PublishMessage, stream::LastRawMessageErrorKind};
+use futures::StreamExt;
+
+use crate::{error::GameServiceError, events::GameStateEvent, state::state::AppState};
+
+/// Stream of every `chessmart.game.>` subject, shared with the gateway's game event listener.
+pub const GAME_STREAM_NAME: &str = "game-publisher";
+
+pub async fn ensure_stream(state: &AppState) -> Result<(), GameServiceError> {
+    state
+        .jetstream
+        .get_or_create_stream(jetstream::stream::Config {
+            name: GAME_STREAM_NAME.to_string(),
+            subjects: vec!["chessmart.game.>".into()],
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| GameServiceError::EventStoreError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Appends an event to the game's subject, provided the last event on the subject is still
+/// `expected_event_seq` (0 when the game has no events yet). Returns the new event's sequence.
+pub async fn append_event(
+    state: &AppState,
+    game_id: &str,
+    expected_event_seq: u64,
+    event: &GameStateEvent,
+) -> Result<u64, GameServiceError> {
+    let payload = serde_json::to_vec(event)?;
+
+    let ack = state
+        .jetstream
+        .send_publish(
+            GameStateEvent::subject(game_id),
+            PublishMessage::build()
+                .payload(payload.into())
+                .expected_last_subject_sequence(expected_event_seq),
+        )
+        .await
+        .map_err(|e| GameServiceError::EventStoreError(e.to_string()))?
+        .await
+        .map_err(|e| match e.kind() {
+            PublishErrorKind::WrongLastSequence => GameServiceError::ConcurrentMoveError(
+                format!("Game {} was updated concurrently", game_id)
+            ),
+            _ => GameServiceError::EventStoreError(e.to_string()),
+        })?;
+
+    Ok(ack.sequence)
+}
+
+/// Every event of a game in order, with the sequence of the last one.
+pub async fn load_events(
+    state: &AppState,
+    game_id: &str,
+) -> Result<(Vec<GameStateEvent>, u64), GameServiceError> {
+    let subject = GameStateEvent::subject(game_id);
+
+    let stream = state
+        .jetstream
+        .get_stream(GAME_STREAM_NAME)
+        .await
+        .map_err(|e| GameServiceError::EventStoreError(e.to_string()))?;
+
+    let last_event_seq = match stream.get_last_raw_message_by_subject(&subject).await {
+        Ok(message) => message.sequence,
+        Err(e) if e.kind() == LastRawMessageErrorKind::NoMessageFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(GameServiceError::EventStoreError(e.to_string())),
+    };
+
+    let consumer = stream
+        .create_consumer(jetstream::consumer::pull::OrderedConfig {
+            filter_subject: subject,
+            deliver_policy: DeliverPolicy::All,
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| GameServiceError::EventStoreError(e.to_string()))?;
+
+    let mut messages = consumer
+        .messages()
+        .await
+        .map_err(|e| GameServiceError::EventStoreError(e.to_string()))?;
+
+    let mut events = Vec::new();
+
+    while let Some(message) = messages.next().await {
+        let message = message.map_err(|e| GameServiceError::EventStoreError(e.to_string()))?;
+        events.push(serde_json::from_slice::<GameStateEvent>(&message.payload)?);
+
+        let info = message.info().map_err(|e| GameServiceError::EventStoreError(e.to_string()))?;
+        if info.stream_sequence >= last_event_seq {
+            break;
+        }
+    }
+
+    Ok((events, last_event_seq))
+}
@@ -1,31 +1,89 @@
 use redis::AsyncCommands;
 
-use crate::{error::GameServiceError, primitives::ChessGame, state::state::AppState};
+use crate::{error::GameServiceError, primitives::ChessGame, repositories::redis_keys, snapshot, state::state::AppState};
 
 const UPDATE_GAME_SCRIPT: &str = include_str!("lua-scripts/update-game.lua");
 
+/// Sorted set of ongoing correspondence games, scored by the current move deadline (ms).
+const CORRESPONDENCE_DEADLINES_KEY: &str = "game:correspondence:deadlines";
+
 pub async fn store_game(
     state: &AppState,
     chess_game: &ChessGame
 ) -> Result<(), GameServiceError> {
-    let game_key = get_game_key(chess_game.id.as_str());
+    let [game_key] = store_game_keys(chess_game.id.as_str());
+    redis_keys::check_same_slot(&[&game_key])?;
     let mut connection = state.redis.get_multiplexed_async_connection().await?;
 
-    let _: () = redis::pipe()
+    // Indexed before it is stored, readers skip ids of games that don't exist
+    if chess_game.game_rules.is_correspondence() {
+        let mut pipeline = redis::pipe();
+        pipeline
+            .sadd(get_account_correspondence_games_key(&chess_game.account_ids.w), &chess_game.id)
+            .sadd(get_account_correspondence_games_key(&chess_game.account_ids.b), &chess_game.id);
+        add_move_deadline(&mut pipeline, chess_game);
+
+        let _: () = pipeline
+            .query_async(&mut connection)
+            .await?;
+    }
+
+    let mut pipeline = redis::pipe();
+    pipeline
         .atomic()
-        .hset(&game_key, "gameRepr", chess_game.to_string())
-        .hset(&game_key, "seq", chess_game.seq())
-        .expire(&game_key, 86400)
+        .hset(&game_key, "gameRepr", encode_game(state, chess_game))
+        .hset(&game_key, "seq", chess_game.seq());
+
+    if let Some(event_seq) = chess_game.event_seq {
+        pipeline.hset(&game_key, "eventSeq", event_seq);
+    }
+
+    if chess_game.game_rules.is_correspondence() {
+        // Correspondence games can last for months, so they are never expired
+        pipeline.persist(&game_key);
+    } else {
+        pipeline.expire(&game_key, 86400);
+    }
+
+    let _: () = pipeline
         .query_async(&mut connection)
         .await?;
 
     Ok(())
 }
 
+/// Keys of the transaction storing a game.
+pub fn store_game_keys(game_id: &str) -> [String; 1] {
+    [get_game_key(game_id)]
+}
+
+/// Keys of update-game.lua.
+pub fn update_game_keys(game_id: &str) -> [String; 1] {
+    [get_game_key(game_id)]
+}
+
+fn encode_game(state: &AppState, chess_game: &ChessGame) -> Vec<u8> {
+    snapshot::encode(chess_game, state.config.storage.snapshot_format)
+}
+
 fn get_game_key(game_id: &str) -> String {
     format!("game:chess-game:{}:status", game_id)
 }
 
+fn get_account_correspondence_games_key(account_id: &str) -> String {
+    format!("game:account:{}:correspondence-games", account_id)
+}
+
+fn add_move_deadline(pipeline: &mut redis::Pipeline, chess_game: &ChessGame) {
+    if let Some(move_deadline) = chess_game.game_clocks.move_deadline {
+        pipeline.zadd(
+            CORRESPONDENCE_DEADLINES_KEY,
+            &chess_game.id,
+            move_deadline.timestamp_millis()
+        );
+    }
+}
+
 pub async fn find_game(
     state: &AppState,
     game_id: &str
@@ -34,13 +92,14 @@ pub async fn find_game(
 
     let mut connection = state.redis.get_multiplexed_async_connection().await?;
 
-    let game_repr: Option<String> = connection.hget(&game_key, "gameRepr").await?;
+    let (game_repr, event_seq): (Option<Vec<u8>>, Option<u64>) = connection
+        .hget(&game_key, &["gameRepr", "eventSeq"])
+        .await?;
 
     match game_repr {
         Some(repr) => {
-            // let game = ChessGame::from_string(&repr)
-            //     .map_err(|e| GameServiceError::ParseJsonError(e.to_string()))?;
-            let game = ChessGame::from_string(&repr)?;
+            let mut game = snapshot::decode(&repr)?;
+            game.event_seq = event_seq;
 
             Ok(Some(game))
         }
@@ -48,38 +107,101 @@ pub async fn find_game(
     }
 }
 
-pub async fn delete_game(
+/// Drops the cached state of an event-sourced game, the next read rebuilds it from its events.
+pub async fn evict_game(
     state: &AppState,
     game_id: &str
 ) -> Result<(), GameServiceError> {
-    let game_key = get_game_key(game_id);
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let _: () = connection.del(get_game_key(game_id)).await?;
+
+    Ok(())
+}
+
+pub async fn delete_game(
+    state: &AppState,
+    chess_game: &ChessGame
+) -> Result<(), GameServiceError> {
+    let game_key = get_game_key(&chess_game.id);
+
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    // Deleted before its index entries, which are skipped or dropped by their readers if this
+    // stops halfway
+    let _: () = redis::pipe()
+        .del(&game_key)
+        .zrem(CORRESPONDENCE_DEADLINES_KEY, &chess_game.id)
+        .srem(get_account_correspondence_games_key(&chess_game.account_ids.w), &chess_game.id)
+        .srem(get_account_correspondence_games_key(&chess_game.account_ids.b), &chess_game.id)
+        .query_async(&mut connection)
+        .await?;
 
+    Ok(())
+}
+
+/// Ids of correspondence games whose side to move has passed its deadline.
+pub async fn find_overdue_correspondence_game_ids(
+    state: &AppState,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<String>, GameServiceError> {
     let mut connection = state.redis.get_multiplexed_async_connection().await?;
 
-    let result: () = connection.del(&game_key).await?;
+    let game_ids: Vec<String> = connection
+        .zrangebyscore(CORRESPONDENCE_DEADLINES_KEY, "-inf", now.timestamp_millis())
+        .await?;
 
-    Ok(result)
+    Ok(game_ids)
+}
+
+/// Drops a game from the deadline index, e.g. when its state has already expired.
+pub async fn remove_correspondence_deadline(
+    state: &AppState,
+    game_id: &str,
+) -> Result<(), GameServiceError> {
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let _: () = connection.zrem(CORRESPONDENCE_DEADLINES_KEY, game_id).await?;
+
+    Ok(())
+}
+
+pub async fn find_account_correspondence_game_ids(
+    state: &AppState,
+    account_id: &str,
+) -> Result<Vec<String>, GameServiceError> {
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let game_ids: Vec<String> = connection
+        .smembers(get_account_correspondence_games_key(account_id))
+        .await?;
+
+    Ok(game_ids)
 }
 
 pub async fn update_game(
     state: &AppState,
     chess_game: &ChessGame
 ) -> Result<(), GameServiceError> {
-    let game_key = get_game_key(chess_game.id.as_str());
-
     let mut connection = state.redis.get_multiplexed_async_connection().await?;
 
-    let game_state = chess_game.to_string();
+    let game_state = encode_game(state, chess_game);
 
     let script = redis::Script::new(UPDATE_GAME_SCRIPT);
-    let result: i32 = script
-        .key(&game_key)
+    let result: i32 = redis_keys::prepare_invoke(&script, &update_game_keys(&chess_game.id))?
         .arg(&game_state)
         .arg(chess_game.seq())
+        .arg(chess_game.event_seq.map(|event_seq| event_seq.to_string()).unwrap_or_default())
         .invoke_async(&mut connection)
         .await
         .map_err(|e| GameServiceError::RedisError(e))?;
 
+    if result == 1 && chess_game.game_rules.is_correspondence() {
+        let mut pipeline = redis::pipe();
+        add_move_deadline(&mut pipeline, chess_game);
+        let _: () = pipeline.query_async(&mut connection).await?;
+    }
+
     match result {
         1 => Ok(()),
         0 => Err(GameServiceError::ConcurrentMoveError(
@@ -0,0 +1,24 @@
+--[[
+  Clear a field of a rematch window if it still holds the given value, e.g. to release an accepted
+  rematch whose game couldn't be created.
+
+  Input:
+    KEYS[1] rematch key
+
+    ARGV[1] field name
+    ARGV[2] expected field value
+
+  Output:
+    0 - field missing or holding another value
+    1 - field correctly cleared
+]]
+
+local rematchKey = KEYS[1]
+local field = ARGV[1]
+local value = ARGV[2]
+
+if redis.call('HGET', rematchKey, field) ~= value then
+  return 0
+end
+
+return redis.call('HDEL', rematchKey, field)
@@ -0,0 +1,25 @@
+--[[
+  Set a field of a rematch window once, e.g. who offered the rematch or who accepted it.
+  Prevents a rematch from being accepted twice, or written to after its window has closed.
+
+  Input:
+    KEYS[1] rematch key
+
+    ARGV[1] field name
+    ARGV[2] field value
+
+  Output:
+    -1 - rematch window closed (key missing)
+     0 - field already set
+     1 - field correctly set
+]]
+
+local rematchKey = KEYS[1]
+local field = ARGV[1]
+local value = ARGV[2]
+
+if redis.call('EXISTS', rematchKey) == 0 then
+  return -1
+end
+
+return redis.call('HSETNX', rematchKey, field, value)
@@ -0,0 +1,29 @@
+--[[
+  Move a challenge out of the given status, performing an atomic Check-And-Set operation.
+  Prevents a challenge from being e.g. accepted by two players, or accepted after it was cancelled.
+
+  Input:
+    KEYS[1] challenge key
+
+    ARGV[1] expected current status
+    ARGV[2] new status
+    ARGV[3] new challenge representation
+
+  Output:
+    0 - challenge missing or not in the expected status
+    1 - challenge correctly updated
+]]
+
+local challengeKey = KEYS[1]
+local expectedStatus = ARGV[1]
+local newStatus = ARGV[2]
+local newChallengeRepr = ARGV[3]
+
+local status = redis.call('HGET', challengeKey, 'status')
+
+if status ~= expectedStatus then
+  return 0
+end
+
+redis.call('HSET', challengeKey, 'status', newStatus, 'challengeRepr', newChallengeRepr)
+return 1
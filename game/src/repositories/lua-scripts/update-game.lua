@@ -7,6 +7,7 @@
 
     ARGV[1] new game state representation
     ARGV[2] sequence number
+    ARGV[3] sequence of the last game event, empty for games not backed by events
 
   Output:
     0 - unexpected sequence number
@@ -16,6 +17,7 @@
 local gameKey = KEYS[1]
 local newGameRepr = ARGV[1]
 local seq = ARGV[2]
+local eventSeq = ARGV[3]
 
 local oldSeq = redis.call('HGET', gameKey, 'seq')
 
@@ -24,4 +26,7 @@ if tonumber(seq) ~= tonumber(oldSeq) + 1 then
 end
 
 redis.call('HSET', gameKey, 'gameRepr', newGameRepr, 'seq', seq)
+if eventSeq ~= nil and eventSeq ~= '' then
+  redis.call('HSET', gameKey, 'eventSeq', eventSeq)
+end
 return 1
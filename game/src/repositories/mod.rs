@@ -1 +1,5 @@
+pub mod challenge_repository;
+pub mod event_repository;
 pub mod game_repository;
+pub mod redis_keys;
+pub mod rematch_repository;
@@ -0,0 +1,35 @@
+//! Key layout of the game state, for Redis Cluster.
+//!
+//! Redis Cluster only runs a script or a transaction if all of its keys hash to one slot. Games,
+//! challenges and rematches are each kept under a single key, and every script and transaction
+//! only touches that key, so they spread over the cluster:
+//!
+//! - The indexes pointing at them (correspondence deadlines, challenge expiries and the
+//!   correspondence games and challenges of an account) are written with separate commands. An
+//!   index can briefly list a game or challenge that is gone or no longer pending, and every
+//!   reader skips or drops those entries.
+//! - A game is indexed before it is stored, so it can't lose its move deadline. Challenges are
+//!   stored first, their expiry is checked on every read.
+//!
+//! Every script and transaction goes through `check_same_slot`, which enforces the invariant when
+//! key slot checks are on.
+
+pub use shared::redis_keys::{check_same_slot, prepare_invoke, set_key_slot_checks};
+
+#[cfg(test)]
+mod tests {
+    use super::{check_same_slot, set_key_slot_checks};
+    use crate::repositories::{challenge_repository, game_repository, rematch_repository};
+
+    #[test]
+    fn test_script_keys_share_a_slot() {
+        set_key_slot_checks(true);
+
+        check_same_slot(&game_repository::store_game_keys("game")).unwrap();
+        check_same_slot(&game_repository::update_game_keys("game")).unwrap();
+        check_same_slot(&challenge_repository::store_challenge_keys("challenge")).unwrap();
+        check_same_slot(&challenge_repository::update_challenge_keys("challenge")).unwrap();
+        check_same_slot(&rematch_repository::store_rematch_keys("game")).unwrap();
+        check_same_slot(&rematch_repository::rematch_field_keys("game")).unwrap();
+    }
+}
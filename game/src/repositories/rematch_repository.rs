@@ -0,0 +1,128 @@
+use redis::AsyncCommands;
+
+use crate::{error::GameServiceError, primitives::Rematch, repositories::redis_keys, state::state::AppState};
+
+const SET_REMATCH_FIELD_SCRIPT: &str = include_str!("lua-scripts/set-rematch-field.lua");
+const CLEAR_REMATCH_FIELD_SCRIPT: &str = include_str!("lua-scripts/clear-rematch-field.lua");
+
+fn get_rematch_key(game_id: &str) -> String {
+    format!("game:rematch:{}", game_id)
+}
+
+/// Opens the rematch window of a game, the key expires when the window closes.
+pub async fn store_rematch(
+    state: &AppState,
+    rematch: &Rematch,
+) -> Result<(), GameServiceError> {
+    let [rematch_key] = store_rematch_keys(&rematch.game_id);
+    redis_keys::check_same_slot(&[&rematch_key])?;
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let _: () = redis::pipe()
+        .atomic()
+        .hset(&rematch_key, "rematchRepr", serde_json::to_string(rematch)?)
+        .pexpire_at(&rematch_key, rematch.expires_at.timestamp_millis())
+        .query_async(&mut connection)
+        .await?;
+
+    Ok(())
+}
+
+/// Keys of the transaction opening a rematch window.
+pub fn store_rematch_keys(game_id: &str) -> [String; 1] {
+    [get_rematch_key(game_id)]
+}
+
+/// Keys of set-rematch-field.lua and clear-rematch-field.lua.
+pub fn rematch_field_keys(game_id: &str) -> [String; 1] {
+    [get_rematch_key(game_id)]
+}
+
+pub async fn find_rematch(
+    state: &AppState,
+    game_id: &str,
+) -> Result<Option<Rematch>, GameServiceError> {
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let (rematch_repr, offered_by, new_game_id): (Option<String>, Option<String>, Option<String>) = connection
+        .hget(get_rematch_key(game_id), &["rematchRepr", "offeredBy", "newGameId"])
+        .await?;
+
+    match rematch_repr {
+        Some(repr) => {
+            let mut rematch: Rematch = serde_json::from_str(&repr)?;
+            rematch.offered_by = offered_by;
+            rematch.new_game_id = new_game_id;
+
+            Ok(Some(rematch))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Sets `field` unless it is already set. Returns false if it was.
+async fn set_rematch_field(
+    state: &AppState,
+    game_id: &str,
+    field: &str,
+    value: &str,
+) -> Result<bool, GameServiceError> {
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let script = redis::Script::new(SET_REMATCH_FIELD_SCRIPT);
+    let result: i32 = redis_keys::prepare_invoke(&script, &rematch_field_keys(game_id))?
+        .arg(field)
+        .arg(value)
+        .invoke_async(&mut connection)
+        .await?;
+
+    if result == -1 {
+        return Err(GameServiceError::RematchError(
+            format!("Rematch window of game {} has closed", game_id)
+        ));
+    }
+
+    Ok(result == 1)
+}
+
+pub async fn set_offered_by(
+    state: &AppState,
+    game_id: &str,
+    account_id: &str,
+) -> Result<bool, GameServiceError> {
+    set_rematch_field(state, game_id, "offeredBy", account_id).await
+}
+
+pub async fn set_accepted_by(
+    state: &AppState,
+    game_id: &str,
+    account_id: &str,
+) -> Result<bool, GameServiceError> {
+    set_rematch_field(state, game_id, "acceptedBy", account_id).await
+}
+
+/// Releases a rematch accepted by `account_id`, so it can be accepted again.
+pub async fn clear_accepted_by(
+    state: &AppState,
+    game_id: &str,
+    account_id: &str,
+) -> Result<bool, GameServiceError> {
+    let mut connection = state.redis.get_multiplexed_async_connection().await?;
+
+    let script = redis::Script::new(CLEAR_REMATCH_FIELD_SCRIPT);
+    let result: i32 = redis_keys::prepare_invoke(&script, &rematch_field_keys(game_id))?
+        .arg("acceptedBy")
+        .arg(account_id)
+        .invoke_async(&mut connection)
+        .await?;
+
+    Ok(result == 1)
+}
+
+pub async fn set_new_game_id(
+    state: &AppState,
+    game_id: &str,
+    new_game_id: &str,
+) -> Result<bool, GameServiceError> {
+    set_rematch_field(state, game_id, "newGameId", new_game_id).await
+}
@@ -0,0 +1,264 @@
+use shared::events::ChallengeUpdateEvent;
+use tracing::{debug, error};
+
+use crate::{error::GameServiceError, primitives::{Challenge, ChallengeStatus, ChessGame, CreateChallengeDto, CreateGameDto}, repositories::challenge_repository, services::season_service, state::state::AppState};
+
+pub async fn create_challenge(
+    state: &AppState,
+    payload: CreateChallengeDto,
+) -> Result<Challenge, GameServiceError> {
+    let CreateChallengeDto {
+        account_id,
+        challenged_account_id,
+        game_type,
+        color,
+        ranked,
+    } = payload;
+
+    if challenged_account_id.as_deref() == Some(account_id.as_str()) {
+        return Err(GameServiceError::ChallengeError("Can't challenge yourself".to_string()));
+    }
+
+    if ranked {
+        season_service::ensure_active_season(state).await?;
+    }
+
+    let challenge = Challenge::new(account_id, challenged_account_id, game_type, color, ranked);
+
+    challenge_repository::store_challenge(state, &challenge).await?;
+
+    emit_challenge_update(state, &challenge).await?;
+
+    debug!(
+        "Challenge {} ({}) created by {}, challenged: {}",
+        challenge.id,
+        game_type.to_str(),
+        challenge.challenger_account_id,
+        challenge.challenged_account_id.as_deref().unwrap_or("anyone with the link")
+    );
+
+    Ok(challenge)
+}
+
+pub async fn get_challenge(
+    state: &AppState,
+    challenge_id: &str,
+) -> Result<Challenge, GameServiceError> {
+    let challenge = challenge_repository::find_challenge(state, challenge_id).await?;
+
+    match challenge {
+        Some(mut challenge) => {
+            // The expiry sweep runs periodically, a challenge past its expiry is already expired
+            if challenge.is_expired() {
+                challenge.status = ChallengeStatus::Expired;
+            }
+
+            Ok(challenge)
+        }
+        None => Err(GameServiceError::ChallengeNotFoundError(challenge_id.to_string())),
+    }
+}
+
+/// Pending challenges sent or received by an account, oldest first.
+pub async fn get_challenges(
+    state: &AppState,
+    account_id: &str,
+) -> Result<Vec<Challenge>, GameServiceError> {
+    let challenge_ids = challenge_repository::find_account_challenge_ids(state, account_id).await?;
+
+    let mut challenges = Vec::new();
+
+    for challenge_id in challenge_ids {
+        match challenge_repository::find_challenge(state, &challenge_id).await? {
+            Some(challenge) if challenge.status == ChallengeStatus::Pending && !challenge.is_expired() => {
+                challenges.push(challenge);
+            }
+            Some(_) => {}
+            None => {
+                challenge_repository::remove_account_challenge_id(state, account_id, &challenge_id).await?;
+            }
+        }
+    }
+
+    challenges.sort_by_key(|challenge| challenge.created_at);
+
+    Ok(challenges)
+}
+
+/// Accepts a challenge and creates its game, with the colors the challenger asked for.
+pub async fn accept_challenge(
+    state: &AppState,
+    account_id: &str,
+    challenge_id: &str,
+) -> Result<(Challenge, ChessGame), GameServiceError> {
+    let mut challenge = get_pending_challenge(state, challenge_id).await?;
+
+    if challenge.challenger_account_id == account_id {
+        return Err(GameServiceError::ChallengeError("Can't accept your own challenge".to_string()));
+    }
+
+    if challenge.challenged_account_id.as_deref().is_some_and(|challenged| challenged != account_id) {
+        return Err(GameServiceError::ChallengeError(
+            format!("Challenge {} is addressed to another player", challenge_id)
+        ));
+    }
+
+    // The season may have ended since the challenge was created
+    if challenge.ranked {
+        season_service::ensure_active_season(state).await?;
+    }
+
+    challenge.status = ChallengeStatus::Accepted;
+    challenge.accepted_by = Some(account_id.to_owned());
+    challenge_repository::update_challenge(state, &challenge, ChallengeStatus::Pending).await?;
+
+    let created = crate::services::game_service::create_game(
+        state,
+        CreateGameDto {
+            account_id0: challenge.challenger_account_id.clone(),
+            account_id1: account_id.to_owned(),
+            game_type: challenge.game_type,
+            metadata: serde_json::json!({ "challengeId": challenge.id }).to_string(),
+            ranked: challenge.ranked,
+            white_account_id: challenge.white_account_id(account_id),
+        }
+    ).await;
+
+    let chess_game = match created {
+        Ok(chess_game) => chess_game,
+        Err(e) => {
+            // Nobody can accept an accepted challenge, so it is released for another try
+            challenge.status = ChallengeStatus::Pending;
+            challenge.accepted_by = None;
+
+            if let Err(release_error) = challenge_repository::update_challenge(state, &challenge, ChallengeStatus::Accepted).await {
+                error!("Failed to release challenge {} after its game wasn't created: {}", challenge.id, release_error);
+            }
+
+            return Err(e);
+        }
+    };
+
+    challenge.game_id = Some(chess_game.id.clone());
+    challenge_repository::update_challenge(state, &challenge, ChallengeStatus::Accepted).await?;
+
+    emit_challenge_update(state, &challenge).await?;
+
+    debug!("Challenge {} accepted by {}, game {}", challenge.id, account_id, chess_game.id);
+
+    Ok((challenge, chess_game))
+}
+
+pub async fn decline_challenge(
+    state: &AppState,
+    account_id: &str,
+    challenge_id: &str,
+) -> Result<Challenge, GameServiceError> {
+    let mut challenge = get_pending_challenge(state, challenge_id).await?;
+
+    // Open challenges have nobody to decline them, they are cancelled or left to expire
+    if challenge.challenged_account_id.as_deref() != Some(account_id) {
+        return Err(GameServiceError::ChallengeError(
+            format!("Only the challenged player can decline challenge {}", challenge_id)
+        ));
+    }
+
+    settle_challenge(state, &mut challenge, ChallengeStatus::Declined).await?;
+
+    Ok(challenge)
+}
+
+pub async fn cancel_challenge(
+    state: &AppState,
+    account_id: &str,
+    challenge_id: &str,
+) -> Result<Challenge, GameServiceError> {
+    let mut challenge = get_pending_challenge(state, challenge_id).await?;
+
+    if challenge.challenger_account_id != account_id {
+        return Err(GameServiceError::ChallengeError(
+            format!("Only the challenger can cancel challenge {}", challenge_id)
+        ));
+    }
+
+    settle_challenge(state, &mut challenge, ChallengeStatus::Cancelled).await?;
+
+    Ok(challenge)
+}
+
+/// Expires every pending challenge whose expiry has passed. A challenge that can't be expired is
+/// skipped, so it doesn't hold up the others.
+pub async fn expire_challenges(state: &AppState) -> Result<(), GameServiceError> {
+    let challenge_ids = challenge_repository::find_expired_challenge_ids(
+        state,
+        chrono::Utc::now()
+    ).await?;
+
+    for challenge_id in challenge_ids {
+        if let Err(e) = expire_challenge(state, &challenge_id).await {
+            error!("Failed to expire challenge {}: {}", challenge_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn expire_challenge(state: &AppState, challenge_id: &str) -> Result<(), GameServiceError> {
+    match challenge_repository::find_challenge(state, challenge_id).await? {
+        Some(mut challenge) if challenge.status == ChallengeStatus::Pending => {
+            settle_challenge(state, &mut challenge, ChallengeStatus::Expired).await
+        }
+        _ => challenge_repository::remove_challenge_expiry(state, challenge_id).await,
+    }
+}
+
+async fn get_pending_challenge(
+    state: &AppState,
+    challenge_id: &str,
+) -> Result<Challenge, GameServiceError> {
+    let challenge = get_challenge(state, challenge_id).await?;
+
+    if challenge.status != ChallengeStatus::Pending {
+        return Err(GameServiceError::ChallengeError(
+            format!("Challenge {} is {}", challenge_id, challenge.status.to_str())
+        ));
+    }
+
+    Ok(challenge)
+}
+
+async fn settle_challenge(
+    state: &AppState,
+    challenge: &mut Challenge,
+    status: ChallengeStatus,
+) -> Result<(), GameServiceError> {
+    challenge.status = status;
+    challenge_repository::update_challenge(state, challenge, ChallengeStatus::Pending).await?;
+
+    emit_challenge_update(state, challenge).await?;
+
+    debug!("Challenge {} {}", challenge.id, status.to_str());
+
+    Ok(())
+}
+
+async fn emit_challenge_update(
+    state: &AppState,
+    challenge: &Challenge,
+) -> Result<(), GameServiceError> {
+    crate::services::streaming_service::emit_challenge_update(
+        state,
+        ChallengeUpdateEvent {
+            challenge_id: challenge.id.clone(),
+            challenger_account_id: challenge.challenger_account_id.clone(),
+            challenged_account_id: challenge.challenged_account_id.clone(),
+            accepted_by: challenge.accepted_by.clone(),
+            game_type: challenge.game_type.to_str().to_owned(),
+            color: challenge.color.to_str().to_owned(),
+            ranked: challenge.ranked,
+            status: challenge.status.to_str().to_owned(),
+            expires_at: challenge.expires_at.timestamp_millis(),
+            game_id: challenge.game_id.clone(),
+        }
+    ).await
+}
@@ -1,8 +1,8 @@
 use rand::{seq::SliceRandom, thread_rng};
 use shared::{events::{GameOverEvent, GameStartEvent, GameStateUpdateEvent}};
-use tracing::debug;
+use tracing::{debug, error, warn};
 
-use crate::{error::GameServiceError, jobs::{TaskMessage, TaskType, check_game_job::CheckGamePayload}, primitives::{AccountIds, ChessGame, CreateGameDto}, state::state::AppState};
+use crate::{config::GameStateMode, error::GameServiceError, events::GameStateEvent, jobs::{TaskMessage, TaskType, check_game_job::CheckGamePayload}, primitives::{AccountIds, ChessGame, CreateGameDto, MakeMoveDto, MakeMoveResult}, repositories::event_repository, state::state::AppState};
 
 pub async fn create_game(
     state: &AppState,
@@ -12,16 +12,26 @@ pub async fn create_game(
         account_id0,
         account_id1,
         metadata,
-        game_type
+        game_type,
+        ranked,
+        white_account_id,
     } = payload;
 
-    // Randomly assign colors
     let mut vec = vec![account_id0, account_id1];
-    vec.shuffle(&mut thread_rng());
+
+    match white_account_id {
+        Some(white_account_id) if white_account_id == vec[0] => {}
+        Some(white_account_id) if white_account_id == vec[1] => vec.swap(0, 1),
+        Some(white_account_id) => return Err(GameServiceError::InvalidArgumentError(
+            format!("White player {} isn't one of the game's accounts", white_account_id)
+        )),
+        // Randomly assign colors
+        None => vec.shuffle(&mut thread_rng()),
+    }
 
     let (white_account_id, black_account_id) = (vec[0].as_str(), vec[1].as_str());
 
-    let chess_game = ChessGame::from_scratch(
+    let mut chess_game = ChessGame::from_scratch(
         game_type,
         AccountIds {
             w: white_account_id.to_string(),
@@ -30,15 +40,31 @@ pub async fn create_game(
         metadata,
         crate::primitives::get_game_rules(game_type)
     );
+    chess_game.ranked = ranked;
+
+    if state.config.storage.game_state_mode == GameStateMode::EventSourced {
+        let event_seq = event_repository::append_event(
+            state,
+            &chess_game.id,
+            0,
+            &GameStateEvent::game_created(&chess_game)
+        ).await?;
+
+        chess_game.event_seq = Some(event_seq);
+    }
 
     crate::repositories::game_repository::store_game(state, &chess_game).await?;
 
     crate::services::streaming_service::emit_game_start(
         state,
         GameStartEvent {
-            account_id_0: black_account_id.to_owned(),
+            account_id_0: white_account_id.to_owned(),
             account_id_1: black_account_id.to_string(),
-            game_id: chess_game.id.to_owned()
+            game_id: chess_game.id.to_owned(),
+            game_type: game_type.to_str().to_owned(),
+            ranked,
+            white_account_id: white_account_id.to_owned(),
+            black_account_id: black_account_id.to_owned(),
         }
     ).await?;
 
@@ -74,17 +100,72 @@ pub async fn get_game(
     state: &AppState,
     game_id: &str,
 ) -> Result<ChessGame, GameServiceError> {
+    let game_option = find_game(state, game_id).await?;
+
+    match game_option {
+        Some(game) => Ok(game),
+        None => Err(GameServiceError::GameNotFoundError(format!("Couldn't find game {}", game_id)))
+    }
+}
+
+/// Reads a game from redis. In event-sourced mode a missing game is rebuilt from its events and
+/// written back to redis.
+async fn find_game(
+    state: &AppState,
+    game_id: &str,
+) -> Result<Option<ChessGame>, GameServiceError> {
     let game_option = crate::repositories::game_repository::find_game(
         state,
         game_id
     ).await?;
 
-    match game_option {
-        Some(game) => Ok(game),
-        None => Err(GameServiceError::GameNotFoundError(format!("Couldn't find game {}", game_id)))
+    if game_option.is_some() || state.config.storage.game_state_mode != GameStateMode::EventSourced {
+        return Ok(game_option);
+    }
+
+    let (events, event_seq) = event_repository::load_events(state, game_id).await?;
+
+    match ChessGame::from_events(&events)? {
+        Some(mut chess_game) => {
+            chess_game.event_seq = Some(event_seq);
+            crate::repositories::game_repository::store_game(state, &chess_game).await?;
+
+            debug!("Game {}: rebuilt from {} events", game_id, events.len());
+
+            Ok(Some(chess_game))
+        }
+        None => Ok(None),
     }
 }
 
+/// Persists an updated game. Event-sourced games append `event` first, the write only succeeds
+/// if no other event was appended since the game was read.
+async fn save_game(
+    state: &AppState,
+    chess_game: &mut ChessGame,
+    event: GameStateEvent,
+) -> Result<(), GameServiceError> {
+    let Some(expected_event_seq) = chess_game.event_seq else {
+        return crate::repositories::game_repository::update_game(state, chess_game).await;
+    };
+
+    let event_seq = event_repository::append_event(
+        state,
+        &chess_game.id,
+        expected_event_seq,
+        &event
+    ).await?;
+    chess_game.event_seq = Some(event_seq);
+
+    // The event is the source of truth, a cache that can't be updated is dropped and rebuilt
+    if let Err(e) = crate::repositories::game_repository::update_game(state, chess_game).await {
+        warn!("Game {}: evicting cached state after failed update: {}", chess_game.id, e);
+        crate::repositories::game_repository::evict_game(state, &chess_game.id).await?;
+    }
+
+    Ok(())
+}
+
 pub async fn check_game_result(
     state: &AppState,
     chess_game: &mut ChessGame,
@@ -96,11 +177,23 @@ pub async fn check_game_result(
 
     let result = game_result.unwrap();
 
+    if let Some(expected_event_seq) = chess_game.event_seq {
+        event_repository::append_event(
+            state,
+            &chess_game.id,
+            expected_event_seq,
+            &GameStateEvent::GameEnded { result: result.clone(), at: chrono::Utc::now() }
+        ).await?;
+    }
+
+    let abandoned_account_id = chess_game.abandoned_account_id(&result).map(str::to_owned);
+
     let ChessGame {
         account_ids,
         id,
         game_type,
         metadata,
+        ranked,
         ..
     } = chess_game;
 
@@ -114,17 +207,21 @@ pub async fn check_game_result(
             winner_account_id: result.winner_account_id,
             game_id: id.to_string(),
             game_type: game_type.to_str().to_owned(),
-            metadata: metadata.to_string()
+            metadata: metadata.to_string(),
+            ranked: *ranked,
+            abandoned_account_id,
         }
     ).await?;
 
     debug!("Game {}: emitted game over event", chess_game.id);
 
+    crate::services::rematch_service::open_rematch(state, chess_game).await?;
+
     remove_game_from_check_queue(state, chess_game).await?;
 
     crate::repositories::game_repository::delete_game(
         state,
-        &chess_game.id
+        chess_game
     ).await?;
 
     Ok(())
@@ -147,17 +244,51 @@ async fn remove_game_from_check_queue(state: &AppState, chess_game: &ChessGame)
 
 pub async fn make_move(
     state: &AppState,
-    game_id: &str,
-    account_id: &str,
-    game_move: &str,
-) -> Result<ChessGame, GameServiceError> {
-    let mut chess_game = get_game(state, game_id).await?;
-    let _ = chess_game.make_move(account_id, game_move)?;
+    payload: MakeMoveDto,
+) -> Result<MakeMoveResult, GameServiceError> {
+    let MakeMoveDto {
+        game_id,
+        account_id,
+        game_move,
+        client_move_id,
+        expected_seq,
+    } = payload;
 
-    crate::repositories::game_repository::update_game(
-        state,
-        &mut chess_game
-    ).await?;
+    let mut chess_game = get_game(state, &game_id).await?;
+
+    if let Some(result) = find_duplicate_move(&chess_game, &account_id, client_move_id.as_deref()) {
+        return Ok(result);
+    }
+
+    if let Some(expected_seq) = expected_seq {
+        if expected_seq != chess_game.seq() {
+            return Err(GameServiceError::ConcurrentMoveError(format!(
+                "Game {} is at seq {}, the move was made on seq {}",
+                game_id,
+                chess_game.seq(),
+                expected_seq
+            )));
+        }
+    }
+
+    let at = chrono::Utc::now();
+    chess_game.make_move_at(&account_id, &game_move, at)?;
+
+    if let Some(client_move_id) = &client_move_id {
+        chess_game.record_client_move(&account_id, client_move_id);
+    }
+
+    let event = GameStateEvent::move_made(&chess_game, &account_id, at);
+    match save_game(state, &mut chess_game, event).await {
+        // A retry can race the original submission, in which case the original has won
+        Err(GameServiceError::ConcurrentMoveError(message)) if client_move_id.is_some() => {
+            let chess_game = get_game(state, &game_id).await?;
+
+            return find_duplicate_move(&chess_game, &account_id, client_move_id.as_deref())
+                .ok_or(GameServiceError::ConcurrentMoveError(message));
+        }
+        result => result?,
+    }
 
     crate::services::streaming_service::emit_game_state_update(
         state,
@@ -175,7 +306,27 @@ pub async fn make_move(
 
     debug!("Game {}: move '{}' by {}", game_id, game_move, account_id);
 
-    Ok(chess_game)
+    Ok(MakeMoveResult {
+        seq: chess_game.seq(),
+        chess_game,
+        duplicate: false,
+    })
+}
+
+fn find_duplicate_move(
+    chess_game: &ChessGame,
+    account_id: &str,
+    client_move_id: Option<&str>,
+) -> Option<MakeMoveResult> {
+    let seq = chess_game.find_client_move(account_id, client_move_id?)?;
+
+    debug!("Game {}: move {} by {} was already applied", chess_game.id, client_move_id?, account_id);
+
+    Some(MakeMoveResult {
+        chess_game: chess_game.clone(),
+        seq,
+        duplicate: true,
+    })
 }
 
 pub async fn resign(
@@ -187,12 +338,70 @@ pub async fn resign(
 
     let _ = chess_game.resign(account_id)?;
 
-    crate::repositories::game_repository::update_game(
+    save_game(
         state,
-        &chess_game
+        &mut chess_game,
+        GameStateEvent::Resigned { account_id: account_id.to_owned(), at: chrono::Utc::now() }
     ).await?;
 
     debug!("Game {}: {} resigned", game_id, account_id);
 
     Ok(chess_game)
 }
+
+/// Ongoing correspondence games of an account where it is the account's turn to move.
+pub async fn get_my_turn_games(
+    state: &AppState,
+    account_id: &str,
+) -> Result<Vec<ChessGame>, GameServiceError> {
+    let game_ids = crate::repositories::game_repository::find_account_correspondence_game_ids(
+        state,
+        account_id
+    ).await?;
+
+    let mut games = Vec::new();
+
+    for game_id in game_ids {
+        let game_option = find_game(state, &game_id).await?;
+
+        if let Some(game) = game_option {
+            if game.turn_account_id() == account_id {
+                games.push(game);
+            }
+        }
+    }
+
+    Ok(games)
+}
+
+/// Ends every correspondence game whose side to move has let its move deadline pass. A game that
+/// can't be checked is skipped, so it doesn't hold up the deadlines of the others.
+pub async fn check_correspondence_deadlines(state: &AppState) -> Result<(), GameServiceError> {
+    let game_ids = crate::repositories::game_repository::find_overdue_correspondence_game_ids(
+        state,
+        chrono::Utc::now()
+    ).await?;
+
+    for game_id in game_ids {
+        if let Err(e) = check_correspondence_deadline(state, &game_id).await {
+            error!("Game {}: failed to check correspondence move deadline: {}", game_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn check_correspondence_deadline(state: &AppState, game_id: &str) -> Result<(), GameServiceError> {
+    match find_game(state, game_id).await? {
+        Some(mut chess_game) => {
+            debug!("Game {}: correspondence move deadline passed", game_id);
+            check_game_result(state, &mut chess_game).await
+        }
+        None => {
+            crate::repositories::game_repository::remove_correspondence_deadline(
+                state,
+                game_id
+            ).await
+        }
+    }
+}
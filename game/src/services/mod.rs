@@ -1,2 +1,5 @@
+pub mod challenge_service;
 pub mod game_service;
+pub mod rematch_service;
+pub mod season_service;
 pub mod streaming_service;
@@ -0,0 +1,152 @@
+use shared::events::RematchUpdateEvent;
+use tracing::{debug, error};
+
+use crate::{error::GameServiceError, primitives::{ChessGame, CreateGameDto, Rematch}, repositories::rematch_repository, services::season_service, state::state::AppState};
+
+/// Opens the rematch window of a game that just ended.
+pub async fn open_rematch(
+    state: &AppState,
+    chess_game: &ChessGame,
+) -> Result<(), GameServiceError> {
+    rematch_repository::store_rematch(state, &Rematch::new(chess_game)).await
+}
+
+/// Offers a rematch to the opponent. If the opponent already offered one, the offers cross and
+/// the rematch is accepted right away.
+pub async fn offer_rematch(
+    state: &AppState,
+    account_id: &str,
+    game_id: &str,
+) -> Result<Rematch, GameServiceError> {
+    let rematch = get_rematch(state, account_id, game_id).await?;
+
+    if rematch.offered_by.as_deref() == Some(rematch.opponent_account_id(account_id)) {
+        let (rematch, _) = accept_rematch(state, account_id, game_id).await?;
+        return Ok(rematch);
+    }
+
+    if rematch.ranked {
+        season_service::ensure_active_season(state).await?;
+    }
+
+    if !rematch_repository::set_offered_by(state, game_id, account_id).await? {
+        // Someone offered in the meantime, either us again or our opponent
+        return match get_rematch(state, account_id, game_id).await?.offered_by {
+            Some(offered_by) if offered_by == account_id => Ok(rematch),
+            _ => Ok(accept_rematch(state, account_id, game_id).await?.0),
+        };
+    }
+
+    let rematch = Rematch { offered_by: Some(account_id.to_owned()), ..rematch };
+
+    emit_rematch_update(state, &rematch, "offered").await?;
+
+    debug!("Game {}: rematch offered by {}", game_id, account_id);
+
+    Ok(rematch)
+}
+
+/// Accepts the opponent's rematch offer and creates the new game with the colors swapped.
+pub async fn accept_rematch(
+    state: &AppState,
+    account_id: &str,
+    game_id: &str,
+) -> Result<(Rematch, ChessGame), GameServiceError> {
+    let mut rematch = get_rematch(state, account_id, game_id).await?;
+
+    if rematch.offered_by.as_deref() != Some(rematch.opponent_account_id(account_id)) {
+        return Err(GameServiceError::RematchError(
+            format!("Game {}: no rematch offer from the opponent", game_id)
+        ));
+    }
+
+    if rematch.ranked {
+        season_service::ensure_active_season(state).await?;
+    }
+
+    if !rematch_repository::set_accepted_by(state, game_id, account_id).await? {
+        return Err(GameServiceError::RematchError(
+            format!("Game {}: rematch was already accepted", game_id)
+        ));
+    }
+
+    let white_account_id = rematch.white_account_id().to_owned();
+    let black_account_id = rematch.opponent_account_id(&white_account_id).to_owned();
+
+    let created = crate::services::game_service::create_game(
+        state,
+        CreateGameDto {
+            account_id0: white_account_id.clone(),
+            account_id1: black_account_id,
+            game_type: rematch.game_type,
+            metadata: serde_json::json!({ "rematchOf": game_id }).to_string(),
+            ranked: rematch.ranked,
+            white_account_id: Some(white_account_id),
+        }
+    ).await;
+
+    let chess_game = match created {
+        Ok(chess_game) => chess_game,
+        Err(e) => {
+            // The rematch stays open for another try while its window lasts
+            if let Err(release_error) = rematch_repository::clear_accepted_by(state, game_id, account_id).await {
+                error!("Game {}: failed to release the rematch after its game wasn't created: {}", game_id, release_error);
+            }
+
+            return Err(e);
+        }
+    };
+
+    rematch_repository::set_new_game_id(state, game_id, &chess_game.id).await?;
+    rematch.new_game_id = Some(chess_game.id.clone());
+
+    emit_rematch_update(state, &rematch, "accepted").await?;
+
+    debug!("Game {}: rematch accepted by {}, new game {}", game_id, account_id, chess_game.id);
+
+    Ok((rematch, chess_game))
+}
+
+async fn get_rematch(
+    state: &AppState,
+    account_id: &str,
+    game_id: &str,
+) -> Result<Rematch, GameServiceError> {
+    let rematch = rematch_repository::find_rematch(state, game_id).await?
+        .ok_or_else(|| GameServiceError::RematchError(
+            format!("Rematch window of game {} has closed", game_id)
+        ))?;
+
+    if !rematch.is_player(account_id) {
+        return Err(GameServiceError::RematchError(
+            format!("Account {} didn't play game {}", account_id, game_id)
+        ));
+    }
+
+    if rematch.new_game_id.is_some() {
+        return Err(GameServiceError::RematchError(
+            format!("Game {}: rematch was already accepted", game_id)
+        ));
+    }
+
+    Ok(rematch)
+}
+
+async fn emit_rematch_update(
+    state: &AppState,
+    rematch: &Rematch,
+    status: &str,
+) -> Result<(), GameServiceError> {
+    crate::services::streaming_service::emit_rematch_update(
+        state,
+        RematchUpdateEvent {
+            game_id: rematch.game_id.clone(),
+            account_id_0: rematch.account_ids.w.clone(),
+            account_id_1: rematch.account_ids.b.clone(),
+            offered_by: rematch.offered_by.clone().unwrap_or_default(),
+            status: status.to_owned(),
+            expires_at: rematch.expires_at.timestamp_millis(),
+            new_game_id: rematch.new_game_id.clone(),
+        }
+    ).await
+}
@@ -0,0 +1,17 @@
+use shared::GetActiveSeasonRequest;
+
+use crate::{error::GameServiceError, state::state::AppState};
+
+/// Fails unless a season is running. Ranked challenges and rematches close between seasons,
+/// like the ranked queues.
+pub async fn ensure_active_season(state: &AppState) -> Result<(), GameServiceError> {
+    let response = state.ranking_client.clone()
+        .get_active_season(GetActiveSeasonRequest {})
+        .await
+        .map_err(|status| GameServiceError::RankingServiceError(status.message().to_owned()))?;
+
+    match response.into_inner().season {
+        Some(_) => Ok(()),
+        None => Err(GameServiceError::NoActiveSeasonError),
+    }
+}
@@ -1,4 +1,4 @@
-use shared::{events::{GameEvent, GameOverEvent, GameStartEvent, GameStateUpdateEvent}};
+use shared::{events::{ChallengeUpdateEvent, GameEvent, GameOverEvent, GameStartEvent, GameStateUpdateEvent, RematchUpdateEvent}};
 
 use crate::{error::GameServiceError, state::state::AppState};
 
@@ -52,3 +52,39 @@ pub async fn emit_game_over_event(
 
     Ok(())
 }
+
+pub async fn emit_challenge_update(
+    state: &AppState,
+    payload: ChallengeUpdateEvent
+) -> Result<(), GameServiceError> {
+    let event = GameEvent::ChallengeUpdate(payload.clone());
+    let subject = event.subject();
+
+    let payload = serde_json::to_vec(&event).unwrap();
+
+    state
+        .jetstream
+        .publish(subject.to_string(), payload.into())
+        .await
+        .expect("Failed to emit challenge-update event");
+
+    Ok(())
+}
+
+pub async fn emit_rematch_update(
+    state: &AppState,
+    payload: RematchUpdateEvent
+) -> Result<(), GameServiceError> {
+    let event = GameEvent::RematchUpdate(payload.clone());
+    let subject = event.subject();
+
+    let payload = serde_json::to_vec(&event).unwrap();
+
+    state
+        .jetstream
+        .publish(subject.to_string(), payload.into())
+        .await
+        .expect("Failed to emit rematch-update event");
+
+    Ok(())
+}
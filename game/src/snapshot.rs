@@ -0,0 +1,426 @@
+//! Versioned storage encodings of a `ChessGame`.
+//!
+//! The binary format is the default for the Redis game state. JSON is kept as a human readable
+//! debug format, and `decode` accepts both so games written in either format keep loading.
+//!
+//! Binary layout (version 2), integers are LEB128 varints unless noted:
+//!
+//! ```text
+//! magic "CM" | version u8
+//! header:  id, game type, white id, black id, metadata (length prefixed strings)
+//!          time limit ms, increment ms, days per move + 1 (0 = none)
+//! clocks:  white ms, black ms, start ms, last move timestamp + 1, move deadline ms + 1
+//! state:   resigned color u8 (0 = none, 1 = black, 2 = white), seq, fen (string)
+//! moves:   count, then one little endian u16 per move (from | to << 6 | promotion << 12)
+//! client:  white then black client moves, count then id (string) and seq of each
+//! ranked:  u8
+//! ```
+//!
+//! Version 1 snapshots end after the moves and are still read.
+
+use chrono::{DateTime, Utc};
+use shakmaty::{Color, Role, Square, uci::UciMove};
+use shared::primitives::GameType;
+use std::str::FromStr;
+
+use crate::{error::GameServiceError, primitives::{AccountIds, ChessGame, ClientMoves, ColorWrapper, GameClocks, GameRules, SideClientMoves}};
+
+const MAGIC: [u8; 2] = *b"CM";
+const VERSION: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SnapshotFormat {
+    Json,
+    Binary,
+}
+
+impl SnapshotFormat {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            SnapshotFormat::Json => "json",
+            SnapshotFormat::Binary => "binary",
+        }
+    }
+}
+
+pub fn encode(chess_game: &ChessGame, format: SnapshotFormat) -> Vec<u8> {
+    match format {
+        SnapshotFormat::Json => chess_game.to_string().into_bytes(),
+        SnapshotFormat::Binary => encode_binary(chess_game),
+    }
+}
+
+/// Decodes a snapshot in any supported format, detected from its first bytes.
+pub fn decode(bytes: &[u8]) -> Result<ChessGame, GameServiceError> {
+    if bytes.starts_with(&MAGIC) {
+        return decode_binary(bytes);
+    }
+
+    let game_repr = std::str::from_utf8(bytes)
+        .map_err(|e| GameServiceError::InvalidSnapshotError(e.to_string()))?;
+
+    ChessGame::from_string(game_repr)
+}
+
+pub fn encode_binary(chess_game: &ChessGame) -> Vec<u8> {
+    let mut writer = Writer::with_capacity(128 + chess_game.moves.len() * 2);
+
+    writer.bytes(&MAGIC);
+    writer.u8(VERSION);
+
+    writer.str(&chess_game.id);
+    writer.str(chess_game.game_type.to_str());
+    writer.str(&chess_game.account_ids.w);
+    writer.str(&chess_game.account_ids.b);
+    writer.str(&chess_game.metadata);
+
+    let GameRules { time_limit_ms, time_increase_per_turn_ms, days_per_move } = &chess_game.game_rules;
+    writer.varint(*time_limit_ms);
+    writer.varint(*time_increase_per_turn_ms);
+    writer.opt_varint(*days_per_move);
+
+    let GameClocks { w, b, start_timestamp, last_move_timestamp, move_deadline } = &chess_game.game_clocks;
+    writer.varint(*w);
+    writer.varint(*b);
+    writer.varint(start_timestamp.timestamp_millis() as u64);
+    writer.opt_varint(last_move_timestamp.map(|timestamp| timestamp as u32 as u64));
+    writer.opt_varint(move_deadline.map(|deadline| deadline.timestamp_millis() as u64));
+
+    writer.u8(match chess_game.resigned_color.map(Color::from) {
+        None => 0,
+        Some(Color::Black) => 1,
+        Some(Color::White) => 2,
+    });
+    writer.varint(chess_game.seq());
+    writer.str(&chess_game.fen());
+
+    writer.varint(chess_game.moves.len() as u64);
+    for game_move in &chess_game.moves {
+        writer.bytes(&pack_move(game_move).to_le_bytes());
+    }
+
+    for client_moves in [&chess_game.client_moves.w, &chess_game.client_moves.b] {
+        writer.varint(client_moves.len() as u64);
+        for (id, seq) in client_moves {
+            writer.str(id);
+            writer.varint(*seq);
+        }
+    }
+
+    writer.u8(chess_game.ranked as u8);
+
+    writer.into_inner()
+}
+
+pub fn decode_binary(bytes: &[u8]) -> Result<ChessGame, GameServiceError> {
+    let mut reader = Reader::new(bytes);
+
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(invalid("missing magic bytes"));
+    }
+
+    let version = reader.u8()?;
+    if version == 0 || version > VERSION {
+        return Err(invalid(&format!("unsupported version {}", version)));
+    }
+
+    let id = reader.string()?;
+    let game_type = GameType::from_str(&reader.string()?)
+        .map_err(|e| GameServiceError::UnknownGameTypeError(e.to_string()))?;
+    let account_ids = AccountIds {
+        w: reader.string()?,
+        b: reader.string()?,
+    };
+    let metadata = reader.string()?;
+
+    let game_rules = GameRules {
+        time_limit_ms: reader.varint()?,
+        time_increase_per_turn_ms: reader.varint()?,
+        days_per_move: reader.opt_varint()?,
+    };
+
+    let game_clocks = GameClocks {
+        w: reader.varint()?,
+        b: reader.varint()?,
+        start_timestamp: datetime_from_millis(reader.varint()?)?,
+        last_move_timestamp: reader.opt_varint()?.map(|timestamp| timestamp as u32 as i32),
+        move_deadline: reader.opt_varint()?.map(datetime_from_millis).transpose()?,
+    };
+
+    let resigned_color = match reader.u8()? {
+        0 => None,
+        1 => Some(ColorWrapper(Color::Black)),
+        2 => Some(ColorWrapper(Color::White)),
+        value => return Err(invalid(&format!("invalid resigned color {}", value))),
+    };
+    let seq = reader.varint()?;
+    let chess = ChessGame::chess_from_fen(&reader.string()?)?;
+
+    let moves_count = reader.varint()? as usize;
+    let mut moves = Vec::with_capacity(moves_count);
+    for _ in 0..moves_count {
+        let packed = reader.take(2)?;
+        moves.push(unpack_move(u16::from_le_bytes([packed[0], packed[1]]))?);
+    }
+
+    let client_moves = match version {
+        1 => ClientMoves::default(),
+        _ => ClientMoves {
+            w: reader.client_moves()?,
+            b: reader.client_moves()?,
+        },
+    };
+
+    let ranked = match version {
+        1 => false,
+        _ => reader.u8()? != 0,
+    };
+
+    let mut chess_game = ChessGame::new(
+        id,
+        chess,
+        game_type,
+        account_ids,
+        metadata,
+        game_rules,
+        game_clocks,
+    );
+    chess_game.resigned_color = resigned_color;
+    chess_game.moves = moves;
+    chess_game.seq = seq;
+    chess_game.client_moves = client_moves;
+    chess_game.ranked = ranked;
+
+    Ok(chess_game)
+}
+
+fn pack_move(game_move: &UciMove) -> u16 {
+    match game_move {
+        UciMove::Normal { from, to, promotion } => {
+            let promotion = promotion.map_or(0, |role| role as u16);
+            (*from as u16) | (*to as u16) << 6 | promotion << 12
+        }
+        // Drops and null moves don't occur in standard chess, a1a1 stands in for them
+        UciMove::Put { .. } | UciMove::Null => 0,
+    }
+}
+
+fn unpack_move(packed: u16) -> Result<UciMove, GameServiceError> {
+    let from = Square::try_from(packed & 0x3f).map_err(|_| invalid("invalid square"))?;
+    let to = Square::try_from((packed >> 6) & 0x3f).map_err(|_| invalid("invalid square"))?;
+
+    if from == to {
+        return Ok(UciMove::Null);
+    }
+
+    let promotion = match packed >> 12 {
+        0 => None,
+        role => Some(Role::try_from(role).map_err(|_| invalid("invalid promotion role"))?),
+    };
+
+    Ok(UciMove::Normal { from, to, promotion })
+}
+
+fn datetime_from_millis(millis: u64) -> Result<DateTime<Utc>, GameServiceError> {
+    DateTime::from_timestamp_millis(millis as i64)
+        .ok_or_else(|| invalid("timestamp out of range"))
+}
+
+fn invalid(message: &str) -> GameServiceError {
+    GameServiceError::InvalidSnapshotError(message.to_string())
+}
+
+struct Writer {
+    buffer: Vec<u8>,
+}
+
+impl Writer {
+    fn with_capacity(capacity: usize) -> Self {
+        Self { buffer: Vec::with_capacity(capacity) }
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buffer.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        self.buffer.push(value as u8);
+    }
+
+    /// `None` is stored as 0 and `Some(v)` as `v + 1`.
+    fn opt_varint(&mut self, value: Option<u64>) {
+        self.varint(value.map_or(0, |value| value + 1));
+    }
+
+    fn str(&mut self, value: &str) {
+        self.varint(value.len() as u64);
+        self.bytes(value.as_bytes());
+    }
+
+    fn into_inner(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], GameServiceError> {
+        let end = self.position.checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid("unexpected end of snapshot"))?;
+
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, GameServiceError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, GameServiceError> {
+        let mut value = 0u64;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(invalid("varint too long"))
+    }
+
+    fn opt_varint(&mut self) -> Result<Option<u64>, GameServiceError> {
+        Ok(self.varint()?.checked_sub(1))
+    }
+
+    fn client_moves(&mut self) -> Result<SideClientMoves, GameServiceError> {
+        let count = self.varint()? as usize;
+        let mut client_moves = SideClientMoves::new();
+
+        for _ in 0..count {
+            let id = self.string()?;
+            client_moves.insert(id, self.varint()?);
+        }
+
+        Ok(client_moves)
+    }
+
+    fn string(&mut self) -> Result<String, GameServiceError> {
+        let len = self.varint()? as usize;
+        let bytes = self.take(len)?;
+
+        String::from_utf8(bytes.to_vec())
+            .map_err(|e| GameServiceError::InvalidSnapshotError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::get_game_rules;
+
+    fn played_game() -> ChessGame {
+        let mut game = ChessGame::from_scratch(
+            GameType::Correspondence3d,
+            AccountIds { w: "white".to_string(), b: "black".to_string() },
+            "{\"mmr\":[1000,1200]}".to_string(),
+            get_game_rules(GameType::Correspondence3d)
+        );
+        game.ranked = true;
+
+        // Includes castling and a promotion with capture
+        for (account_id, game_move) in [
+            ("white", "e4"), ("black", "d5"), ("white", "Nf3"), ("black", "dxe4"),
+            ("white", "Bc4"), ("black", "f5"), ("white", "O-O"), ("black", "exf3"),
+            ("white", "d4"), ("black", "fxg2"), ("white", "Qe2"), ("black", "gxf1=N"),
+        ] {
+            game.make_move(account_id, game_move).unwrap();
+            game.record_client_move(account_id, &format!("{}-{}", account_id, game.seq()));
+        }
+
+        game
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let game = played_game();
+
+        let bytes = encode(&game, SnapshotFormat::Binary);
+        let decoded = decode(&bytes).unwrap();
+
+        assert_eq!(encode(&decoded, SnapshotFormat::Binary), bytes);
+        assert_eq!(decoded.fen(), game.fen());
+        assert_eq!(decoded.moves, game.moves);
+        assert_eq!(decoded.seq(), game.seq());
+        assert_eq!(decoded.find_client_move("black", "black-12"), Some(12));
+        assert_eq!(decoded.find_client_move("white", "white-1"), Some(1));
+        assert!(decoded.ranked);
+    }
+
+    #[test]
+    fn test_version_1_snapshots_are_read() {
+        let mut game = played_game();
+        game.client_moves = ClientMoves::default();
+
+        // A version 1 snapshot is a current one without the client moves and ranked flag
+        let mut bytes = encode(&game, SnapshotFormat::Binary);
+        bytes[2] = 1;
+        bytes.truncate(bytes.len() - 3);
+
+        let decoded = decode(&bytes).unwrap();
+
+        assert_eq!(decoded.moves, game.moves);
+        assert!(decoded.client_moves.w.is_empty());
+        assert!(!decoded.ranked);
+    }
+
+    #[test]
+    fn test_decode_reads_json_snapshots() {
+        let game = played_game();
+
+        let decoded = decode(&encode(&game, SnapshotFormat::Json)).unwrap();
+
+        assert_eq!(decoded.to_string(), game.to_string());
+    }
+
+    #[test]
+    fn test_binary_is_smaller_than_json() {
+        let game = played_game();
+
+        let binary = encode(&game, SnapshotFormat::Binary);
+        let json = encode(&game, SnapshotFormat::Json);
+
+        assert!(binary.len() * 2 < json.len());
+    }
+
+    #[test]
+    fn test_truncated_snapshot_is_rejected() {
+        let bytes = encode(&played_game(), SnapshotFormat::Binary);
+
+        assert!(matches!(
+            decode(&bytes[..bytes.len() - 1]),
+            Err(GameServiceError::InvalidSnapshotError(_))
+        ));
+    }
+}
@@ -3,18 +3,19 @@ use std::sync::Arc;
 
 use shared::error::ChessmartApiError;
 
-use crate::{client::{nats::{NatsDB, NatsJetstreamContext}, redis::{RedisClient, RedisDB}}, config::{ApiConfig, NatsConfig, RedisConfig, ServerConfig, TracingConfig}};
+use crate::{client::{nats::{NatsDB, NatsJetstreamContext}, ranking::{RankingClient, RankingGrpcClient}, redis::{RedisClient, RedisDB}}, config::{ApiConfig, NatsConfig, RankingServiceConfig, RedisConfig, ServerConfig, StorageConfig, TracingConfig}};
 
 #[derive(Clone)]
 pub struct AppState {
     pub config: Arc<ApiConfig>,
     pub redis: Arc<RedisClient>,
     pub jetstream: Arc<NatsJetstreamContext>,
+    pub ranking_client: RankingGrpcClient,
 }
 
 impl AppState
 {
-    pub fn new(config: ApiConfig, redis: RedisClient, jetstream: NatsJetstreamContext) -> Self {
+    pub fn new(config: ApiConfig, redis: RedisClient, jetstream: NatsJetstreamContext, ranking_client: RankingGrpcClient) -> Self {
         let config = Arc::new(config);
         let redis = Arc::new(redis);
         let jetstream = Arc::new(jetstream);
@@ -23,6 +24,7 @@ impl AppState
             config,
             redis,
             jetstream,
+            ranking_client,
         }
     }
 }
@@ -33,6 +35,8 @@ pub struct AppStateBuilder {
     tracing_config: Option<TracingConfig>,
     redis_config: Option<RedisConfig>,
     nats_config: Option<NatsConfig>,
+    storage_config: Option<StorageConfig>,
+    ranking_service_config: Option<RankingServiceConfig>,
 }
 
 impl AppStateBuilder {
@@ -42,6 +46,8 @@ impl AppStateBuilder {
             tracing_config: None,
             redis_config: None,
             nats_config: None,
+            storage_config: None,
+            ranking_service_config: None,
         }
     }
 
@@ -55,6 +61,16 @@ impl AppStateBuilder {
         self
     }
 
+    pub fn with_storage(mut self, storage_config: Option<StorageConfig>) -> Self {
+        self.storage_config = storage_config;
+        self
+    }
+
+    pub fn with_ranking_service(mut self, ranking_service_config: Option<RankingServiceConfig>) -> Self {
+        self.ranking_service_config = ranking_service_config;
+        self
+    }
+
     pub fn with_server(mut self, server_config: Option<ServerConfig>) -> Self {
         self.server_config = server_config;
         self
@@ -72,15 +88,21 @@ impl AppStateBuilder {
         let nats_config = self.nats_config.expect("nats-config not set");
         let jetstream = NatsDB::new(&nats_config).await?;
 
+        let ranking_service_config = self.ranking_service_config.unwrap_or_default();
+        let ranking_client = RankingClient::connect(&ranking_service_config)?;
+
         Ok(AppState::new(
             ApiConfig::new(
                 self.server_config.unwrap_or_default(),
                 self.tracing_config,
                 redis_config,
                 nats_config,
+                self.storage_config.unwrap_or_default(),
+                ranking_service_config,
             ),
             redis,
-            jetstream
+            jetstream,
+            ranking_client,
         ))
     }
 }
@@ -1,5 +1,5 @@
 use shared::error::ChessmartApiError;
-use tracing::info;
+use tracing::{error, info};
 
 use crate::{jobs::TaskMessage, state::state::AppState};
 use crate::jobs::TaskType::CheckGameJob;
@@ -17,6 +17,14 @@ impl Worker {
         println!("The game task worker started.");
 
         loop {
+            if let Err(e) = crate::services::game_service::check_correspondence_deadlines(&self.state).await {
+                error!("Failed to check correspondence deadlines: {e}");
+            }
+
+            if let Err(e) = crate::services::challenge_service::expire_challenges(&self.state).await {
+                error!("Failed to expire challenges: {e}");
+            }
+
             let outcome: Option<Vec<u8>> = {
                 let mut conn = self.state.redis.get_connection()?;
 
@@ -18,3 +18,26 @@ pub struct LoginRequestDto {
 pub struct RefreshTokenRequestDto {
   pub token: String,
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateChallengeRequestDto {
+  /// Account to challenge, leave empty for an open challenge shared as a link
+  pub challenged_account_id: Option<String>,
+  pub game_type: String,
+  /// Color of the challenger: white, black or random
+  pub color: Option<String>,
+  #[serde(default)]
+  pub ranked: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
+pub struct LeaderboardQueryDto {
+  /// Number of top players, 10 by default and at most 100
+  pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
+pub struct RatingHistoryQueryDto {
+  /// History of the ranked ratings (default) or of the normal ones
+  pub ranked: Option<bool>,
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -53,7 +55,22 @@ pub struct MeResponseDto {
   pub game_type: Option<String>,
   pub ranked: Option<bool>,
   pub game_id: Option<String>,
-  pub mmr: u64,
+  /// Queues the player is searching in
+  pub pools: Vec<QueuePoolResponseDto>,
+  /// Estimated wait in seconds while searching
+  pub estimated_wait_seconds: Option<u32>,
+  /// Seconds until the player can join queues again after declining or abandoning games
+  pub queue_cooldown_seconds: Option<u32>,
+  /// Recent declines, pending game timeouts and abandons, decaying over time
+  pub queue_penalty_score: f64,
+  /// Ranked ratings, keyed by rating category
+  pub ratings: HashMap<String, RatingResponseDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct QueuePoolResponseDto {
+  pub game_type: String,
+  pub ranked: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -63,5 +80,160 @@ pub struct AccountResponseDto {
   pub is_admin: bool,
   pub created_at: Option<DateTime<Utc>>,
   pub last_login_at: Option<DateTime<Utc>>,
-  pub mmr: u64,
+  /// Ranked ratings, keyed by rating category
+  pub ratings: HashMap<String, RatingResponseDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RatingResponseDto {
+  pub rating: u64,
+  pub deviation: u64,
+  pub provisional: bool,
+}
+
+impl RatingResponseDto {
+  /// Ranked ratings of every category of a ranking response.
+  pub fn ranked_ratings(response: shared::GetAccountRankingResponse) -> HashMap<String, Self> {
+    response.ratings
+      .into_iter()
+      .filter_map(|(category, ratings)| {
+        let rating = ratings.ranked?;
+
+        Some((category, Self {
+          rating: rating.rating.round() as u64,
+          deviation: rating.deviation.round() as u64,
+          provisional: rating.provisional,
+        }))
+      })
+      .collect()
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RatingHistoryPointDto {
+  pub game_id: String,
+  /// Rating after the game
+  pub rating: u64,
+  /// win, draw or loss
+  pub result: String,
+  pub played_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StreakDto {
+  pub result: String,
+  pub length: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategoryRatingHistoryDto {
+  /// Oldest first
+  pub points: Vec<RatingHistoryPointDto>,
+  pub peak_rating: Option<u64>,
+  pub lowest_rating: Option<u64>,
+  pub current_streak: Option<StreakDto>,
+  pub wins: u32,
+  pub draws: u32,
+  pub losses: u32,
+}
+
+impl From<shared::RatingHistory> for CategoryRatingHistoryDto {
+  fn from(history: shared::RatingHistory) -> Self {
+    Self {
+      points: history.points
+        .into_iter()
+        .map(|point| RatingHistoryPointDto {
+          game_id: point.game_id,
+          rating: point.rating.round() as u64,
+          result: point.result,
+          played_at: DateTime::from_timestamp_millis(point.played_at),
+        })
+        .collect(),
+      peak_rating: history.peak_rating.map(|rating| rating.round() as u64),
+      lowest_rating: history.lowest_rating.map(|rating| rating.round() as u64),
+      current_streak: history.current_streak_result.map(|result| StreakDto {
+        result,
+        length: history.current_streak_length,
+      }),
+      wins: history.wins,
+      draws: history.draws,
+      losses: history.losses,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RatingHistoryResponseDto {
+  /// Keyed by rating category, only the categories the player has played
+  pub histories: HashMap<String, CategoryRatingHistoryDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MyTurnGamesResponseDto {
+  pub games: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChallengeResponseDto {
+  pub id: String,
+  pub challenger_account_id: String,
+  pub challenged_account_id: Option<String>,
+  pub game_type: String,
+  pub color: String,
+  pub ranked: bool,
+  pub status: String,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub game_id: Option<String>,
+}
+
+impl From<shared::Challenge> for ChallengeResponseDto {
+  fn from(challenge: shared::Challenge) -> Self {
+    Self {
+      id: challenge.id,
+      challenger_account_id: challenge.challenger_account_id,
+      challenged_account_id: challenge.challenged_account_id,
+      game_type: challenge.game_type,
+      color: challenge.color,
+      ranked: challenge.ranked,
+      status: challenge.status,
+      expires_at: DateTime::from_timestamp_millis(challenge.expires_at),
+      game_id: challenge.game_id,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChallengesResponseDto {
+  pub challenges: Vec<ChallengeResponseDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LeaderboardEntryDto {
+  pub rank: u64,
+  pub account_id: String,
+  pub rating: u64,
+}
+
+impl From<shared::LeaderboardEntry> for LeaderboardEntryDto {
+  fn from(entry: shared::LeaderboardEntry) -> Self {
+    Self {
+      rank: entry.rank,
+      account_id: entry.account_id,
+      rating: entry.rating.round() as u64,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LeaderboardResponseDto {
+  pub category: String,
+  pub top: Vec<LeaderboardEntryDto>,
+  /// Players ranked around the user, empty if the user is not on the leaderboard
+  pub around_me: Vec<LeaderboardEntryDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AcceptChallengeResponseDto {
+  pub challenge: ChallengeResponseDto,
+  pub game: String,
 }
@@ -10,6 +10,11 @@ pub struct MakeMoveRequestDto {
     game_id: String,
     #[serde(rename = "move")]
     game_move: String,
+    /// Lets the client safely resend the move, e.g. after a reconnect
+    #[serde(rename = "clientMoveId")]
+    client_move_id: Option<String>,
+    /// Seq of the game state the move was made on
+    seq: Option<u64>,
 }
 
 pub async fn handle_make_move(
@@ -27,13 +32,30 @@ pub async fn handle_make_move(
         .unwrap()
         .uid;
 
-    game_client.make_move(shared::MakeMoveRequest{
+    let result = game_client.make_move(shared::MakeMoveRequest{
         account_id: account_id.to_string(),
         game_id: payload.game_id,
         r#move: payload.game_move,
-    }).await.expect("Failed to make move.");
-
-    ack.send(&()).ok();
+        client_move_id: payload.client_move_id,
+        expected_seq: payload.seq,
+    }).await;
+
+    let response = match result {
+        Ok(response) => {
+            let shared::MakeMoveResponse { seq, duplicate, .. } = response.into_inner();
+
+            serde_json::json!({
+                "seq": seq,
+                "duplicate": duplicate,
+            })
+        }
+        Err(status) => serde_json::json!({
+            "error": status.message(),
+            "retryable": status.code() == tonic::Code::Aborted,
+        }),
+    };
+
+    ack.send(&response).ok();
 }
 
 #[derive(Debug, Deserialize)]
@@ -85,3 +107,66 @@ pub async fn handle_check_result(
 
     ack.send(&()).ok();
 }
+
+#[derive(Debug, Deserialize)]
+pub struct RematchRequestDto {
+    /// Game that just ended
+    #[serde(rename = "gameId")]
+    game_id: String,
+}
+
+pub async fn handle_offer_rematch(
+    socket: SocketRef,
+    Data(payload): Data<RematchRequestDto>,
+    ack: AckSender,
+    State(state): State<AppState>,
+) {
+    println!("Offer rematch, game_id: {}", payload.game_id);
+
+    let mut game_client = state.game_client.clone();
+
+    let account_id = socket.extensions.get::<UserClaims>()
+        .ok_or("Unauthorized")
+        .unwrap()
+        .uid;
+
+    let result = game_client.offer_rematch(shared::OfferRematchRequest {
+        account_id: account_id.to_string(),
+        game_id: payload.game_id,
+    }).await;
+
+    let response = match result {
+        Ok(response) => serde_json::json!({ "expiresAt": response.into_inner().expires_at }),
+        Err(status) => serde_json::json!({ "error": status.message() }),
+    };
+
+    ack.send(&response).ok();
+}
+
+pub async fn handle_accept_rematch(
+    socket: SocketRef,
+    Data(payload): Data<RematchRequestDto>,
+    ack: AckSender,
+    State(state): State<AppState>,
+) {
+    println!("Accept rematch, game_id: {}", payload.game_id);
+
+    let mut game_client = state.game_client.clone();
+
+    let account_id = socket.extensions.get::<UserClaims>()
+        .ok_or("Unauthorized")
+        .unwrap()
+        .uid;
+
+    let result = game_client.accept_rematch(shared::AcceptRematchRequest {
+        account_id: account_id.to_string(),
+        game_id: payload.game_id,
+    }).await;
+
+    let response = match result {
+        Ok(response) => serde_json::json!({ "gameId": response.into_inner().game_id }),
+        Err(status) => serde_json::json!({ "error": status.message() }),
+    };
+
+    ack.send(&response).ok();
+}
@@ -6,19 +6,31 @@ use socketioxide::extract::{AckSender, Data, SocketRef, State};
 use crate::{server::state::AppState, utils::claim::UserClaims};
 
 #[derive(Debug, Deserialize)]
-pub struct AddToQueueDto {
+pub struct QueuePoolDto {
     #[serde(rename = "gameType")]
     game_type: String,
     ranked: bool,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct AddToQueueDto {
+    /// Single queue to join, used when no pools are given
+    #[serde(rename = "gameType", default)]
+    game_type: String,
+    #[serde(default)]
+    ranked: bool,
+    /// Queues to join at once
+    #[serde(default)]
+    pools: Vec<QueuePoolDto>,
+}
+
 pub async fn handle_add_to_queue(
     socket: SocketRef,
     Data(payload): Data<AddToQueueDto>,
     ack: AckSender,
     State(state): State<AppState>,
 ) {
-    println!("Add to queue game_type: {}, ranked: {}", payload.game_type, payload.ranked);
+    println!("Add to queue game_type: {}, ranked: {}, pools: {:?}", payload.game_type, payload.ranked, payload.pools);
 
     let mut matchmaking_client = state.matchmaking_client.clone();
 
@@ -27,13 +39,21 @@ pub async fn handle_add_to_queue(
         .unwrap()
         .uid;
 
-    matchmaking_client.add_to_queue(shared::AddToQueueRequest{
+    let result = matchmaking_client.add_to_queue(shared::AddToQueueRequest{
         account_id: account_id.to_string(),
         game_type: payload.game_type,
         ranked: payload.ranked,
-    }).await.expect("Failed to add player to queue.");
-
-    ack.send(&()).ok();
+        pools: payload.pools.into_iter().map(|pool| shared::QueuePool {
+            game_type: pool.game_type,
+            ranked: pool.ranked,
+        }).collect(),
+    }).await;
+
+    // Ranked queues reject players while no season is active, all queues while on a cooldown
+    match result {
+        Ok(_) => ack.send(&()).ok(),
+        Err(status) => ack.send(&serde_json::json!({ "error": status.message() })).ok(),
+    };
 }
 
 #[derive(Debug, Deserialize)]
@@ -77,12 +97,46 @@ pub async fn handle_accept_pending_game(
 
     let mut matchmaking_client = state.matchmaking_client.clone();
 
-    matchmaking_client.accept_pending_game(shared::AcceptPendingGameRequest {
+    let result = matchmaking_client.accept_pending_game(shared::AcceptPendingGameRequest {
         account_id: account_id.to_string(),
         pending_game_id: payload.pending_game_id,
-    }).await.expect("Failed to accept pending game.");
+    }).await;
 
-    ack.send(&()).ok();
+    match result {
+        Ok(_) => ack.send(&()).ok(),
+        Err(status) => ack.send(&serde_json::json!({ "error": status.message() })).ok(),
+    };
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeclinePendingGameDto {
+    #[serde(rename = "pendingGameId")]
+    pending_game_id: String,
+}
+
+pub async fn handle_decline_pending_game(
+    socket: SocketRef,
+    Data(payload): Data<DeclinePendingGameDto>,
+    ack: AckSender,
+    State(state): State<AppState>,
+) {
+    let account_id = socket.extensions.get::<UserClaims>()
+        .ok_or("Unauthorized")
+        .unwrap()
+        .uid;
+    println!("Decline pending game account_id: {}", account_id);
+
+    let mut matchmaking_client = state.matchmaking_client.clone();
+
+    let result = matchmaking_client.decline_pending_game(shared::DeclinePendingGameRequest {
+        account_id: account_id.to_string(),
+        pending_game_id: payload.pending_game_id,
+    }).await;
+
+    match result {
+        Ok(_) => ack.send(&()).ok(),
+        Err(status) => ack.send(&serde_json::json!({ "error": status.message() })).ok(),
+    };
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,6 +188,10 @@ pub async fn handle_join_game(
 pub struct SerializableQueueSize {
     pub normal: u32,
     pub ranked: u32,
+    #[serde(rename = "normalEstimatedWaitSeconds")]
+    pub normal_estimated_wait_seconds: Option<u32>,
+    #[serde(rename = "rankedEstimatedWaitSeconds")]
+    pub ranked_estimated_wait_seconds: Option<u32>,
 }
 
 pub async fn handle_join_lobby(
@@ -153,13 +211,20 @@ pub async fn handle_join_lobby(
     let mut serializable_sizes: HashMap<String, SerializableQueueSize> = HashMap::new();
 
     for (queue_name, queue_size) in queue_sizes {
-        let shared::QueueSize { normal, ranked } = queue_size;
+        let shared::QueueSize {
+            normal,
+            ranked,
+            normal_estimated_wait_seconds,
+            ranked_estimated_wait_seconds,
+        } = queue_size;
 
         serializable_sizes.insert(
             queue_name,
             SerializableQueueSize {
                 normal,
-                ranked
+                ranked,
+                normal_estimated_wait_seconds,
+                ranked_estimated_wait_seconds,
             }
         );
     }
@@ -13,6 +13,7 @@ pub async fn on_connect(socket: SocketRef) {
     socket.on("matchmaking:add-to-queue", crate::handlers::matchmaking::handle_add_to_queue);
     socket.on("matchmaking:remove-from-queue", crate::handlers::matchmaking::handle_remove_from_queue);
     socket.on("matchmaking:accept-pending-game", crate::handlers::matchmaking::handle_accept_pending_game);
+    socket.on("matchmaking:decline-pending-game", crate::handlers::matchmaking::handle_decline_pending_game);
     socket.on("matchmaking:join-game", crate::handlers::matchmaking::handle_join_game);
     socket.on("matchmaking:join-lobby", crate::handlers::matchmaking::handle_join_lobby);
     socket.on("matchmaking:leave-lobby", crate::handlers::matchmaking::handle_leave_lobby);
@@ -20,6 +21,8 @@ pub async fn on_connect(socket: SocketRef) {
     socket.on("game:make-move", crate::handlers::game::handle_make_move);
     socket.on("game:resign", crate::handlers::game::handle_resign);
     socket.on("game:check-result", crate::handlers::game::handle_check_result);
+    socket.on("game:offer-rematch", crate::handlers::game::handle_offer_rematch);
+    socket.on("game:accept-rematch", crate::handlers::game::handle_accept_rematch);
 
     socket.on("chat:send-message", crate::handlers::chat::handle_send_message);
 }
@@ -1,27 +1,42 @@
 use std::{str::from_utf8, sync::Arc};
 
-use async_nats::jetstream::{self, consumer::PullConsumer};
+use async_nats::jetstream::{self, consumer::{DeliverPolicy, PullConsumer}};
 use futures::StreamExt;
 use shared::events::GameEvent;
 use socketioxide::SocketIo;
 
 use crate::server::state::AppState;
 
+const CONSUMER_NAME: &str = "gateway-game-notifications";
+const LEGACY_CONSUMER_NAME: &str = "consumer";
+
 pub async fn game_consumer(state: Arc<AppState>, socket_io: Arc<SocketIo>) -> Result<(), async_nats::Error> {
     let jetstream = &state.jetstream;
 
     let stream_name = String::from("game-publisher");
 
-    let consumer: PullConsumer = jetstream
+    let stream = jetstream
         .create_stream(jetstream::stream::Config {
             name: stream_name,
             subjects: vec!["chessmart.game.>".into()],
             ..Default::default()
         })
-        .await?
-        // Then, on that `Stream` use method to create Consumer and bind to it too.
-        .create_consumer(jetstream::consumer::pull::Config {
-            durable_name: Some("consumer".into()),
+        .await?;
+
+    // The durable used to be named "consumer" and had no subject filter, so it also received the
+    // per-game event subjects. It is replaced rather than updated in place.
+    if stream.delete_consumer(LEGACY_CONSUMER_NAME).await.is_ok() {
+        println!("Deleted legacy game event consumer {}", LEGACY_CONSUMER_NAME);
+    }
+
+    // Then, on that `Stream` use method to create Consumer and bind to it too.
+    let consumer: PullConsumer = stream
+        .get_or_create_consumer(CONSUMER_NAME, jetstream::consumer::pull::Config {
+            durable_name: Some(CONSUMER_NAME.into()),
+            // Only the notification subjects, per-game event subjects are the game service's state
+            filter_subject: "chessmart.game.*".into(),
+            // Past notifications are of no use to the connected sockets
+            deliver_policy: DeliverPolicy::New,
             ..Default::default()
         })
         .await?;
@@ -30,7 +45,7 @@ pub async fn game_consumer(state: Arc<AppState>, socket_io: Arc<SocketIo>) -> Re
 
     // Attach to the messages iterator for the Consumer.
     // The iterator does its best to optimize retrieval of messages from the server.
-    let mut messages = consumer.messages().await?.take(10);
+    let mut messages = consumer.messages().await?;
 
     // Iterate over messages.
     while let Some(message) = messages.next().await {
@@ -122,5 +137,66 @@ async fn handle_game_event(event: GameEvent, socket_io: Arc<SocketIo>) {
                 .emit("game:game-over", &notification)
                 .await.expect("Failed to send game event");
         }
+        GameEvent::ChallengeUpdate(payload) => {
+            println!("Challenge update: {} {}", payload.challenge_id, payload.status);
+
+            let notification = serde_json::json!({
+                "challengeId": payload.challenge_id,
+                "challengerAccountId": payload.challenger_account_id,
+                "challengedAccountId": payload.challenged_account_id,
+                "acceptedBy": payload.accepted_by,
+                "gameType": payload.game_type,
+                "color": payload.color,
+                "ranked": payload.ranked,
+                "status": payload.status,
+                "expiresAt": payload.expires_at,
+                "gameId": payload.game_id,
+            });
+
+            // Open challenges only reach whoever followed the invite link once it is accepted
+            let recipients = std::iter::once(&payload.challenger_account_id)
+                .chain(payload.challenged_account_id.iter())
+                .chain(payload.accepted_by.iter().filter(|account_id| payload.challenged_account_id.as_ref() != Some(*account_id)));
+
+            for account_id in recipients {
+                socket_io
+                    .to(account_id.to_owned())
+                    .emit("game:challenge-update", &notification)
+                    .await.expect("Failed to send game event");
+            }
+        }
+        GameEvent::RematchUpdate(payload) => {
+            println!("Rematch update: {} {}", payload.game_id, payload.status);
+
+            let notification = serde_json::json!({
+                "gameId": payload.game_id,
+                "offeredBy": payload.offered_by,
+                "status": payload.status,
+                "expiresAt": payload.expires_at,
+                "newGameId": payload.new_game_id,
+            });
+
+            // Move both players' sockets from the finished game's room into the rematch
+            if let Some(new_game_id) = &payload.new_game_id {
+                for account_id in [&payload.account_id_0, &payload.account_id_1] {
+                    socket_io
+                        .to(account_id.to_owned())
+                        .join(new_game_id.to_owned())
+                        .await.expect("Failed to join rematch room");
+
+                    socket_io
+                        .to(account_id.to_owned())
+                        .leave(payload.game_id.to_owned())
+                        .await.expect("Failed to leave game room");
+                }
+            }
+
+            for account_id in [&payload.account_id_0, &payload.account_id_1] {
+                socket_io
+                    .to(account_id.to_owned())
+                    .emit("game:rematch-update", &notification)
+                    .await.expect("Failed to send game event");
+            }
+        }
     }
 }
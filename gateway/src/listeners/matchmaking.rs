@@ -15,7 +15,7 @@ pub async fn matchmaking_consumer(state: Arc<AppState>, socket_io: Arc<SocketIo>
     let consumer: PullConsumer = jetstream
         .create_stream(jetstream::stream::Config {
             name: stream_name,
-            subjects: vec!["chessmart.game.>".into()],
+            subjects: vec!["chessmart.matchmaking.>".into()],
             ..Default::default()
         })
         .await?
@@ -30,7 +30,7 @@ pub async fn matchmaking_consumer(state: Arc<AppState>, socket_io: Arc<SocketIo>
 
     // Attach to the messages iterator for the Consumer.
     // The iterator does its best to optimize retrieval of messages from the server.
-    let mut messages = consumer.messages().await?.take(10);
+    let mut messages = consumer.messages().await?;
 
     // Iterate over messages.
     while let Some(message) = messages.next().await {
@@ -107,6 +107,15 @@ async fn handle_matchmacking_event(event: MatchmakingEvent, socket_io: Arc<Socke
                     .emit("matchmaking:elo-change", &payload)
                     .await.expect("Failed to emit elo change event")
             }
+        },
+        MatchmakingEvent::PlayerStatusChanged(status_changed) => {
+            println!("Player status change: {} {} -> {}", status_changed.account_id, status_changed.previous_status, status_changed.status);
+
+            // Same payload as the status sent on connect
+            socket_io
+                .to(status_changed.account_id)
+                .emit("matchmaking:account-status-update", &status_changed.status)
+                .await.expect("Failed to emit account status update event")
         }
     }
 }
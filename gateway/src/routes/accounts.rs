@@ -1,9 +1,9 @@
-use axum::{extract::State, Json};
+use axum::{extract::{Path, Query, State}, Json};
 use prost_types::Timestamp;
-use shared::{GetAccountRankingResponse, primitives::TimestampExt};
+use shared::primitives::TimestampExt;
 use tracing::info;
 
-use crate::{dtos::response::{AccountResponseDto, MeResponseDto}, error::{AppResponseError, GatewayServiceError}, server::state::AppState, utils::claim::UserClaims};
+use crate::{dtos::{request::RatingHistoryQueryDto, response::{AccountResponseDto, MeResponseDto, QueuePoolResponseDto, RatingHistoryResponseDto, RatingResponseDto}}, error::{AppResponseError, GatewayServiceError}, server::state::AppState, utils::claim::UserClaims};
 
 #[utoipa::path(
     get,
@@ -35,9 +35,7 @@ pub async fn me(
         ).await?
         .into_inner();
 
-    let GetAccountRankingResponse {
-        ranked_mmr, ..
-    } = state
+    let ranking = state
         .ranking_client.clone()
         .get_account_ranking(
             shared::GetAccountRankingRequest { account_id: user.uid.to_string() }
@@ -49,7 +47,10 @@ pub async fn me(
         game_type,
         ranked,
         game_id,
-        ..
+        pools,
+        estimated_wait_seconds,
+        queue_cooldown_seconds,
+        queue_penalty_score,
     } = state
         .matchmaking_client.clone()
         .get_account_status(
@@ -74,11 +75,18 @@ pub async fn me(
         is_admin,
         created_at,
         last_login_at,
-        mmr: ranked_mmr as u64,
+        ratings: RatingResponseDto::ranked_ratings(ranking),
         status,
         game_type,
         ranked,
         game_id,
+        pools: pools.into_iter().map(|pool| QueuePoolResponseDto {
+            game_type: pool.game_type,
+            ranked: pool.ranked,
+        }).collect(),
+        estimated_wait_seconds,
+        queue_cooldown_seconds,
+        queue_penalty_score,
     };
 
     Ok(Json(response))
@@ -114,9 +122,7 @@ pub async fn get_account(
         ).await?
         .into_inner();
 
-    let GetAccountRankingResponse {
-        ranked_mmr, ..
-    } = state
+    let ranking = state
         .ranking_client.clone()
         .get_account_ranking(
             shared::GetAccountRankingRequest { account_id: account_id.to_string() }
@@ -139,8 +145,50 @@ pub async fn get_account(
         is_admin,
         created_at,
         last_login_at,
-        mmr: ranked_mmr as u64,
+        ratings: RatingResponseDto::ranked_ratings(ranking),
     };
 
     Ok(Json(response))
 }
+
+#[utoipa::path(
+    get,
+    path = "/accounts/:account_id/rating-history",
+    params(
+        ("account_id" = String, Path, description = "Account id"),
+        RatingHistoryQueryDto
+    ),
+    responses(
+        (status = 200, description = "Rating after each game, per rating category, with stats", body = [RatingHistoryResponseDto]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_rating_history(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+    Query(query): Query<RatingHistoryQueryDto>,
+    _user: UserClaims,
+) -> Result<Json<RatingHistoryResponseDto>, GatewayServiceError> {
+    info!("Get rating history user id: {}.", account_id);
+
+    let shared::GetRatingHistoryResponse {
+        histories,
+    } = state
+        .ranking_client.clone()
+        .get_rating_history(
+            shared::GetRatingHistoryRequest {
+                account_id,
+                ranked: query.ranked.unwrap_or(true),
+            }
+        ).await?
+        .into_inner();
+
+    Ok(Json(RatingHistoryResponseDto {
+        histories: histories
+            .into_iter()
+            .map(|(category, history)| (category, history.into()))
+            .collect(),
+    }))
+}
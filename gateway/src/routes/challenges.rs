@@ -0,0 +1,191 @@
+use axum::{extract::{Path, State}, Json};
+use tracing::info;
+
+use crate::{dtos::{request::CreateChallengeRequestDto, response::{AcceptChallengeResponseDto, ChallengeResponseDto, ChallengesResponseDto}}, error::{AppResponseError, GatewayServiceError}, server::state::AppState, utils::claim::UserClaims};
+
+fn into_response_dto(challenge: Option<shared::Challenge>) -> Result<ChallengeResponseDto, GatewayServiceError> {
+    challenge
+        .map(ChallengeResponseDto::from)
+        .ok_or_else(|| GatewayServiceError::GrpcError("Missing challenge in game service response".to_string()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/challenges",
+    request_body = CreateChallengeRequestDto,
+    responses(
+        (status = 200, description = "Challenge created, its id doubles as the invite link", body = [ChallengeResponseDto]),
+        (status = 400, description = "Invalid challenge", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn create_challenge(
+    State(state): State<AppState>,
+    user: UserClaims,
+    Json(req): Json<CreateChallengeRequestDto>,
+) -> Result<Json<ChallengeResponseDto>, GatewayServiceError> {
+    info!("Create challenge user id: {}.", user.uid);
+
+    let shared::CreateChallengeResponse { challenge } = state
+        .game_client.clone()
+        .create_challenge(
+            shared::CreateChallengeRequest {
+                account_id: user.uid.to_string(),
+                challenged_account_id: req.challenged_account_id,
+                game_type: req.game_type,
+                color: req.color.unwrap_or_default(),
+                ranked: req.ranked,
+            }
+        ).await?
+        .into_inner();
+
+    Ok(Json(into_response_dto(challenge)?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/challenges",
+    responses(
+        (status = 200, description = "Pending challenges sent or received by the user", body = [ChallengesResponseDto]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_challenges(
+    State(state): State<AppState>,
+    user: UserClaims,
+) -> Result<Json<ChallengesResponseDto>, GatewayServiceError> {
+    info!("Get challenges user id: {}.", user.uid);
+
+    let shared::GetChallengesResponse { challenges } = state
+        .game_client.clone()
+        .get_challenges(
+            shared::GetChallengesRequest { account_id: user.uid.to_string() }
+        ).await?
+        .into_inner();
+
+    Ok(Json(ChallengesResponseDto {
+        challenges: challenges.into_iter().map(ChallengeResponseDto::from).collect(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/challenges/{challenge_id}",
+    params(("challenge_id" = String, Path, description = "Challenge id")),
+    responses(
+        (status = 200, description = "Challenge behind an invite link", body = [ChallengeResponseDto]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_challenge(
+    State(state): State<AppState>,
+    user: UserClaims,
+    Path(challenge_id): Path<String>,
+) -> Result<Json<ChallengeResponseDto>, GatewayServiceError> {
+    info!("Get challenge {} user id: {}.", challenge_id, user.uid);
+
+    let shared::GetChallengeResponse { challenge } = state
+        .game_client.clone()
+        .get_challenge(
+            shared::GetChallengeRequest { challenge_id }
+        ).await?
+        .into_inner();
+
+    Ok(Json(into_response_dto(challenge)?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/challenges/{challenge_id}/accept",
+    params(("challenge_id" = String, Path, description = "Challenge id")),
+    responses(
+        (status = 200, description = "Challenge accepted and game created", body = [AcceptChallengeResponseDto]),
+        (status = 400, description = "Challenge can't be accepted", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn accept_challenge(
+    State(state): State<AppState>,
+    user: UserClaims,
+    Path(challenge_id): Path<String>,
+) -> Result<Json<AcceptChallengeResponseDto>, GatewayServiceError> {
+    info!("Accept challenge {} user id: {}.", challenge_id, user.uid);
+
+    let shared::AcceptChallengeResponse { challenge, game_repr } = state
+        .game_client.clone()
+        .accept_challenge(
+            shared::AcceptChallengeRequest { account_id: user.uid.to_string(), challenge_id }
+        ).await?
+        .into_inner();
+
+    Ok(Json(AcceptChallengeResponseDto {
+        challenge: into_response_dto(challenge)?,
+        game: game_repr,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/challenges/{challenge_id}/decline",
+    params(("challenge_id" = String, Path, description = "Challenge id")),
+    responses(
+        (status = 200, description = "Challenge declined", body = [ChallengeResponseDto]),
+        (status = 400, description = "Challenge can't be declined", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn decline_challenge(
+    State(state): State<AppState>,
+    user: UserClaims,
+    Path(challenge_id): Path<String>,
+) -> Result<Json<ChallengeResponseDto>, GatewayServiceError> {
+    info!("Decline challenge {} user id: {}.", challenge_id, user.uid);
+
+    let shared::DeclineChallengeResponse { challenge } = state
+        .game_client.clone()
+        .decline_challenge(
+            shared::DeclineChallengeRequest { account_id: user.uid.to_string(), challenge_id }
+        ).await?
+        .into_inner();
+
+    Ok(Json(into_response_dto(challenge)?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/challenges/{challenge_id}/cancel",
+    params(("challenge_id" = String, Path, description = "Challenge id")),
+    responses(
+        (status = 200, description = "Challenge cancelled", body = [ChallengeResponseDto]),
+        (status = 400, description = "Challenge can't be cancelled", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn cancel_challenge(
+    State(state): State<AppState>,
+    user: UserClaims,
+    Path(challenge_id): Path<String>,
+) -> Result<Json<ChallengeResponseDto>, GatewayServiceError> {
+    info!("Cancel challenge {} user id: {}.", challenge_id, user.uid);
+
+    let shared::CancelChallengeResponse { challenge } = state
+        .game_client.clone()
+        .cancel_challenge(
+            shared::CancelChallengeRequest { account_id: user.uid.to_string(), challenge_id }
+        ).await?
+        .into_inner();
+
+    Ok(Json(into_response_dto(challenge)?))
+}
@@ -0,0 +1,32 @@
+use axum::{extract::State, Json};
+use tracing::info;
+
+use crate::{dtos::response::MyTurnGamesResponseDto, error::{AppResponseError, GatewayServiceError}, server::state::AppState, utils::claim::UserClaims};
+
+#[utoipa::path(
+    get,
+    path = "/games/my-turn",
+    responses(
+        (status = 200, description = "Ongoing correspondence games waiting for the user's move", body = [MyTurnGamesResponseDto]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn my_turn_games(
+    State(state): State<AppState>,
+    user: UserClaims,
+) -> Result<Json<MyTurnGamesResponseDto>, GatewayServiceError> {
+    info!("Get my turn games user id: {}.", user.uid);
+
+    let shared::GetMyTurnGamesResponse {
+        game_reprs,
+    } = state
+        .game_client.clone()
+        .get_my_turn_games(
+            shared::GetMyTurnGamesRequest { account_id: user.uid.to_string() }
+        ).await?
+        .into_inner();
+
+    Ok(Json(MyTurnGamesResponseDto { games: game_reprs }))
+}
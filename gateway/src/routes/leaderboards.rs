@@ -0,0 +1,50 @@
+use axum::{extract::{Path, Query, State}, Json};
+use tracing::info;
+
+use crate::{dtos::{request::LeaderboardQueryDto, response::LeaderboardResponseDto}, error::{AppResponseError, GatewayServiceError}, server::state::AppState, utils::claim::UserClaims};
+
+const DEFAULT_LEADERBOARD_LIMIT: u32 = 10;
+
+#[utoipa::path(
+    get,
+    path = "/leaderboards/:category",
+    params(
+        ("category" = String, Path, description = "Rating category: bullet, blitz, rapid, classical or correspondence"),
+        LeaderboardQueryDto
+    ),
+    responses(
+        (status = 200, description = "Top ranked players of the category, and the players around the user", body = [LeaderboardResponseDto]),
+        (status = 400, description = "Unknown rating category", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_leaderboard(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+    Query(query): Query<LeaderboardQueryDto>,
+    user: UserClaims,
+) -> Result<Json<LeaderboardResponseDto>, GatewayServiceError> {
+    info!("Get {} leaderboard user id: {}.", category, user.uid);
+
+    let shared::GetLeaderboardResponse {
+        top,
+        around_me,
+    } = state
+        .ranking_client.clone()
+        .get_leaderboard(
+            shared::GetLeaderboardRequest {
+                category: category.clone(),
+                limit: query.limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT),
+                account_id: Some(user.uid.to_string()),
+            }
+        ).await?
+        .into_inner();
+
+    Ok(Json(LeaderboardResponseDto {
+        category,
+        top: top.into_iter().map(Into::into).collect(),
+        around_me: around_me.into_iter().map(Into::into).collect(),
+    }))
+}
@@ -1,3 +1,6 @@
 pub mod authentication;
 pub mod accounts;
+pub mod challenges;
+pub mod games;
+pub mod leaderboards;
 pub mod websocket;
@@ -11,7 +11,7 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 pub mod state;
 
-use crate::{ dtos::{request::{LoginRequestDto, RefreshTokenRequestDto, RegisterRequestDto}, response::{AccountResponseDto, LoginResponseDto, MeResponseDto, MessageResponseDto, RegisterResponseDto}}, routes::{accounts::{get_account, me}, authentication::{login, logout, post_register, refresh}, websocket::ws_handler}, server::state::AppState, utils::claim::UserClaims};
+use crate::{ dtos::{request::{CreateChallengeRequestDto, LeaderboardQueryDto, LoginRequestDto, RatingHistoryQueryDto, RefreshTokenRequestDto, RegisterRequestDto}, response::{AcceptChallengeResponseDto, AccountResponseDto, ChallengeResponseDto, ChallengesResponseDto, LeaderboardEntryDto, LeaderboardResponseDto, LoginResponseDto, MeResponseDto, QueuePoolResponseDto, MessageResponseDto, CategoryRatingHistoryDto, MyTurnGamesResponseDto, RatingHistoryPointDto, RatingHistoryResponseDto, RatingResponseDto, StreakDto, RegisterResponseDto}}, routes::{accounts::{get_account, get_rating_history, me}, authentication::{login, logout, post_register, refresh}, challenges::{accept_challenge, cancel_challenge, create_challenge, decline_challenge, get_challenge, get_challenges}, games::my_turn_games, leaderboards::get_leaderboard, websocket::ws_handler}, server::state::AppState, utils::claim::UserClaims};
 
 pub async fn run_server(state: AppState) -> anyhow::Result<()> {
 
@@ -43,17 +43,24 @@ pub async fn run_server(state: AppState) -> anyhow::Result<()> {
     let state_for_consumer = Arc::new(state.clone());
     let socket_io = Arc::new(io);
 
-    tokio::spawn(async move {
-        if let Err(e) = crate::listeners::game::game_consumer(
-            state_for_consumer.clone(),
-            socket_io.clone()
-        ).await {
-            eprintln!("Game Event consumer failed: {}", e);
+    tokio::spawn({
+        let state_for_consumer = state_for_consumer.clone();
+        let socket_io = socket_io.clone();
+
+        async move {
+            if let Err(e) = crate::listeners::game::game_consumer(
+                state_for_consumer,
+                socket_io
+            ).await {
+                eprintln!("Game Event consumer failed: {}", e);
+            }
         }
+    });
 
+    tokio::spawn(async move {
         if let Err(e) = crate::listeners::matchmaking::matchmaking_consumer(
-            state_for_consumer.clone(),
-            socket_io.clone()
+            state_for_consumer,
+            socket_io
         ).await {
             eprintln!("Matchmaking Event consumer failed: {}", e);
         }
@@ -87,6 +94,15 @@ pub async fn run_server(state: AppState) -> anyhow::Result<()> {
         crate::routes::authentication::logout,
         crate::routes::accounts::get_account,
         crate::routes::accounts::me,
+        crate::routes::accounts::get_rating_history,
+        crate::routes::games::my_turn_games,
+        crate::routes::leaderboards::get_leaderboard,
+        crate::routes::challenges::create_challenge,
+        crate::routes::challenges::get_challenges,
+        crate::routes::challenges::get_challenge,
+        crate::routes::challenges::accept_challenge,
+        crate::routes::challenges::decline_challenge,
+        crate::routes::challenges::cancel_challenge,
     ),
     components(schemas(
         RegisterRequestDto,
@@ -98,6 +114,21 @@ pub async fn run_server(state: AppState) -> anyhow::Result<()> {
         MessageResponseDto,
         AccountResponseDto,
         MeResponseDto,
+        QueuePoolResponseDto,
+        RatingResponseDto,
+        RatingHistoryQueryDto,
+        RatingHistoryPointDto,
+        StreakDto,
+        CategoryRatingHistoryDto,
+        RatingHistoryResponseDto,
+        MyTurnGamesResponseDto,
+        CreateChallengeRequestDto,
+        ChallengeResponseDto,
+        ChallengesResponseDto,
+        AcceptChallengeResponseDto,
+        LeaderboardQueryDto,
+        LeaderboardEntryDto,
+        LeaderboardResponseDto,
     ))
 )]
 struct ApiDoc;
@@ -118,6 +149,20 @@ fn app(state: AppState) -> Router {
         // Account routes
         .route("/accounts/me", get(me))
         .route("/accounts/:account_id", get(get_account))
+        .route("/accounts/:account_id/rating-history", get(get_rating_history))
+
+        // Game routes
+        .route("/games/my-turn", get(my_turn_games))
+
+        // Challenge routes
+        .route("/challenges", post(create_challenge).get(get_challenges))
+        .route("/challenges/:challenge_id", get(get_challenge))
+        .route("/challenges/:challenge_id/accept", post(accept_challenge))
+        .route("/challenges/:challenge_id/decline", post(decline_challenge))
+        .route("/challenges/:challenge_id/cancel", post(cancel_challenge))
+
+        // Leaderboard routes
+        .route("/leaderboards/:category", get(get_leaderboard))
 
         .with_state(state);
 
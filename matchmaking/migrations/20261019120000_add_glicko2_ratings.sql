@@ -0,0 +1,33 @@
+-- Replace the ranked and normal MMRs with Glicko-2 ratings
+ALTER TABLE rankings
+    ADD COLUMN ranked_rating DOUBLE PRECISION NOT NULL DEFAULT 1000,
+    ADD COLUMN ranked_deviation DOUBLE PRECISION NOT NULL DEFAULT 350,
+    ADD COLUMN ranked_volatility DOUBLE PRECISION NOT NULL DEFAULT 0.06,
+    ADD COLUMN ranked_rated_at TIMESTAMPTZ,
+    ADD COLUMN normal_rating DOUBLE PRECISION NOT NULL DEFAULT 1000,
+    ADD COLUMN normal_deviation DOUBLE PRECISION NOT NULL DEFAULT 350,
+    ADD COLUMN normal_volatility DOUBLE PRECISION NOT NULL DEFAULT 0.06,
+    ADD COLUMN normal_rated_at TIMESTAMPTZ;
+
+-- Existing MMRs carry over, their deviation shrinks with the number of games already played
+UPDATE rankings r
+SET ranked_rating = r.ranked_mmr,
+    normal_rating = r.normal_mmr,
+    ranked_deviation = GREATEST(45, 350 / sqrt(1 + COALESCE(ranked.games, 0))),
+    ranked_rated_at = ranked.last_played_at,
+    normal_deviation = GREATEST(45, 350 / sqrt(1 + COALESCE(normal.games, 0))),
+    normal_rated_at = normal.last_played_at
+FROM rankings r2
+LEFT JOIN (
+    SELECT account_id, COUNT(*) AS games, MAX(created_at) AS last_played_at
+    FROM mmr_changes WHERE is_ranked GROUP BY account_id
+) ranked ON ranked.account_id = r2.account_id
+LEFT JOIN (
+    SELECT account_id, COUNT(*) AS games, MAX(created_at) AS last_played_at
+    FROM mmr_changes WHERE NOT is_ranked GROUP BY account_id
+) normal ON normal.account_id = r2.account_id
+WHERE r.id = r2.id;
+
+ALTER TABLE rankings
+    DROP COLUMN ranked_mmr,
+    DROP COLUMN normal_mmr;
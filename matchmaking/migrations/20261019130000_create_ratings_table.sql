@@ -0,0 +1,58 @@
+-- Ratings per rating category (bullet, blitz, ...) and pool, created on the first rated game
+CREATE TABLE ratings (
+    id uuid NOT NULL,
+    account_id VARCHAR(255) NOT NULL,
+    category VARCHAR(255) NOT NULL,
+    is_ranked BOOLEAN NOT NULL,
+    rating DOUBLE PRECISION NOT NULL,
+    deviation DOUBLE PRECISION NOT NULL,
+    volatility DOUBLE PRECISION NOT NULL,
+    rated_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT current_timestamp,
+    updated_at TIMESTAMPTZ DEFAULT current_timestamp,
+    CONSTRAINT ratings_pkey PRIMARY KEY (id)
+);
+
+CREATE UNIQUE INDEX idx_ratings_account_id_category_is_ranked_unique_index
+ON ratings
+USING btree (account_id, category, is_ranked);
+
+-- Existing ratings carry over to every category the player has played, with a deviation
+-- based on the number of games played in that category
+INSERT INTO ratings (id, account_id, category, is_ranked, rating, deviation, volatility, rated_at)
+SELECT
+    gen_random_uuid(),
+    r.account_id,
+    c.category,
+    c.is_ranked,
+    CASE WHEN c.is_ranked THEN r.ranked_rating ELSE r.normal_rating END,
+    GREATEST(45, 350 / sqrt(1 + c.games)),
+    CASE WHEN c.is_ranked THEN r.ranked_volatility ELSE r.normal_volatility END,
+    c.last_played_at
+FROM rankings r
+JOIN (
+    SELECT
+        account_id,
+        CASE
+            WHEN game_type LIKE 'Bullet%' THEN 'bullet'
+            WHEN game_type LIKE 'Blitz%' THEN 'blitz'
+            WHEN game_type LIKE 'Rapid%' THEN 'rapid'
+            WHEN game_type LIKE 'Correspondence%' THEN 'correspondence'
+        END AS category,
+        is_ranked,
+        COUNT(*) AS games,
+        MAX(created_at) AS last_played_at
+    FROM mmr_changes
+    GROUP BY 1, 2, 3
+) c ON c.account_id = r.account_id
+WHERE c.category IS NOT NULL;
+
+ALTER TABLE rankings
+    DROP COLUMN ranked_rating,
+    DROP COLUMN ranked_deviation,
+    DROP COLUMN ranked_volatility,
+    DROP COLUMN ranked_rated_at,
+    DROP COLUMN normal_rating,
+    DROP COLUMN normal_deviation,
+    DROP COLUMN normal_volatility,
+    DROP COLUMN normal_rated_at;
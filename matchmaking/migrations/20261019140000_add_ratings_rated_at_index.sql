@@ -0,0 +1,4 @@
+-- Leaderboard rebuilds read the recently rated players of a pool
+CREATE INDEX idx_ratings_category_is_ranked_rated_at_index
+ON ratings
+USING btree (category, is_ranked, rated_at);
@@ -0,0 +1,17 @@
+-- Record the rating after each game and its result, to serve rating histories
+ALTER TABLE mmr_changes
+    ADD COLUMN category VARCHAR(255),
+    ADD COLUMN rating DOUBLE PRECISION,
+    ADD COLUMN result VARCHAR(255);
+
+UPDATE mmr_changes
+SET category = CASE
+    WHEN game_type LIKE 'Bullet%' THEN 'bullet'
+    WHEN game_type LIKE 'Blitz%' THEN 'blitz'
+    WHEN game_type LIKE 'Rapid%' THEN 'rapid'
+    WHEN game_type LIKE 'Correspondence%' THEN 'correspondence'
+END;
+
+CREATE INDEX idx_mmr_changes_account_id_created_at_index
+ON mmr_changes
+USING btree (account_id, created_at);
@@ -0,0 +1,53 @@
+CREATE TABLE seasons (
+    id uuid NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    closed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+    CONSTRAINT seasons_pkey PRIMARY KEY (id)
+);
+
+-- At most one season is open at a time
+CREATE UNIQUE INDEX idx_seasons_open_unique_index
+ON seasons ((closed_at IS NULL))
+WHERE closed_at IS NULL;
+
+-- Ranked ratings belong to a season, the ones from before the first season have none
+ALTER TABLE ratings ADD COLUMN season_id uuid REFERENCES seasons (id);
+
+DROP INDEX idx_ratings_account_id_category_is_ranked_unique_index;
+
+CREATE UNIQUE INDEX idx_ratings_account_id_category_is_ranked_season_unique_index
+ON ratings
+USING btree (account_id, category, is_ranked, season_id) NULLS NOT DISTINCT;
+
+ALTER TABLE mmr_changes ADD COLUMN season_id uuid;
+
+CREATE INDEX idx_mmr_changes_season_id_index
+ON mmr_changes
+USING btree (season_id);
+
+-- Final ratings of a season and the rewards they earned
+CREATE TABLE season_results (
+    id uuid NOT NULL,
+    season_id uuid NOT NULL REFERENCES seasons (id),
+    account_id VARCHAR(255) NOT NULL,
+    category VARCHAR(255) NOT NULL,
+    rating DOUBLE PRECISION NOT NULL,
+    deviation DOUBLE PRECISION NOT NULL,
+    games INTEGER NOT NULL,
+    -- Rank among the players who played enough games, none otherwise
+    rank INTEGER,
+    reward VARCHAR(255),
+    created_at TIMESTAMPTZ DEFAULT current_timestamp,
+    CONSTRAINT season_results_pkey PRIMARY KEY (id)
+);
+
+CREATE UNIQUE INDEX idx_season_results_season_id_account_id_category_unique_index
+ON season_results
+USING btree (season_id, account_id, category);
+
+CREATE INDEX idx_season_results_account_id_index
+ON season_results
+USING btree (account_id);
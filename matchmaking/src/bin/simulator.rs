@@ -0,0 +1,293 @@
+//! Runs a synthetic arrival process against a matchmaking queue and prints match statistics as
+//! JSON, so that runs can be compared across commits.
+//!
+//! The in-memory queue runs on a simulated clock and finishes as fast as it can match. The Redis
+//! queue runs in real time against a local Redis, which must not be used by a matcher meanwhile.
+//!
+//! cargo run -p matchmaking --release --bin simulator -- --players 10000 --initial-players 10000
+
+use std::{collections::HashMap, str::FromStr, sync::{atomic::{AtomicU64, Ordering}, Arc}, time::{Duration, Instant}};
+
+use clap::{Parser, ValueEnum};
+use matchmaking::{repositories::{in_memory_matchmaking_queue::InMemoryMatchmakingQueue, matchmaking_queue_repository::{MatchmakingQueueContract, QueueType, RedisMatchmakingQueue}}, services::{matching_strategy::{GreedyWindowStrategy, MatchingStrategy, LookaheadPairingStrategy}, queue_pool_service::PoolDefinition}};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use shared::primitives::GameType;
+
+/// Statuses of simulated players left behind by an interrupted run expire after an hour
+const STATUS_TTL_SECONDS: u64 = 3600;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Backend {
+    Memory,
+    Redis,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Strategy {
+    Greedy,
+    Lookahead,
+}
+
+#[derive(Debug, Clone, Parser)]
+struct SimulatorOpts {
+    #[clap(long, value_enum, default_value_t = Backend::Memory)]
+    backend: Backend,
+
+    /// Matching strategy of the in-memory queue, the Redis queue always runs `match-players.lua`
+    #[clap(long, value_enum, default_value_t = Strategy::Greedy)]
+    strategy: Strategy,
+
+    #[clap(long, default_value = "redis://127.0.0.1:6379")]
+    redis_url: String,
+
+    #[clap(long, default_value = "Blitz5_0")]
+    game_type: String,
+
+    #[clap(long)]
+    ranked: bool,
+
+    /// Players arriving over the run, including the initial ones
+    #[clap(long, default_value_t = 10_000)]
+    players: usize,
+
+    /// Players already waiting when the run starts
+    #[clap(long, default_value_t = 0)]
+    initial_players: usize,
+
+    /// Mean arrivals per second of the Poisson arrival process
+    #[clap(long, default_value_t = 50.0)]
+    arrival_rate: f64,
+
+    #[clap(long, default_value_t = 1500.0)]
+    mmr_mean: f64,
+
+    #[clap(long, default_value_t = 300.0)]
+    mmr_std_dev: f64,
+
+    /// Time between two runs of the matcher (ms)
+    #[clap(long, default_value_t = 1000)]
+    tick_ms: u64,
+
+    /// Most players read per invocation of the matcher
+    #[clap(long, default_value_t = 1000)]
+    batch_size: usize,
+
+    /// Most invocations of the matcher per tick
+    #[clap(long, default_value_t = 20)]
+    max_batches_per_tick: usize,
+
+    /// Time the matcher keeps running after the last arrival (s)
+    #[clap(long, default_value_t = 120)]
+    drain_seconds: u64,
+
+    #[clap(long, default_value_t = 42)]
+    seed: u64,
+}
+
+/// Time of the simulation, advanced by the simulator when simulated.
+enum SimulationClock {
+    Simulated(Arc<AtomicU64>),
+    Real(Instant),
+}
+
+impl SimulationClock {
+    fn now_ms(&self) -> u64 {
+        match self {
+            SimulationClock::Simulated(now_ms) => now_ms.load(Ordering::SeqCst),
+            SimulationClock::Real(started_at) => started_at.elapsed().as_millis() as u64,
+        }
+    }
+
+    async fn advance_to(&self, time_ms: u64) {
+        match self {
+            SimulationClock::Simulated(now_ms) => now_ms.store(time_ms, Ordering::SeqCst),
+            SimulationClock::Real(_) => {
+                let now_ms = self.now_ms();
+                if time_ms > now_ms {
+                    tokio::time::sleep(Duration::from_millis(time_ms - now_ms)).await;
+                }
+            }
+        }
+    }
+}
+
+struct Arrival {
+    account_id: String,
+    mmr: u16,
+    time_ms: u64,
+}
+
+/// Arrivals of a Poisson process with normally distributed mmrs.
+fn arrivals(opts: &SimulatorOpts) -> Vec<Arrival> {
+    let mut rng = StdRng::seed_from_u64(opts.seed);
+    let mut time_ms = 0.0;
+
+    (0..opts.players)
+        .map(|index| {
+            if index >= opts.initial_players {
+                time_ms += -(1.0 - rng.gen::<f64>()).ln() / opts.arrival_rate * 1000.0;
+            }
+
+            // Box-Muller transform
+            let normal = (-2.0 * (1.0 - rng.gen::<f64>()).ln()).sqrt() * (2.0 * std::f64::consts::PI * rng.gen::<f64>()).cos();
+
+            Arrival {
+                account_id: format!("simulator-{}-{}", opts.seed, index),
+                mmr: (opts.mmr_mean + normal * opts.mmr_std_dev).clamp(0.0, u16::MAX as f64) as u16,
+                time_ms: time_ms as u64,
+            }
+        })
+        .collect()
+}
+
+fn value_name(value: impl ValueEnum) -> String {
+    value.to_possible_value().map_or_else(String::new, |value| value.get_name().to_owned())
+}
+
+/// Nearest-rank percentiles of the values.
+fn percentiles(mut values: Vec<f64>) -> serde_json::Value {
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    let percentile = |percentile: f64| {
+        let rank = ((percentile * values.len() as f64).ceil() as usize).max(1);
+        values.get(rank - 1).copied()
+    };
+
+    serde_json::json!({
+        "count": values.len(),
+        "p50": percentile(0.5),
+        "p90": percentile(0.9),
+        "p99": percentile(0.99),
+        "max": values.last(),
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let opts = SimulatorOpts::parse();
+
+    let queue_type = QueueType {
+        game_type: GameType::from_str(&opts.game_type)?,
+        ranked: opts.ranked,
+    };
+    let queue_config = PoolDefinition::defaults()
+        .into_iter()
+        .find(|pool| pool.queue_type() == queue_type)
+        .ok_or_else(|| format!("No queue for game type {}", opts.game_type))?
+        .queue_config();
+
+    let (clock, queue): (SimulationClock, Box<dyn MatchmakingQueueContract>) = match opts.backend {
+        Backend::Memory => {
+            let now_ms = Arc::new(AtomicU64::new(0));
+            let clock_ms = now_ms.clone();
+            let strategy: Box<dyn MatchingStrategy> = match opts.strategy {
+                Strategy::Greedy => Box::new(GreedyWindowStrategy),
+                Strategy::Lookahead => Box::new(LookaheadPairingStrategy),
+            };
+
+            (
+                SimulationClock::Simulated(now_ms),
+                Box::new(InMemoryMatchmakingQueue::with_clock(strategy, Arc::new(move || clock_ms.load(Ordering::SeqCst)))),
+            )
+        }
+        Backend::Redis => (
+            SimulationClock::Real(Instant::now()),
+            Box::new(RedisMatchmakingQueue::new(redis::Client::open(opts.redis_url.as_str())?)),
+        ),
+    };
+
+    let arrivals = arrivals(&opts);
+    let last_arrival_ms = arrivals.last().map_or(0, |arrival| arrival.time_ms);
+    let mut next_arrival = 0;
+
+    // Arrival time and mmr of the players waiting
+    let mut waiting: HashMap<String, (u64, u16)> = HashMap::new();
+    let mut wait_times_ms = Vec::new();
+    let mut rating_gaps = Vec::new();
+    let mut script_latencies_ms = Vec::new();
+    let mut max_queue_size = 0;
+    let mut tick_ms = 0;
+
+    loop {
+        while next_arrival < arrivals.len() && arrivals[next_arrival].time_ms <= tick_ms {
+            let arrival = &arrivals[next_arrival];
+
+            clock.advance_to(arrival.time_ms).await;
+            queue.add_player_to_queue(&arrival.account_id, &[(queue_type, arrival.mmr)], STATUS_TTL_SECONDS).await?;
+            waiting.insert(arrival.account_id.clone(), (arrival.time_ms, arrival.mmr));
+            next_arrival += 1;
+        }
+
+        clock.advance_to(tick_ms).await;
+        max_queue_size = max_queue_size.max(waiting.len());
+
+        for _ in 0..opts.max_batches_per_tick {
+            let started_at = Instant::now();
+            // Players never meet again, so recent opponents don't matter
+            let batch = queue.match_players_in_queue(&queue_type.game_type, queue_type.ranked, &queue_config, 0, opts.batch_size).await?;
+            script_latencies_ms.push(started_at.elapsed().as_secs_f64() * 1000.0);
+
+            for pair in batch.matched_players.chunks_exact(2) {
+                let mut mmrs = [0; 2];
+
+                for (index, account_id) in pair.iter().enumerate() {
+                    queue.remove_player_from_queue(account_id).await?;
+
+                    if let Some((arrived_at, mmr)) = waiting.remove(account_id) {
+                        wait_times_ms.push(tick_ms.saturating_sub(arrived_at) as f64);
+                        mmrs[index] = mmr;
+                    }
+                }
+
+                rating_gaps.push(mmrs[0].abs_diff(mmrs[1]) as f64);
+            }
+
+            if batch.scan_complete {
+                break;
+            }
+        }
+
+        let arrivals_done = next_arrival == arrivals.len();
+        if arrivals_done && (waiting.len() < 2 || tick_ms >= last_arrival_ms + opts.drain_seconds * 1000) {
+            break;
+        }
+
+        tick_ms += opts.tick_ms;
+    }
+
+    // Players left over are removed, leaving a Redis queue as it was
+    for account_id in waiting.keys() {
+        queue.remove_player_from_queue(account_id).await?;
+    }
+
+    let report = serde_json::json!({
+        "config": {
+            "backend": value_name(opts.backend),
+            "strategy": match opts.backend {
+                Backend::Memory => value_name(opts.strategy),
+                Backend::Redis => "match-players.lua".to_owned(),
+            },
+            "queue": queue_type.pool_id(),
+            "players": opts.players,
+            "initial_players": opts.initial_players,
+            "arrival_rate": opts.arrival_rate,
+            "mmr_mean": opts.mmr_mean,
+            "mmr_std_dev": opts.mmr_std_dev,
+            "tick_ms": opts.tick_ms,
+            "batch_size": opts.batch_size,
+            "max_batches_per_tick": opts.max_batches_per_tick,
+            "seed": opts.seed,
+        },
+        "matched_players": wait_times_ms.len(),
+        "unmatched_players": waiting.len(),
+        "match_rate": if opts.players > 0 { wait_times_ms.len() as f64 / opts.players as f64 } else { 0.0 },
+        "max_queue_size": max_queue_size,
+        "wait_time_ms": percentiles(wait_times_ms),
+        "rating_gap": percentiles(rating_gaps),
+        "script_latency_ms": percentiles(script_latencies_ms),
+    });
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use shared::generated::game_service::game_service_client::GameServiceClient;
+use tonic::transport::Channel;
+
+use crate::config::GameServiceConfig;
+
+pub type GameGrpcClient = GameServiceClient<Channel>;
+
+#[derive(Clone)]
+pub struct GameClient {}
+
+impl GameClient {
+    /// Connects lazily, so matchmaking can start before the game service.
+    pub fn connect(config: &GameServiceConfig) -> Result<GameGrpcClient, shared::error::ChessmartApiError> {
+        let channel = Channel::from_shared(config.game_service_url.clone())
+            .map_err(|e| shared::error::ChessmartApiError::GrpcError(e.to_string()))?
+            .connect_lazy();
+
+        Ok(GameServiceClient::new(channel))
+    }
+}
@@ -1,3 +1,4 @@
 pub mod redis;
 pub mod database;
 pub mod nats;
+pub mod game;
@@ -18,6 +18,21 @@ pub struct Opts {
 
     #[clap(flatten)]
     pub nats: NatsConfig,
+
+    #[clap(flatten)]
+    pub matcher: MatcherConfig,
+
+    #[clap(flatten)]
+    pub game_service: GameServiceConfig,
+
+    #[clap(flatten)]
+    pub leaderboard: LeaderboardConfig,
+
+    #[clap(flatten)]
+    pub season: SeasonConfig,
+
+    #[clap(flatten)]
+    pub player_status: PlayerStatusConfig,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -54,6 +69,11 @@ pub struct RedisConfig {
 
     #[clap(long, env = "REDIS_DATABASE_NAME")]
     pub database_name: String,
+
+    /// Fails every script and transaction whose keys would land in different Redis Cluster slots,
+    /// to catch a broken key layout on a single Redis
+    #[clap(long, env = "REDIS_CHECK_KEY_SLOTS")]
+    pub check_key_slots: bool,
 }
 
 impl Default for RedisConfig {
@@ -64,6 +84,7 @@ impl Default for RedisConfig {
             port: 6379,
             host: "127.0.0.1".to_owned(),
             database_name: "".to_owned(),
+            check_key_slots: false,
         }
     }
 }
@@ -112,6 +133,11 @@ pub struct ApiConfig {
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
     pub nats: NatsConfig,
+    pub matcher: MatcherConfig,
+    pub game_service: GameServiceConfig,
+    pub leaderboard: LeaderboardConfig,
+    pub season: SeasonConfig,
+    pub player_status: PlayerStatusConfig,
 }
 
 impl From<Opts> for ApiConfig {
@@ -122,6 +148,11 @@ impl From<Opts> for ApiConfig {
             database: opts.database,
             redis: opts.redis,
             nats: opts.nats,
+            matcher: opts.matcher,
+            game_service: opts.game_service,
+            leaderboard: opts.leaderboard,
+            season: opts.season,
+            player_status: opts.player_status,
         }
     }
 }
@@ -142,6 +173,11 @@ impl ApiConfig {
         tracing: Option<TracingConfig>,
         redis: RedisConfig,
         nats: NatsConfig,
+        matcher: MatcherConfig,
+        game_service: GameServiceConfig,
+        leaderboard: LeaderboardConfig,
+        season: SeasonConfig,
+        player_status: PlayerStatusConfig,
     ) -> Self {
         Self {
             server,
@@ -149,6 +185,11 @@ impl ApiConfig {
             tracing,
             redis,
             nats,
+            matcher,
+            game_service,
+            leaderboard,
+            season,
+            player_status,
         }
     }
 }
@@ -183,3 +224,166 @@ impl NatsConfig {
     )
   }
 }
+
+#[derive(Debug, Clone, Parser)]
+pub struct MatcherConfig {
+    /// How often every queue is matched (ms)
+    #[clap(long, default_value_t = 1000, env = "MATCHER_TICK_MS")]
+    pub tick_ms: u64,
+
+    /// How long matched players have to accept their pending game
+    #[clap(long, default_value_t = 20, env = "PENDING_GAME_TIMEOUT_SECONDS")]
+    pub pending_game_timeout_seconds: u64,
+
+    /// How long matched players are kept as each other's recent opponent
+    #[clap(long, default_value_t = 900, env = "RECENT_OPPONENT_TTL_SECONDS")]
+    pub recent_opponent_ttl_seconds: u64,
+
+    /// How long both players have to wait in the queue before they can be matched with a recent
+    /// opponent again
+    #[clap(long, default_value_t = 60, env = "REPEAT_OPPONENT_WAIT_SECONDS")]
+    pub repeat_opponent_wait_seconds: u64,
+
+    /// Most players a single run of `match-players.lua` reads, bounding the time it blocks Redis
+    #[clap(long, default_value_t = 1000, env = "MATCH_BATCH_SIZE")]
+    pub match_batch_size: usize,
+
+    /// Most batches of a queue matched per tick, the scan continues on the next tick
+    #[clap(long, default_value_t = 20, env = "MAX_MATCH_BATCHES_PER_TICK")]
+    pub max_match_batches_per_tick: usize,
+
+    /// How long a matcher holds a queue's lock for a batch, the lock is extended before every
+    /// batch so it covers the slowest batch and its pending games
+    #[clap(long, default_value_t = 30000, env = "QUEUE_LOCK_LEASE_MS")]
+    pub queue_lock_lease_ms: u64,
+
+    /// Time after which the weight of a declined, timed out or abandoned game has halved
+    #[clap(long, default_value_t = 3600, env = "QUEUE_PENALTY_HALF_LIFE_SECONDS")]
+    pub queue_penalty_half_life_seconds: u64,
+
+    /// Queue cooldown after a second recent offense, doubling with every further one
+    #[clap(long, default_value_t = 60, env = "QUEUE_COOLDOWN_BASE_SECONDS")]
+    pub queue_cooldown_base_seconds: u64,
+
+    #[clap(long, default_value_t = 1800, env = "QUEUE_COOLDOWN_MAX_SECONDS")]
+    pub queue_cooldown_max_seconds: u64,
+
+    /// JSON file defining the queue pools, relative to the matchmaking crate. The built-in pools
+    /// are used without one
+    #[clap(long, env = "QUEUE_POOLS_PATH")]
+    pub queue_pools_path: Option<String>,
+}
+
+impl Default for MatcherConfig {
+    fn default() -> Self {
+        Self {
+            tick_ms: 1000,
+            pending_game_timeout_seconds: 20,
+            recent_opponent_ttl_seconds: 900,
+            repeat_opponent_wait_seconds: 60,
+            match_batch_size: 1000,
+            max_match_batches_per_tick: 20,
+            queue_lock_lease_ms: 30000,
+            queue_penalty_half_life_seconds: 3600,
+            queue_cooldown_base_seconds: 60,
+            queue_cooldown_max_seconds: 1800,
+            queue_pools_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct GameServiceConfig {
+    #[clap(long, default_value = "http://[::1]:50053", env = "GAME_SERVICE_URL")]
+    pub game_service_url: String,
+}
+
+impl Default for GameServiceConfig {
+    fn default() -> Self {
+        Self {
+            game_service_url: "http://[::1]:50053".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct LeaderboardConfig {
+    /// Players who haven't played a ranked game for this long are left out of leaderboards
+    #[clap(long, default_value_t = 30, env = "LEADERBOARD_ACTIVE_DAYS")]
+    pub active_days: i64,
+
+    /// How often leaderboards are rebuilt from Postgres, to repair any drift
+    #[clap(long, default_value_t = 86400, env = "LEADERBOARD_REBUILD_INTERVAL_SECONDS")]
+    pub rebuild_interval_seconds: u64,
+}
+
+impl Default for LeaderboardConfig {
+    fn default() -> Self {
+        Self {
+            active_days: 30,
+            rebuild_interval_seconds: 86400,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct SeasonConfig {
+    /// Share of the distance to the mean rating a player loses when a season starts
+    #[clap(long, default_value_t = 0.5, env = "SEASON_SOFT_RESET_FACTOR")]
+    pub soft_reset_factor: f64,
+
+    /// Minimum deviation after a soft reset, so players settle quickly into the new season
+    #[clap(long, default_value_t = 150.0, env = "SEASON_RESET_DEVIATION")]
+    pub reset_deviation: f64,
+
+    /// Ranked games of a category a player has to play in a season to be ranked and rewarded
+    #[clap(long, default_value_t = 10, env = "SEASON_REWARD_MIN_GAMES")]
+    pub reward_min_games: i64,
+}
+
+impl Default for SeasonConfig {
+    fn default() -> Self {
+        Self {
+            soft_reset_factor: 0.5,
+            reset_deviation: 150.0,
+            reward_min_games: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct PlayerStatusConfig {
+    /// How long a player stays searching without being matched or leaving the queues
+    #[clap(long, default_value_t = 86400, env = "PLAYER_STATUS_SEARCHING_TTL_SECONDS")]
+    pub searching_ttl_seconds: u64,
+
+    /// How long a player stays pending if their pending game is never timed out, longer than the
+    /// accept window
+    #[clap(long, default_value_t = 300, env = "PLAYER_STATUS_PENDING_TTL_SECONDS")]
+    pub pending_ttl_seconds: u64,
+
+    /// How long a player stays playing if the result of their game never arrives, longer than the
+    /// longest real-time game
+    #[clap(long, default_value_t = 21600, env = "PLAYER_STATUS_PLAYING_TTL_SECONDS")]
+    pub playing_ttl_seconds: u64,
+
+    /// How often the games of playing players are checked, to free players whose game is gone
+    #[clap(long, default_value_t = 60, env = "PLAYER_STATUS_RECONCILE_INTERVAL_SECONDS")]
+    pub reconcile_interval_seconds: u64,
+
+    /// Statuses read from Redis at once while reconciling
+    #[clap(long, default_value_t = 500, env = "PLAYER_STATUS_RECONCILE_BATCH_SIZE")]
+    pub reconcile_batch_size: usize,
+}
+
+impl Default for PlayerStatusConfig {
+    fn default() -> Self {
+        Self {
+            searching_ttl_seconds: 86400,
+            pending_ttl_seconds: 300,
+            playing_ttl_seconds: 21600,
+            reconcile_interval_seconds: 60,
+            reconcile_batch_size: 500,
+        }
+    }
+}
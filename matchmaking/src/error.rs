@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::repositories::matchmaking_queue_repository::PlayerStatus;
+
 #[derive(Error, Debug)]
 pub enum MatchmakingServiceError {
     #[error("DB Error {0}")]
@@ -11,12 +13,55 @@ pub enum MatchmakingServiceError {
     #[error("{0}")]
     UnknownGameTypeError(String),
 
+    #[error("{0}")]
+    PendingGameError(String),
+
+    #[error("Invalid game result {0}")]
+    InvalidGameResult(String),
+
+    #[error("Ratings of game {0} kept changing while applying its result")]
+    RankingUpdateConflict(String),
+
+    #[error("Ranked queues are closed until the next season starts")]
+    NoActiveSeasonError,
+
+    #[error("Queue cooldown after declined or abandoned games, {remaining_seconds} seconds remaining")]
+    QueueCooldownError { remaining_seconds: u64 },
+
+    #[error("Player can't go from {} to {}", .from.as_str(), .to.as_str())]
+    PlayerStatusTransitionError { from: PlayerStatus, to: PlayerStatus },
+
+    #[error("{0}")]
+    SeasonError(String),
+
+    #[error("Invalid queue pools: {0}")]
+    QueuePoolConfigError(String),
+
+    #[error("gRPC error: {0}")]
+    GrpcError(String),
+
+    #[error("Stream error: {0}")]
+    StreamError(String),
+
     #[error("Invalid quote uuid {0}")]
     InvalidUuid(#[from] uuid::Error),
 }
 
 impl From<MatchmakingServiceError> for tonic::Status {
     fn from(error: MatchmakingServiceError) -> Self {
-        tonic::Status::invalid_argument(error.to_string())
+        match error {
+            MatchmakingServiceError::QueueCooldownError { remaining_seconds } => {
+                let mut status = tonic::Status::failed_precondition(error.to_string());
+                status.metadata_mut().insert("queue-cooldown-remaining-seconds", remaining_seconds.into());
+                status
+            }
+            _ => tonic::Status::invalid_argument(error.to_string()),
+        }
+    }
+}
+
+impl From<tonic::Status> for MatchmakingServiceError {
+    fn from(status: tonic::Status) -> Self {
+        MatchmakingServiceError::GrpcError(status.message().to_string())
     }
 }
@@ -0,0 +1,7 @@
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod listeners;
+pub mod repositories;
+pub mod services;
+pub mod state;
@@ -0,0 +1,94 @@
+use std::{str::FromStr, sync::Arc};
+
+use async_nats::jetstream::{self, consumer::PullConsumer};
+use futures::StreamExt;
+use shared::{events::GameEvent, primitives::GameType};
+
+use crate::{client::nats::NatsJetstreamContext, error::MatchmakingServiceError, services::{color_service::ColorService, matchmaking_queue_service::MatchmakingQueueService, player_status_service::PlayerStatusServiceContract}};
+
+/// Switches players to `playing` when their game starts and records the colors they got. Games
+/// that don't go through the queue, such as challenges and rematches, are only known to
+/// matchmaking through this event. Their statuses are reset once the game result is applied, see
+/// [`crate::listeners::ranking`].
+pub async fn game_consumer(
+    jetstream: NatsJetstreamContext,
+    matchmaking_queue_service: Arc<MatchmakingQueueService>,
+    player_status_service: Arc<dyn PlayerStatusServiceContract>,
+    color_service: Arc<ColorService>,
+) -> Result<(), async_nats::Error> {
+    let consumer: PullConsumer = jetstream
+        .get_or_create_stream(jetstream::stream::Config {
+            name: String::from("game-publisher"),
+            subjects: vec!["chessmart.game.>".into()],
+            ..Default::default()
+        })
+        .await?
+        .get_or_create_consumer("matchmaking-player-status", jetstream::consumer::pull::Config {
+            durable_name: Some("matchmaking-player-status".into()),
+            filter_subject: "chessmart.game.game-start".into(),
+            ..Default::default()
+        })
+        .await?;
+
+    println!("Listening for game events...");
+
+    let mut messages = consumer.messages().await?;
+
+    while let Some(message) = messages.next().await {
+        let message = message?;
+
+        let event = match serde_json::from_slice::<GameEvent>(&message.payload) {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!("Skipping malformed game event on {}: {}", message.subject, e);
+                message.ack().await?;
+                continue;
+            }
+        };
+
+        match handle_game_event(event, &matchmaking_queue_service, player_status_service.as_ref(), &color_service).await {
+            // Left unacknowledged otherwise, so the event is redelivered
+            Ok(()) => message.ack().await?,
+            Err(e) => tracing::error!("Failed to handle game event on {}: {}", message.subject, e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_game_event(
+    event: GameEvent,
+    matchmaking_queue_service: &MatchmakingQueueService,
+    player_status_service: &dyn PlayerStatusServiceContract,
+    color_service: &ColorService,
+) -> Result<(), MatchmakingServiceError> {
+    let GameEvent::GameStart(game_start) = event else {
+        return Ok(());
+    };
+
+    let game_type = GameType::from_str(&game_start.game_type)
+        .map_err(|e| MatchmakingServiceError::UnknownGameTypeError(e.to_string()))?;
+
+    color_service.record_game_start(&game_start).await?;
+
+    // Correspondence games don't keep their players at the board
+    if game_type.is_correspondence() {
+        return Ok(());
+    }
+
+    for account_id in [&game_start.account_id_0, &game_start.account_id_1] {
+        // A player may still be queued, e.g. when accepting a challenge while searching
+        matchmaking_queue_service.remove_player_from_queue(account_id).await?;
+
+        player_status_service.set_player_playing(
+            account_id,
+            &game_start.game_id,
+            &game_type,
+            game_start.ranked,
+        ).await?;
+    }
+
+    tracing::debug!("Players of game {} are now playing", game_start.game_id);
+
+    Ok(())
+}
@@ -0,0 +1,3 @@
+pub mod game;
+pub mod queue_pools;
+pub mod ranking;
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+
+use crate::{client::nats::NatsJetstreamContext, services::{queue_pool_service::QueuePoolService, streaming_service::QUEUE_POOLS_RELOAD_SUBJECT}};
+
+/// Reloads the queue pools of this replica whenever the `ReloadQueuePools` admin RPC is served by
+/// any replica. A replica keeps its current pools if its pools file is invalid.
+pub async fn queue_pools_reload_consumer(
+    jetstream: NatsJetstreamContext,
+    queue_pool_service: Arc<QueuePoolService>,
+) -> Result<(), async_nats::Error> {
+    let mut subscriber = jetstream.client().subscribe(QUEUE_POOLS_RELOAD_SUBJECT).await?;
+
+    println!("Listening for queue pool reloads...");
+
+    while subscriber.next().await.is_some() {
+        if let Err(e) = queue_pool_service.reload() {
+            tracing::error!("Failed to reload queue pools: {}", e);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use async_nats::jetstream::{self, consumer::PullConsumer};
+use futures::StreamExt;
+use shared::events::{GameEvent, GameOverEvent};
+
+use crate::{error::MatchmakingServiceError, services::{leaderboard_service::LeaderboardService, player_status_service::PlayerStatusServiceContract, queue_penalty_service::{QueueOffense, QueuePenaltyService}, ranking_service::RankingServiceContract}, state::state::AppState};
+
+/// Updates the players' rankings and leaderboards with the result of every game, then frees them
+/// for matchmaking. A player who abandoned the game is penalized.
+pub async fn game_over_consumer(
+    state: AppState,
+    ranking_service: Arc<dyn RankingServiceContract>,
+    leaderboard_service: Arc<LeaderboardService>,
+    player_status_service: Arc<dyn PlayerStatusServiceContract>,
+    queue_penalty_service: Arc<QueuePenaltyService>,
+) -> Result<(), async_nats::Error> {
+    let consumer: PullConsumer = state
+        .jetstream
+        .get_or_create_stream(jetstream::stream::Config {
+            name: String::from("game-publisher"),
+            subjects: vec!["chessmart.game.>".into()],
+            ..Default::default()
+        })
+        .await?
+        .get_or_create_consumer("matchmaking-rankings", jetstream::consumer::pull::Config {
+            durable_name: Some("matchmaking-rankings".into()),
+            filter_subject: "chessmart.game.game-over".into(),
+            ..Default::default()
+        })
+        .await?;
+
+    println!("Listening for game results...");
+
+    let mut messages = consumer.messages().await?;
+
+    while let Some(message) = messages.next().await {
+        let message = message?;
+
+        let game_over = match serde_json::from_slice::<GameEvent>(&message.payload) {
+            Ok(GameEvent::GameOver(game_over)) => game_over,
+            Ok(_) => {
+                message.ack().await?;
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Skipping malformed game event on {}: {}", message.subject, e);
+                message.ack().await?;
+                continue;
+            }
+        };
+
+        match handle_game_over(&state, &game_over, ranking_service.as_ref(), &leaderboard_service, player_status_service.as_ref(), &queue_penalty_service).await {
+            // Left unacknowledged otherwise, so the result is redelivered
+            Ok(()) => message.ack().await?,
+            Err(e) => tracing::error!("Failed to apply result of game {}: {}", game_over.game_id, e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_game_over(
+    state: &AppState,
+    game_over: &GameOverEvent,
+    ranking_service: &dyn RankingServiceContract,
+    leaderboard_service: &LeaderboardService,
+    player_status_service: &dyn PlayerStatusServiceContract,
+    queue_penalty_service: &QueuePenaltyService,
+) -> Result<(), MatchmakingServiceError> {
+    let updates = ranking_service.apply_game_result(game_over).await?;
+
+    // Handling may have failed after the result was applied, so the leaderboards and players are
+    // brought up to date with the recorded changes
+    if updates.is_empty() {
+        tracing::debug!("Result of game {} was already applied", game_over.game_id);
+
+        for change in ranking_service.find_applied_changes(&game_over.game_id).await? {
+            let rating = ranking_service.get_or_create_ranking(&change.account_id).await?.rating(change.pool);
+
+            leaderboard_service.update_player_rating(&change.account_id, change.pool, &rating).await?;
+
+            crate::services::streaming_service::emit_elo_change(state, (&change).into()).await?;
+        }
+    }
+
+    for update in &updates {
+        tracing::debug!(
+            "Game {}: {} {:+} MMR, now {}",
+            game_over.game_id,
+            update.account_id,
+            update.mmr_change,
+            update.rating.mmr()
+        );
+
+        leaderboard_service.update_player_rating(&update.account_id, update.pool, &update.rating).await?;
+
+        crate::services::streaming_service::emit_elo_change(state, update.into()).await?;
+    }
+
+    for account_id in [&game_over.account_id_0, &game_over.account_id_1] {
+        player_status_service.clear_player_game(account_id, &game_over.game_id).await?;
+    }
+
+    // Keyed by the game, so a redelivered result doesn't penalize the player twice
+    if let Some(account_id) = &game_over.abandoned_account_id {
+        queue_penalty_service.record_offense(account_id, QueueOffense::Abandon, &game_over.game_id).await?;
+    }
+
+    Ok(())
+}
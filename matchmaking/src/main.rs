@@ -1,32 +1,31 @@
 use std::{str::FromStr, sync::Arc};
 
 use opentelemetry_otlp::WithExportConfig;
-use shared::{AcceptPendingGameRequest, AcceptPendingGameResponse, AddToQueueRequestPb, AddToQueueResponse, GetAccountRankingRequest, GetAccountRankingResponse, GetAccountStatusRequest, GetAccountStatusResponse, GetQueueSizesRequest, GetQueueSizesResponse, MatchmakerService, MatchmakerServiceServer, RankingService, RankingServiceServer, RemoveFromQueueRequest, RemoveFromQueueResponse, primitives::GameType};
+use shared::{MatchmakingAdminService, MatchmakingAdminServiceServer, QueuePoolDefinition, ReloadQueuePoolsRequest, ReloadQueuePoolsResponse, CloseSeasonRequest, CloseSeasonResponse, GetActiveSeasonRequest, GetActiveSeasonResponse, OpenSeasonRequest, OpenSeasonResponse, Season as SeasonPb, SeasonAdminService, SeasonAdminServiceServer, AcceptPendingGameRequest, CategoryRatings, GetLeaderboardRequest, GetLeaderboardResponse, GetRatingHistoryRequest, GetRatingHistoryResponse, LeaderboardEntry as LeaderboardEntryPb, PlayerRating, RatingHistory as RatingHistoryPb, RatingHistoryPoint as RatingHistoryPointPb, AcceptPendingGameResponse, DeclinePendingGameRequest, DeclinePendingGameResponse, AddToQueueRequestPb, AddToQueueResponse, GetAccountRankingRequest, GetAccountRankingResponse, GetAccountStatusRequest, GetAccountStatusResponse, GetQueueSizesRequest, GetQueueSizesResponse, MatchmakerService, QueuePool, MatchmakerServiceServer, RankingService, RankingServiceServer, RemoveFromQueueRequest, RemoveFromQueueResponse, primitives::{GameType, RatingCategory}};
 use tonic::transport::Server;
 use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
 
-use crate::{config::{ApiConfig, TracingConfig}, repositories::{matchmaking_queue_repository::RedisMatchmakingQueue, player_status_repository::PlayerStatusRepositoryService, ranking_repository::RankingRepositoryService}, state::state::{AppState, AppStateBuilder}, services::{matchmaking_queue_service::{AddToQueue, MatchmakingQueueService}, player_status_service::{MatchMakingStatus, PlayerStatusService, PlayerStatusServiceContract}, ranking_service::{MyRankingService, Ranking, RankingServiceContract}}};
-
-pub mod services;
-mod config;
-mod state;
-mod repositories;
-mod client;
-mod error;
+use matchmaking::{config::{ApiConfig, MatcherConfig, TracingConfig}, error::MatchmakingServiceError, repositories::{color_history_repository::RedisColorHistoryRepository, leaderboard_repository::RedisLeaderboardRepository, matchmaking_queue_repository::{PlayerStatus, QueueType, RedisMatchmakingQueue}, pending_game_repository::RedisPendingGameRepository, player_status_repository::PlayerStatusRepositoryService, queue_penalty_repository::RedisQueuePenaltyRepository, ranking_repository::RankingRepositoryService, redis_keys, season_repository::SeasonRepositoryService, wait_time_repository::RedisWaitTimeRepository}, state::{leaderboard_rebuilder::LeaderboardRebuilder, matcher::Matcher, player_status_reconciler::PlayerStatusReconciler, state::{AppState, AppStateBuilder}}, services::{color_service::ColorService, leaderboard_service::{Leaderboard, LeaderboardEntry, LeaderboardService}, matchmaking_queue_service::{AddToQueue, MatchmakingQueueService}, pending_game_service::PendingGameService, player_status_service::{MatchMakingStatus, PlayerStatusService, PlayerStatusServiceContract}, queue_penalty_service::{QueuePenaltyPolicy, QueuePenaltyService}, queue_pool_service::{PoolDefinition, QueuePoolService}, ranking_service::{MyRankingService, Ranking, RankingServiceContract, RatingPool}, rating_history::RatingHistory, season_service::{Season, SeasonService}}};
 
 pub struct MatchmakerGatewayService {
     matchmaking_queue_service: MatchmakingQueueService,
     player_status_service: PlayerStatusService,
+    pending_game_service: Arc<PendingGameService>,
+    queue_penalty_service: Arc<QueuePenaltyService>,
 }
 
 impl MatchmakerGatewayService {
     pub fn new(
         matchmaking_queue_service: MatchmakingQueueService,
         player_status_service: PlayerStatusService,
+        pending_game_service: Arc<PendingGameService>,
+        queue_penalty_service: Arc<QueuePenaltyService>,
     ) -> Self {
         Self {
             matchmaking_queue_service,
             player_status_service,
+            pending_game_service,
+            queue_penalty_service,
         }
     }
 }
@@ -41,27 +40,58 @@ impl MatchmakerService for MatchmakerGatewayService {
         let AddToQueueRequestPb {
             account_id,
             ranked,
-            game_type
+            game_type,
+            pools,
         } = request.into_inner();
 
-        let game_type = GameType::from_str(&game_type)?;
+        let pools = if pools.is_empty() {
+            vec![QueueType { game_type: GameType::from_str(&game_type)?, ranked }]
+        } else {
+            pools.into_iter()
+                .map(|pool| Ok(QueueType {
+                    game_type: GameType::from_str(&pool.game_type)
+                        .map_err(|e| MatchmakingServiceError::UnknownGameTypeError(e.to_string()))?,
+                    ranked: pool.ranked,
+                }))
+                .collect::<Result<Vec<_>, MatchmakingServiceError>>()?
+        };
 
         self.matchmaking_queue_service.add_player_to_queue(
             AddToQueue {
                 account_id,
-                game_type,
-                ranked
+                pools,
             }
-        ).await.unwrap();
+        ).await?;
 
         Ok(tonic::Response::new(AddToQueueResponse{}))
     }
 
     async fn accept_pending_game(
         &self,
-        _request: tonic::Request<AcceptPendingGameRequest>,
+        request: tonic::Request<AcceptPendingGameRequest>,
     ) -> Result<tonic::Response<AcceptPendingGameResponse>, tonic::Status> {
-        todo!()
+        let AcceptPendingGameRequest {
+            account_id,
+            pending_game_id,
+        } = request.into_inner();
+
+        self.pending_game_service.accept_pending_game(&account_id, &pending_game_id).await?;
+
+        Ok(tonic::Response::new(AcceptPendingGameResponse{}))
+    }
+
+    async fn decline_pending_game(
+        &self,
+        request: tonic::Request<DeclinePendingGameRequest>,
+    ) -> Result<tonic::Response<DeclinePendingGameResponse>, tonic::Status> {
+        let DeclinePendingGameRequest {
+            account_id,
+            pending_game_id,
+        } = request.into_inner();
+
+        self.pending_game_service.decline_pending_game(&account_id, &pending_game_id).await?;
+
+        Ok(tonic::Response::new(DeclinePendingGameResponse{}))
     }
 
     async fn remove_from_queue(
@@ -90,6 +120,7 @@ impl MatchmakerService for MatchmakerGatewayService {
             game_type,
             game_id,
             ranked,
+            pools,
         } = self.player_status_service.get_player_status(&account_id).await?;
 
         let game_type = match game_type {
@@ -97,12 +128,26 @@ impl MatchmakerService for MatchmakerGatewayService {
             None => None
         };
 
+        let estimated_wait_seconds = match status {
+            PlayerStatus::Searching => self.matchmaking_queue_service.estimate_player_wait_seconds(&account_id, &pools).await?,
+            _ => None,
+        };
+
+        let (queue_penalty_score, queue_cooldown_seconds) = self.queue_penalty_service.get_penalty(&account_id).await?;
+
         Ok(
             tonic::Response::new(GetAccountStatusResponse{
                 status: status.as_str().into(),
                 game_type,
                 game_id,
-                ranked
+                ranked,
+                pools: pools.into_iter().map(|pool| QueuePool {
+                    game_type: pool.game_type.to_str().into(),
+                    ranked: pool.ranked,
+                }).collect(),
+                estimated_wait_seconds,
+                queue_cooldown_seconds: Some(queue_cooldown_seconds as u32).filter(|seconds| *seconds > 0),
+                queue_penalty_score,
             })
         )
     }
@@ -122,14 +167,20 @@ impl MatchmakerService for MatchmakerGatewayService {
 
 pub struct RankingGatewayService {
     ranking_service: Arc<dyn RankingServiceContract>,
+    leaderboard_service: Arc<LeaderboardService>,
+    season_service: Arc<SeasonService>,
 }
 
 impl RankingGatewayService {
     pub fn new(
         ranking_service: Arc<dyn RankingServiceContract>,
+        leaderboard_service: Arc<LeaderboardService>,
+        season_service: Arc<SeasonService>,
     ) -> Self {
         Self {
             ranking_service,
+            leaderboard_service,
+            season_service,
         }
     }
 }
@@ -146,10 +197,250 @@ impl RankingService for RankingGatewayService {
         let GetAccountRankingRequest { account_id } = request.into_inner();
         let ranking: Ranking = self.ranking_service.get_or_create_ranking(&account_id).await?;
 
+        // Deviations keep growing while a player is inactive
+        let now = chrono::Utc::now();
+        let player_rating = |category: RatingCategory, ranked: bool| {
+            let rating = ranking.rating(RatingPool { category, ranked }).at(now);
+
+            PlayerRating {
+                rating: rating.rating as f32,
+                deviation: rating.deviation as f32,
+                provisional: rating.is_provisional(),
+            }
+        };
+
         Ok(tonic::Response::new(
             GetAccountRankingResponse {
-                ranked_mmr: ranking.ranked_mmr as f32,
-                normal_mmr: ranking.normal_mmr as f32
+                ratings: RatingCategory::ALL
+                    .into_iter()
+                    .map(|category| (
+                        category.to_str().to_owned(),
+                        CategoryRatings {
+                            ranked: Some(player_rating(category, true)),
+                            normal: Some(player_rating(category, false)),
+                        },
+                    ))
+                    .collect(),
+            }
+        ))
+    }
+
+    async fn get_leaderboard(
+        &self,
+        request: tonic::Request<GetLeaderboardRequest>,
+    ) -> std::result::Result<
+        tonic::Response<GetLeaderboardResponse>,
+        tonic::Status,
+    > {
+        let GetLeaderboardRequest {
+            category,
+            limit,
+            account_id,
+        } = request.into_inner();
+
+        let category = RatingCategory::from_str(&category)?;
+
+        let Leaderboard {
+            top,
+            around_me,
+        } = self.leaderboard_service.get_leaderboard(category, limit, account_id.as_deref()).await?;
+
+        let to_entry_pb = |entry: LeaderboardEntry| LeaderboardEntryPb {
+            rank: entry.rank,
+            account_id: entry.account_id,
+            rating: entry.rating as f32,
+        };
+
+        Ok(tonic::Response::new(
+            GetLeaderboardResponse {
+                top: top.into_iter().map(to_entry_pb).collect(),
+                around_me: around_me.into_iter().map(to_entry_pb).collect(),
+            }
+        ))
+    }
+
+    async fn get_rating_history(
+        &self,
+        request: tonic::Request<GetRatingHistoryRequest>,
+    ) -> std::result::Result<
+        tonic::Response<GetRatingHistoryResponse>,
+        tonic::Status,
+    > {
+        let GetRatingHistoryRequest {
+            account_id,
+            ranked,
+        } = request.into_inner();
+
+        let histories = self.ranking_service.get_rating_history(&account_id, ranked).await?;
+
+        let to_history_pb = |history: RatingHistory| RatingHistoryPb {
+            points: history.points.into_iter().map(|point| RatingHistoryPointPb {
+                game_id: point.game_id,
+                rating: point.rating as f32,
+                result: point.result.to_str().to_owned(),
+                played_at: point.played_at.timestamp_millis(),
+            }).collect(),
+            peak_rating: history.peak_rating.map(|rating| rating as f32),
+            lowest_rating: history.lowest_rating.map(|rating| rating as f32),
+            current_streak_result: history.current_streak.map(|(result, _)| result.to_str().to_owned()),
+            current_streak_length: history.current_streak.map_or(0, |(_, length)| length),
+            wins: history.wins,
+            draws: history.draws,
+            losses: history.losses,
+        };
+
+        Ok(tonic::Response::new(
+            GetRatingHistoryResponse {
+                histories: histories
+                    .into_iter()
+                    .map(|(category, history)| (category.to_str().to_owned(), to_history_pb(history)))
+                    .collect(),
+            }
+        ))
+    }
+
+    async fn get_active_season(
+        &self,
+        _request: tonic::Request<GetActiveSeasonRequest>,
+    ) -> std::result::Result<
+        tonic::Response<GetActiveSeasonResponse>,
+        tonic::Status,
+    > {
+        let season = self.season_service.get_active_season().await?;
+
+        Ok(tonic::Response::new(
+            GetActiveSeasonResponse {
+                season: season.map(to_season_pb),
+            }
+        ))
+    }
+}
+
+pub struct SeasonAdminGatewayService {
+    season_service: Arc<SeasonService>,
+    leaderboard_service: Arc<LeaderboardService>,
+}
+
+impl SeasonAdminGatewayService {
+    pub fn new(
+        season_service: Arc<SeasonService>,
+        leaderboard_service: Arc<LeaderboardService>,
+    ) -> Self {
+        Self {
+            season_service,
+            leaderboard_service,
+        }
+    }
+}
+
+fn to_season_pb(season: Season) -> SeasonPb {
+    SeasonPb {
+        id: season.id,
+        name: season.name,
+        starts_at: season.starts_at.timestamp_millis(),
+        ends_at: season.ends_at.timestamp_millis(),
+        closed_at: season.closed_at.map(|closed_at| closed_at.timestamp_millis()),
+    }
+}
+
+#[tonic::async_trait]
+impl SeasonAdminService for SeasonAdminGatewayService {
+    async fn open_season(
+        &self,
+        request: tonic::Request<OpenSeasonRequest>,
+    ) -> std::result::Result<
+        tonic::Response<OpenSeasonResponse>,
+        tonic::Status,
+    > {
+        let OpenSeasonRequest {
+            name,
+            starts_at,
+            ends_at,
+        } = request.into_inner();
+
+        let timestamp = |millis: i64| chrono::DateTime::from_timestamp_millis(millis)
+            .ok_or_else(|| MatchmakingServiceError::SeasonError(format!("Invalid timestamp {}", millis)));
+
+        let season = self.season_service.open_season(
+            name,
+            starts_at.map(timestamp).transpose()?,
+            timestamp(ends_at)?,
+        ).await?;
+
+        // Leaderboards switch to the soft reset ratings of the new season
+        self.leaderboard_service.rebuild_leaderboards().await?;
+
+        Ok(tonic::Response::new(
+            OpenSeasonResponse {
+                season: Some(to_season_pb(season)),
+            }
+        ))
+    }
+
+    async fn close_season(
+        &self,
+        _request: tonic::Request<CloseSeasonRequest>,
+    ) -> std::result::Result<
+        tonic::Response<CloseSeasonResponse>,
+        tonic::Status,
+    > {
+        let (season, results) = self.season_service.close_season().await?;
+
+        Ok(tonic::Response::new(
+            CloseSeasonResponse {
+                season: Some(to_season_pb(season)),
+                results: results.len() as u32,
+            }
+        ))
+    }
+}
+
+pub struct MatchmakingAdminGatewayService {
+    state: AppState,
+    queue_pool_service: Arc<QueuePoolService>,
+}
+
+impl MatchmakingAdminGatewayService {
+    pub fn new(
+        state: AppState,
+        queue_pool_service: Arc<QueuePoolService>,
+    ) -> Self {
+        Self {
+            state,
+            queue_pool_service,
+        }
+    }
+}
+
+fn to_queue_pool_definition_pb(pool: &PoolDefinition) -> QueuePoolDefinition {
+    QueuePoolDefinition {
+        id: pool.id.clone(),
+        time_control: pool.time_control.to_str().into(),
+        variant: pool.variant.to_str().into(),
+        rated: pool.rated,
+        base_mmr_range: pool.base_mmr_range,
+        mmr_range_increase_per_second: pool.mmr_range_increase_per_second,
+        max_mmr_delta: pool.max_mmr_delta,
+        enabled: pool.enabled,
+    }
+}
+
+#[tonic::async_trait]
+impl MatchmakingAdminService for MatchmakingAdminGatewayService {
+    async fn reload_queue_pools(
+        &self,
+        _request: tonic::Request<ReloadQueuePoolsRequest>,
+    ) -> std::result::Result<
+        tonic::Response<ReloadQueuePoolsResponse>,
+        tonic::Status,
+    > {
+        // Reloaded here first so an invalid pools file fails the request, then on every replica
+        let pools = self.queue_pool_service.reload()?;
+        matchmaking::services::streaming_service::emit_queue_pools_reload(&self.state).await?;
+
+        Ok(tonic::Response::new(
+            ReloadQueuePoolsResponse {
+                pools: pools.iter().map(to_queue_pool_definition_pb).collect(),
             }
         ))
     }
@@ -166,9 +457,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing,
         redis,
         nats,
+        matcher,
+        game_service,
+        leaderboard,
+        season,
+        player_status,
     } = ApiConfig::read_config_with_defaults();
 
+    // Relative to the crate like the .env file, so that it is found wherever the service is started
+    let matcher = MatcherConfig {
+        queue_pools_path: matcher.queue_pools_path
+            .filter(|path| !path.is_empty())
+            .map(|path| std::path::Path::new(crate_dir).join(path).to_string_lossy().into_owned()),
+        ..matcher
+    };
+
     init_tracing(tracing.clone())?;
+    redis_keys::set_key_slot_checks(redis.check_key_slots);
 
     let state: AppState = AppStateBuilder::new()
         .with_server(Some(server))
@@ -176,6 +481,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with_tracing(tracing)
         .with_redis(Some(redis))
         .with_nats(Some(nats))
+        .with_matcher(Some(matcher))
+        .with_game_service(Some(game_service))
+        .with_leaderboard(Some(leaderboard))
+        .with_season(Some(season))
+        .with_player_status(Some(player_status))
         .build()
         .await?;
 
@@ -185,43 +495,167 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let player_status_repository = PlayerStatusRepositoryService::new(state.redis.clone());
 
+    let season_service = Arc::new(SeasonService::new(
+        state.config.season.clone(),
+        Arc::new(SeasonRepositoryService::new(state.db.clone())),
+    ));
+
+    let queue_penalty_service = Arc::new(QueuePenaltyService::new(
+        QueuePenaltyPolicy::from_config(&state.config.matcher),
+        Arc::new(RedisQueuePenaltyRepository::new(state.redis.clone())),
+    ));
+
+    let queue_pool_service = Arc::new(QueuePoolService::new(&state.config.matcher)?);
+
     let matchmaking_queue_service = MatchmakingQueueService::new(
         Arc::new(RedisMatchmakingQueue::new(state.redis.clone())),
         Arc::new(
-            PlayerStatusService::new(Arc::new(player_status_repository))
+            PlayerStatusService::new(state.clone(), Arc::new(player_status_repository))
         ),
         Arc::new(MyRankingService::new(
             Arc::new(
                 RankingRepositoryService::new(state.db.clone())
             )
-        ))
+        )),
+        season_service.clone(),
+        Arc::new(RedisWaitTimeRepository::new(state.redis.clone())),
+        queue_penalty_service.clone(),
+        queue_pool_service.clone(),
     );
 
     let player_status_service = PlayerStatusService::new(
+        state.clone(),
         Arc::new(
             PlayerStatusRepositoryService::new(state.redis.clone())
         )
     );
 
-    let matchmaker_gateway_service = MatchmakerGatewayService::new(
-        // Arc::new(state),
-        matchmaking_queue_service,
-        player_status_service,
-    );
+    let leaderboard_service = Arc::new(LeaderboardService::new(
+        state.config.leaderboard.clone(),
+        Arc::new(RedisLeaderboardRepository::new(state.redis.clone())),
+        Arc::new(RankingRepositoryService::new(state.db.clone())),
+    ));
 
     let ranking_gateway_service = RankingGatewayService::new(
         Arc::new(
             MyRankingService::new(
                 Arc::new(RankingRepositoryService::new(state.db.clone()))
             )
+        ),
+        leaderboard_service.clone(),
+        season_service.clone(),
+    );
+
+    let season_admin_gateway_service = SeasonAdminGatewayService::new(
+        season_service.clone(),
+        leaderboard_service.clone(),
+    );
+
+    let matchmaking_admin_gateway_service = MatchmakingAdminGatewayService::new(state.clone(), queue_pool_service.clone());
+
+    let listener_player_status_service = Arc::new(
+        PlayerStatusService::new(
+            state.clone(),
+            Arc::new(PlayerStatusRepositoryService::new(state.redis.clone()))
         )
     );
 
+    let listener_matchmaking_queue_service = Arc::new(MatchmakingQueueService::new(
+        Arc::new(RedisMatchmakingQueue::new(state.redis.clone())),
+        listener_player_status_service.clone(),
+        Arc::new(MyRankingService::new(
+            Arc::new(RankingRepositoryService::new(state.db.clone()))
+        )),
+        season_service.clone(),
+        Arc::new(RedisWaitTimeRepository::new(state.redis.clone())),
+        queue_penalty_service.clone(),
+        queue_pool_service.clone(),
+    ));
+
+    let color_service = Arc::new(ColorService::new(
+        Arc::new(RedisColorHistoryRepository::new(state.redis.clone())),
+    ));
+
+    let pending_game_service = Arc::new(PendingGameService::new(
+        state.clone(),
+        listener_matchmaking_queue_service.clone(),
+        listener_player_status_service.clone(),
+        Arc::new(RedisPendingGameRepository::new(state.redis.clone())),
+        color_service.clone(),
+        queue_penalty_service.clone(),
+    ));
+
+    let matchmaker_gateway_service = MatchmakerGatewayService::new(
+        // Arc::new(state),
+        matchmaking_queue_service,
+        player_status_service,
+        pending_game_service.clone(),
+        queue_penalty_service.clone(),
+    );
+
+    matchmaking::services::streaming_service::ensure_stream(&state).await?;
+
+    let matcher = Matcher::new(
+        state.clone(),
+        listener_matchmaking_queue_service.clone(),
+        pending_game_service,
+    );
+    tokio::spawn(matcher.run());
+
+    let listener_ranking_service: Arc<dyn RankingServiceContract> = Arc::new(MyRankingService::new(
+        Arc::new(RankingRepositoryService::new(state.db.clone()))
+    ));
+
+    let leaderboard_rebuilder = LeaderboardRebuilder::new(state.clone(), leaderboard_service.clone());
+    tokio::spawn(leaderboard_rebuilder.run());
+
+    let player_status_reconciler = PlayerStatusReconciler::new(state.clone(), listener_player_status_service.clone());
+    tokio::spawn(player_status_reconciler.run());
+
+    let ranking_listener_state = state.clone();
+    let listener_leaderboard_service = leaderboard_service.clone();
+    let ranking_listener_player_status_service = listener_player_status_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = matchmaking::listeners::ranking::game_over_consumer(
+            ranking_listener_state,
+            listener_ranking_service,
+            listener_leaderboard_service,
+            ranking_listener_player_status_service,
+            queue_penalty_service,
+        ).await {
+            eprintln!("Game result consumer failed: {}", e);
+        }
+    });
+
+    let jetstream = state.jetstream.clone();
+    tokio::spawn(async move {
+        if let Err(e) = matchmaking::listeners::game::game_consumer(
+            jetstream,
+            listener_matchmaking_queue_service,
+            listener_player_status_service,
+            color_service,
+        ).await {
+            eprintln!("Game event consumer failed: {}", e);
+        }
+    });
+
+    let jetstream = state.jetstream.clone();
+    tokio::spawn(async move {
+        if let Err(e) = matchmaking::listeners::queue_pools::queue_pools_reload_consumer(
+            jetstream,
+            queue_pool_service,
+        ).await {
+            eprintln!("Queue pool reload consumer failed: {}", e);
+        }
+    });
+
     println!("MatchmakerService gRPC server running on {}", addr);
 
     Server::builder()
         .add_service(MatchmakerServiceServer::new(matchmaker_gateway_service))
         .add_service(RankingServiceServer::new(ranking_gateway_service))
+        .add_service(SeasonAdminServiceServer::new(season_admin_gateway_service))
+        .add_service(MatchmakingAdminServiceServer::new(matchmaking_admin_gateway_service))
         .serve(addr)
         .await?;
 
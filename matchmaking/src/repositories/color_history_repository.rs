@@ -0,0 +1,78 @@
+use redis::AsyncCommands;
+
+use crate::{error::MatchmakingServiceError, services::color_service::PieceColor};
+
+const RECORD_COLOR_SCRIPT: &str = include_str!("lua-scripts/record-color.lua");
+
+/// Games kept in a player's color history
+const COLOR_HISTORY_LENGTH: usize = 20;
+
+/// A history expires once the player stopped playing for a while, ending their session
+const COLOR_HISTORY_TTL_MS: u64 = 24 * 60 * 60 * 1000;
+
+#[async_trait::async_trait]
+pub trait ColorHistoryRepository: Send + Sync {
+    /// Colors of the player's recent games, most recent first.
+    async fn find_color_history(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<PieceColor>, MatchmakingServiceError>;
+
+    async fn record_color(
+        &self,
+        account_id: &str,
+        game_id: &str,
+        color: PieceColor,
+    ) -> Result<(), MatchmakingServiceError>;
+}
+
+pub struct RedisColorHistoryRepository {
+    client: redis::Client,
+}
+
+impl RedisColorHistoryRepository {
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+
+    pub fn get_color_history_key(account_id: &str) -> String {
+        format!("matchmaking:account:{}:color-history", account_id)
+    }
+}
+
+#[async_trait::async_trait]
+impl ColorHistoryRepository for RedisColorHistoryRepository {
+    async fn find_color_history(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<PieceColor>, MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let entries: Vec<String> = conn
+            .lrange(Self::get_color_history_key(account_id), 0, -1)
+            .await?;
+
+        Ok(entries
+            .iter()
+            .filter_map(|entry| entry.rsplit_once(':'))
+            .filter_map(|(_, color)| color.parse().ok())
+            .collect())
+    }
+
+    async fn record_color(
+        &self,
+        account_id: &str,
+        game_id: &str,
+        color: PieceColor,
+    ) -> Result<(), MatchmakingServiceError> {
+        let _: i32 = redis::Script::new(RECORD_COLOR_SCRIPT)
+            .key(Self::get_color_history_key(account_id))
+            .arg(format!("{}:{}", game_id, color.to_str()))
+            .arg(COLOR_HISTORY_LENGTH)
+            .arg(COLOR_HISTORY_TTL_MS)
+            .invoke_async(&mut self.client.get_multiplexed_async_connection().await?)
+            .await?;
+
+        Ok(())
+    }
+}
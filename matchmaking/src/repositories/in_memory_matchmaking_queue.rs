@@ -0,0 +1,456 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use shared::QueueSize;
+use shared::primitives::GameType;
+
+use crate::error::MatchmakingServiceError;
+use crate::repositories::matchmaking_queue_repository::{MatchBatch, MatchmakingQueueContract, PlayerStatus, QueueConfig, QueueType};
+use crate::services::matching_strategy::{resume_mmr, MatchingContext, MatchingStrategy, QueuedPlayer};
+
+/// Current unix timestamp (ms) of the queue.
+pub type Clock = Arc<dyn Fn() -> u64 + Send + Sync>;
+
+#[derive(Default)]
+struct Queues {
+    /// Players of every queue with their mmr and join time
+    players: HashMap<QueueType, HashMap<String, (u16, u64)>>,
+    /// Recent opponents of every player, with the time they expire at (ms)
+    recent_opponents: HashMap<QueueType, HashMap<String, HashMap<String, u64>>>,
+    /// Owner of every queue lock and the time it expires at (ms)
+    locks: HashMap<QueueType, (String, u64)>,
+    /// Mmr the next batch of every queue resumes at, and whether players with that exact mmr
+    /// are skipped
+    cursors: HashMap<QueueType, (u16, bool)>,
+}
+
+/// Matchmaking queues kept in memory, matched by any `MatchingStrategy`. Unlike the Redis queue
+/// it isn't shared between replicas, it is meant for tests and for trying out strategies.
+pub struct InMemoryMatchmakingQueue {
+    strategy: Box<dyn MatchingStrategy>,
+    clock: Clock,
+    queues: Mutex<Queues>,
+}
+
+impl InMemoryMatchmakingQueue {
+    pub fn new(strategy: Box<dyn MatchingStrategy>) -> Self {
+        Self::with_clock(strategy, Arc::new(|| chrono::Utc::now().timestamp_millis() as u64))
+    }
+
+    /// Queue whose wait times are measured by `clock`, e.g. a simulated one.
+    pub fn with_clock(strategy: Box<dyn MatchingStrategy>, clock: Clock) -> Self {
+        Self {
+            strategy,
+            clock,
+            queues: Mutex::new(Queues::default()),
+        }
+    }
+
+    /// Records two players of a queue as each other's recent opponent until `ttl_ms` elapses, as
+    /// creating a pending game does for the Redis queue.
+    pub fn record_opponents(&self, queue_type: &QueueType, account_id_0: &str, account_id_1: &str, ttl_ms: u64) {
+        let expires_at = (self.clock)() + ttl_ms;
+        let mut queues = self.queues.lock().unwrap();
+        let recent_opponents = queues.recent_opponents.entry(*queue_type).or_default();
+
+        for (account_id, opponent_id) in [(account_id_0, account_id_1), (account_id_1, account_id_0)] {
+            recent_opponents
+                .entry(account_id.to_owned())
+                .or_default()
+                .insert(opponent_id.to_owned(), expires_at);
+        }
+    }
+
+    /// Takes two players matched in a queue out of every queue they wait in, as creating a
+    /// pending game does for the Redis queue. Returns false and leaves both players queued if one
+    /// of them already left the queue they were matched in, e.g. matched in another one first.
+    pub fn take_matched_players(&self, queue_type: &QueueType, account_id_0: &str, account_id_1: &str) -> bool {
+        let mut queues = self.queues.lock().unwrap();
+        let in_matched_queue = |account_id: &str| queues.players
+            .get(queue_type)
+            .is_some_and(|players| players.contains_key(account_id));
+
+        if !in_matched_queue(account_id_0) || !in_matched_queue(account_id_1) {
+            return false;
+        }
+
+        for players in queues.players.values_mut() {
+            players.remove(account_id_0);
+            players.remove(account_id_1);
+        }
+
+        true
+    }
+}
+
+#[async_trait::async_trait]
+impl MatchmakingQueueContract for InMemoryMatchmakingQueue {
+    async fn match_players_in_queue(
+        &self,
+        game_type: &GameType,
+        ranked: bool,
+        queue_config: &QueueConfig,
+        repeat_opponent_wait_ms: u64,
+        batch_size: usize,
+    ) -> redis::RedisResult<MatchBatch> {
+        let queue_type = QueueType { game_type: *game_type, ranked };
+        let now_ms = (self.clock)();
+        let mut queues = self.queues.lock().unwrap();
+        let cursor = queues.cursors.get(&queue_type).copied();
+
+        // Sorted as the members of a Redis sorted set
+        let mut players: Vec<QueuedPlayer> = queues.players
+            .get(&queue_type)
+            .into_iter()
+            .flatten()
+            .map(|(account_id, (mmr, join_time_ms))| QueuedPlayer {
+                account_id: account_id.clone(),
+                mmr: *mmr,
+                join_time_ms: *join_time_ms,
+            })
+            .collect();
+        players.sort_by(|a, b| a.mmr.cmp(&b.mmr).then_with(|| a.account_id.cmp(&b.account_id)));
+
+        // The batch, as `match-players.lua` reads it from the cursor on
+        let players: Vec<QueuedPlayer> = players
+            .into_iter()
+            .filter(|player| match cursor {
+                Some((mmr, true)) => player.mmr > mmr,
+                Some((mmr, false)) => player.mmr >= mmr,
+                None => true,
+            })
+            .take(batch_size)
+            .collect();
+
+        let recent_opponents: HashMap<String, HashSet<String>> = queues.recent_opponents
+            .get(&queue_type)
+            .into_iter()
+            .flatten()
+            .map(|(account_id, opponents)| (
+                account_id.clone(),
+                opponents
+                    .iter()
+                    .filter(|(_, expires_at)| **expires_at > now_ms)
+                    .map(|(opponent_id, _)| opponent_id.clone())
+                    .collect(),
+            ))
+            .collect();
+
+        let context = MatchingContext {
+            queue_config,
+            repeat_opponent_wait_ms,
+            recent_opponents: &recent_opponents,
+            now_ms,
+        };
+
+        let matched_players: Vec<String> = self.strategy
+            .match_players(&players, &context)
+            .into_iter()
+            .flat_map(|(account_id_0, account_id_1)| [account_id_0, account_id_1])
+            .collect();
+
+        let scan_complete = players.len() < batch_size;
+        if scan_complete {
+            queues.cursors.remove(&queue_type);
+        } else {
+            let matched: HashSet<&str> = matched_players.iter().map(String::as_str).collect();
+            let last_mmr = players.last().map_or(0, |player| player.mmr);
+
+            // Every batch makes progress, even if all of it could still reach past it
+            let next_cursor = match (resume_mmr(&players, &matched, &context), cursor) {
+                (Some(mmr), Some((cursor_mmr, _))) if mmr <= cursor_mmr => (last_mmr, last_mmr <= cursor_mmr),
+                (Some(mmr), _) => (mmr, false),
+                (None, Some((cursor_mmr, _))) => (last_mmr, last_mmr <= cursor_mmr),
+                (None, None) => (last_mmr, false),
+            };
+            queues.cursors.insert(queue_type, next_cursor);
+        }
+
+        Ok(MatchBatch { matched_players, scan_complete })
+    }
+
+    async fn add_player_to_queue(
+        &self,
+        account_id: &str,
+        pools: &[(QueueType, u16)],
+        _status_ttl_seconds: u64,
+    ) -> redis::RedisResult<Option<PlayerStatus>> {
+        let now_ms = (self.clock)();
+        let mut queues = self.queues.lock().unwrap();
+
+        // Players who are already searching are left untouched
+        if queues.players.values().any(|players| players.contains_key(account_id)) {
+            return Ok(Some(PlayerStatus::Searching));
+        }
+
+        for (queue_type, mmr) in pools {
+            queues.players
+                .entry(*queue_type)
+                .or_default()
+                .insert(account_id.to_owned(), (*mmr, now_ms));
+        }
+
+        Ok(None)
+    }
+
+    async fn remove_player_from_queue(
+        &self,
+        account_id: &str,
+    ) -> redis::RedisResult<(i32, bool)> {
+        let mut queues = self.queues.lock().unwrap();
+
+        let removed = queues.players
+            .values_mut()
+            .filter_map(|players| players.remove(account_id))
+            .count() as i32;
+
+        Ok((removed, removed > 0))
+    }
+
+    async fn get_queue_sizes(
+        &self,
+        queue_types: Vec<QueueType>,
+    ) -> Result<HashMap<String, QueueSize>, MatchmakingServiceError> {
+        let queues = self.queues.lock().unwrap();
+        let mut queue_sizes: HashMap<String, QueueSize> = HashMap::new();
+
+        for queue_type in queue_types {
+            let queue_size = queue_sizes
+                .entry(queue_type.game_type.to_str().into())
+                .or_default();
+
+            let count = queues.players.get(&queue_type).map_or(0, |players| players.len() as u32);
+
+            if queue_type.ranked {
+                queue_size.ranked = count;
+            } else {
+                queue_size.normal = count;
+            }
+        }
+
+        Ok(queue_sizes)
+    }
+
+    async fn get_player_mmr(
+        &self,
+        account_id: &str,
+        queue_type: &QueueType,
+    ) -> redis::RedisResult<Option<u16>> {
+        let queues = self.queues.lock().unwrap();
+
+        Ok(queues.players
+            .get(queue_type)
+            .and_then(|players| players.get(account_id))
+            .map(|(mmr, _)| *mmr))
+    }
+
+    async fn acquire_queue_lock(
+        &self,
+        game_type: &GameType,
+        ranked: bool,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> redis::RedisResult<bool> {
+        let queue_type = QueueType { game_type: *game_type, ranked };
+        let now_ms = (self.clock)();
+        let mut queues = self.queues.lock().unwrap();
+
+        if queues.locks.get(&queue_type).is_some_and(|(_, expires_at)| *expires_at > now_ms) {
+            return Ok(false);
+        }
+
+        queues.locks.insert(queue_type, (owner.to_owned(), now_ms + ttl_ms));
+
+        Ok(true)
+    }
+
+    async fn extend_queue_lock(
+        &self,
+        game_type: &GameType,
+        ranked: bool,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> redis::RedisResult<bool> {
+        let queue_type = QueueType { game_type: *game_type, ranked };
+        let now_ms = (self.clock)();
+        let mut queues = self.queues.lock().unwrap();
+
+        match queues.locks.get_mut(&queue_type) {
+            Some((lock_owner, expires_at)) if lock_owner == owner && *expires_at > now_ms => {
+                *expires_at = now_ms + ttl_ms;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::{AtomicU64, Ordering}, Arc};
+
+    use shared::primitives::GameType;
+
+    use super::InMemoryMatchmakingQueue;
+    use crate::{repositories::matchmaking_queue_repository::{MatchBatch, MatchmakingQueueContract, QueueConfig, QueueType}, services::matching_strategy::GreedyWindowStrategy};
+
+    #[tokio::test]
+    async fn test_in_memory_queue() {
+        let now_ms = Arc::new(AtomicU64::new(1_000_000));
+        let clock_ms = now_ms.clone();
+        let queue = InMemoryMatchmakingQueue::with_clock(
+            Box::new(GreedyWindowStrategy),
+            Arc::new(move || clock_ms.load(Ordering::SeqCst)),
+        );
+        let blitz = QueueType { game_type: GameType::Blitz5_0, ranked: false };
+        let bullet = QueueType { game_type: GameType::Bullet1_0, ranked: false };
+        let queue_config = QueueConfig { base_mmr_range: 50, mmr_range_increase_per_second: 10, max_mmr_delta: 600 };
+
+        queue.add_player_to_queue("a", &[(blitz, 1500), (bullet, 1400)], 86400).await.unwrap();
+        queue.add_player_to_queue("b", &[(blitz, 1620)], 86400).await.unwrap();
+        queue.record_opponents(&blitz, "a", "b", 60_000);
+
+        // Out of range until their ranges grew
+        assert!(queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 30_000, 100).await.unwrap().matched_players.is_empty());
+
+        // Recent opponents until both waited long enough
+        now_ms.fetch_add(10_000, Ordering::SeqCst);
+        assert!(queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 30_000, 100).await.unwrap().matched_players.is_empty());
+
+        now_ms.fetch_add(20_000, Ordering::SeqCst);
+        assert_eq!(
+            queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 30_000, 100).await.unwrap(),
+            MatchBatch { matched_players: vec!["a".to_string(), "b".to_string()], scan_complete: true }
+        );
+
+        assert_eq!(queue.remove_player_from_queue("a").await.unwrap(), (2, true));
+        assert_eq!(queue.get_player_mmr("a", &bullet).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_queue_skips_recent_opponents() {
+        let now_ms = Arc::new(AtomicU64::new(1_000_000));
+        let clock_ms = now_ms.clone();
+        let queue = InMemoryMatchmakingQueue::with_clock(
+            Box::new(GreedyWindowStrategy),
+            Arc::new(move || clock_ms.load(Ordering::SeqCst)),
+        );
+        let blitz = QueueType { game_type: GameType::Blitz5_0, ranked: false };
+        let queue_config = QueueConfig { base_mmr_range: 50, mmr_range_increase_per_second: 10, max_mmr_delta: 600 };
+
+        for (account_id, mmr) in [("a", 1500), ("b", 1510), ("c", 1540)] {
+            queue.add_player_to_queue(account_id, &[(blitz, mmr)], 86400).await.unwrap();
+        }
+        queue.record_opponents(&blitz, "a", "b", 600_000);
+
+        // "b" is the closest neighbor of "a" but a recent opponent, so "a" gets "c"
+        assert_eq!(
+            queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 30_000, 100).await.unwrap(),
+            MatchBatch { matched_players: vec!["a".to_string(), "c".to_string()], scan_complete: true }
+        );
+        queue.remove_player_from_queue("a").await.unwrap();
+        queue.remove_player_from_queue("c").await.unwrap();
+
+        // "a" queues again, "b" already waited long enough but "a" hasn't
+        now_ms.fetch_add(20_000, Ordering::SeqCst);
+        queue.add_player_to_queue("a", &[(blitz, 1500)], 86400).await.unwrap();
+        now_ms.fetch_add(20_000, Ordering::SeqCst);
+        assert!(queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 30_000, 100).await.unwrap().matched_players.is_empty());
+
+        // Matched again once both waited, while they are still recent opponents
+        now_ms.fetch_add(10_000, Ordering::SeqCst);
+        assert_eq!(
+            queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 30_000, 100).await.unwrap(),
+            MatchBatch { matched_players: vec!["a".to_string(), "b".to_string()], scan_complete: true }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_queue_pools() {
+        let queue = InMemoryMatchmakingQueue::with_clock(Box::new(GreedyWindowStrategy), Arc::new(|| 1_000_000));
+        let blitz = QueueType { game_type: GameType::Blitz5_0, ranked: false };
+        let bullet = QueueType { game_type: GameType::Bullet1_0, ranked: false };
+        let rapid = QueueType { game_type: GameType::Rapid10_0, ranked: false };
+        let queue_config = QueueConfig { base_mmr_range: 50, mmr_range_increase_per_second: 10, max_mmr_delta: 600 };
+
+        queue.add_player_to_queue("a", &[(blitz, 1500), (bullet, 1400)], 86400).await.unwrap();
+        queue.add_player_to_queue("b", &[(blitz, 1510)], 86400).await.unwrap();
+        queue.add_player_to_queue("c", &[(bullet, 1410)], 86400).await.unwrap();
+
+        // "a" is matched in both pools before either pair is taken
+        let blitz_batch = queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 0, 100).await.unwrap();
+        assert_eq!(blitz_batch.matched_players, vec!["a".to_string(), "b".to_string()]);
+        let bullet_batch = queue.match_players_in_queue(&bullet.game_type, false, &queue_config, 0, 100).await.unwrap();
+        assert_eq!(bullet_batch.matched_players, vec!["a".to_string(), "c".to_string()]);
+
+        // Taking the first pair takes "a" out of every pool, so the second pair is dropped
+        assert!(queue.take_matched_players(&blitz, "a", "b"));
+        assert_eq!(queue.get_player_mmr("a", &bullet).await.unwrap(), None);
+        assert!(!queue.take_matched_players(&bullet, "a", "c"));
+        assert_eq!(queue.get_player_mmr("c", &bullet).await.unwrap(), Some(1410));
+        assert!(queue.match_players_in_queue(&bullet.game_type, false, &queue_config, 0, 100).await.unwrap().matched_players.is_empty());
+
+        // Leaving the queue leaves every pool
+        queue.add_player_to_queue("d", &[(blitz, 1500), (bullet, 1400), (rapid, 1600)], 86400).await.unwrap();
+        assert_eq!(queue.remove_player_from_queue("d").await.unwrap(), (3, true));
+        for queue_type in [blitz, bullet, rapid] {
+            assert_eq!(queue.get_player_mmr("d", &queue_type).await.unwrap(), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_queue_batches() {
+        let queue = InMemoryMatchmakingQueue::with_clock(Box::new(GreedyWindowStrategy), Arc::new(|| 1_000_000));
+        let blitz = QueueType { game_type: GameType::Blitz5_0, ranked: false };
+        let queue_config = QueueConfig { base_mmr_range: 50, mmr_range_increase_per_second: 10, max_mmr_delta: 600 };
+
+        for (account_id, mmr) in [("a", 1000), ("b", 1500), ("c", 1520), ("d", 2000), ("e", 2010)] {
+            queue.add_player_to_queue(account_id, &[(blitz, mmr)], 86400).await.unwrap();
+        }
+
+        // "b" is scanned again, it could still be matched past the first batch
+        let batch = queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 0, 2).await.unwrap();
+        assert_eq!(batch, MatchBatch { matched_players: vec![], scan_complete: false });
+
+        let batch = queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 0, 2).await.unwrap();
+        assert_eq!(batch, MatchBatch { matched_players: vec!["b".to_string(), "c".to_string()], scan_complete: false });
+        queue.remove_player_from_queue("b").await.unwrap();
+        queue.remove_player_from_queue("c").await.unwrap();
+
+        let batch = queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 0, 2).await.unwrap();
+        assert_eq!(batch, MatchBatch { matched_players: vec!["d".to_string(), "e".to_string()], scan_complete: false });
+        queue.remove_player_from_queue("d").await.unwrap();
+        queue.remove_player_from_queue("e").await.unwrap();
+
+        // The next batch starts over once the end of the queue is reached
+        let batch = queue.match_players_in_queue(&blitz.game_type, false, &queue_config, 0, 2).await.unwrap();
+        assert_eq!(batch, MatchBatch { matched_players: vec![], scan_complete: true });
+        assert!(queue.queues.lock().unwrap().cursors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_queue_locks() {
+        let now_ms = Arc::new(AtomicU64::new(1_000_000));
+        let clock_ms = now_ms.clone();
+        let queue = InMemoryMatchmakingQueue::with_clock(
+            Box::new(GreedyWindowStrategy),
+            Arc::new(move || clock_ms.load(Ordering::SeqCst)),
+        );
+        let blitz = GameType::Blitz5_0;
+
+        assert!(queue.acquire_queue_lock(&blitz, false, "a", 1_000).await.unwrap());
+        assert!(!queue.acquire_queue_lock(&blitz, false, "b", 1_000).await.unwrap());
+
+        // A run outlasting its first lease keeps the queue while it extends the lock
+        now_ms.fetch_add(900, Ordering::SeqCst);
+        assert!(queue.extend_queue_lock(&blitz, false, "a", 1_000).await.unwrap());
+        now_ms.fetch_add(900, Ordering::SeqCst);
+        assert!(!queue.acquire_queue_lock(&blitz, false, "b", 1_000).await.unwrap());
+        assert!(!queue.extend_queue_lock(&blitz, false, "b", 1_000).await.unwrap());
+
+        // A lost lock can't be extended by its former owner
+        now_ms.fetch_add(200, Ordering::SeqCst);
+        assert!(queue.acquire_queue_lock(&blitz, false, "b", 1_000).await.unwrap());
+        assert!(!queue.extend_queue_lock(&blitz, false, "a", 1_000).await.unwrap());
+    }
+}
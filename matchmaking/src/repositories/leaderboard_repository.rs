@@ -0,0 +1,169 @@
+use redis::AsyncCommands;
+use shared::primitives::RatingCategory;
+
+use crate::{error::MatchmakingServiceError, repositories::redis_keys};
+
+const LEADERBOARD_REBUILD_LOCK_KEY: &str = "matchmaking:leaderboard:rebuild-lock";
+
+#[async_trait::async_trait]
+pub trait LeaderboardRepository: Send + Sync {
+    /// Adds or moves a player on a leaderboard, or removes them when `rating` is `None`.
+    async fn set_player_rating(
+        &self,
+        category: RatingCategory,
+        account_id: &str,
+        rating: Option<f64>,
+    ) -> Result<(), MatchmakingServiceError>;
+
+    /// Atomically replaces a whole leaderboard.
+    async fn replace_leaderboard(
+        &self,
+        category: RatingCategory,
+        ratings: &[(String, f64)],
+    ) -> Result<(), MatchmakingServiceError>;
+
+    /// Players ranked from `start` to `stop` (0-based, inclusive), best first.
+    async fn find_range(
+        &self,
+        category: RatingCategory,
+        start: u64,
+        stop: u64,
+    ) -> Result<Vec<(String, f64)>, MatchmakingServiceError>;
+
+    /// 0-based rank of a player, `None` if they are not on the leaderboard.
+    async fn find_rank(
+        &self,
+        category: RatingCategory,
+        account_id: &str,
+    ) -> Result<Option<u64>, MatchmakingServiceError>;
+
+    async fn acquire_rebuild_lock(
+        &self,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> Result<bool, MatchmakingServiceError>;
+}
+
+pub struct RedisLeaderboardRepository {
+    client: redis::Client,
+}
+
+impl RedisLeaderboardRepository {
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+
+    /// The category is the hash tag, the key a leaderboard is rebuilt in lands in its slot.
+    pub fn get_leaderboard_key(category: RatingCategory) -> String {
+        format!("matchmaking:leaderboard:{{{}}}", category.to_str())
+    }
+
+    /// Leaderboard key and the key it is rebuilt in.
+    pub fn replace_leaderboard_keys(category: RatingCategory) -> [String; 2] {
+        let leaderboard_key = Self::get_leaderboard_key(category);
+        let rebuild_key = format!("{}:rebuild", leaderboard_key);
+
+        [leaderboard_key, rebuild_key]
+    }
+}
+
+#[async_trait::async_trait]
+impl LeaderboardRepository for RedisLeaderboardRepository {
+    async fn set_player_rating(
+        &self,
+        category: RatingCategory,
+        account_id: &str,
+        rating: Option<f64>,
+    ) -> Result<(), MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let leaderboard_key = Self::get_leaderboard_key(category);
+
+        let _: () = match rating {
+            Some(rating) => conn.zadd(leaderboard_key, account_id, rating).await?,
+            None => conn.zrem(leaderboard_key, account_id).await?,
+        };
+
+        Ok(())
+    }
+
+    async fn replace_leaderboard(
+        &self,
+        category: RatingCategory,
+        ratings: &[(String, f64)],
+    ) -> Result<(), MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let [leaderboard_key, rebuild_key] = Self::replace_leaderboard_keys(category);
+        redis_keys::check_same_slot(&[&leaderboard_key, &rebuild_key])?;
+
+        let mut pipeline = redis::pipe();
+        pipeline.del(&rebuild_key);
+
+        for chunk in ratings.chunks(1000) {
+            let members: Vec<(f64, &str)> = chunk
+                .iter()
+                .map(|(account_id, rating)| (*rating, account_id.as_str()))
+                .collect();
+
+            pipeline.zadd_multiple(&rebuild_key, &members);
+        }
+
+        if ratings.is_empty() {
+            pipeline.del(&leaderboard_key);
+        } else {
+            pipeline.rename(&rebuild_key, &leaderboard_key);
+        }
+
+        let _: () = pipeline
+            .atomic()
+            .query_async(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_range(
+        &self,
+        category: RatingCategory,
+        start: u64,
+        stop: u64,
+    ) -> Result<Vec<(String, f64)>, MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let ratings: Vec<(String, f64)> = conn
+            .zrevrange_withscores(Self::get_leaderboard_key(category), start as isize, stop as isize)
+            .await?;
+
+        Ok(ratings)
+    }
+
+    async fn find_rank(
+        &self,
+        category: RatingCategory,
+        account_id: &str,
+    ) -> Result<Option<u64>, MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let rank: Option<u64> = conn
+            .zrevrank(Self::get_leaderboard_key(category), account_id)
+            .await?;
+
+        Ok(rank)
+    }
+
+    async fn acquire_rebuild_lock(
+        &self,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> Result<bool, MatchmakingServiceError> {
+        let result: Option<String> = redis::cmd("SET")
+            .arg(LEADERBOARD_REBUILD_LOCK_KEY)
+            .arg(owner)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_ms)
+            .query_async(&mut self.client.get_multiplexed_async_connection().await?)
+            .await?;
+
+        Ok(result.is_some())
+    }
+}
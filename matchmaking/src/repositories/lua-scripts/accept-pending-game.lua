@@ -0,0 +1,61 @@
+--[[
+  Record that a player accepted their pending game.
+
+  Input:
+    KEYS[1] pending game key
+    KEYS[2] sorted set of pending game ids, scored by their expiry
+
+    ARGV[1] pending game id
+    ARGV[2] account id of the accepting player
+
+  Output:
+    -2 - account is not a player of the pending game
+    -1 - pending game missing or its accept window has closed
+     0 - both players had already accepted, the game is being created
+     1 - accepted, waiting for the other player
+     2 - both players accepted, the caller creates the game
+]]
+
+local pendingGameKey = KEYS[1]
+local pendingGameTimeoutsKey = KEYS[2]
+local pendingGameId = ARGV[1]
+local accountId = ARGV[2]
+
+local accountId0, accountId1, expiresAt, status = unpack(
+  redis.call('HMGET', pendingGameKey, 'accountId0', 'accountId1', 'expiresAt', 'status')
+)
+
+if not accountId0 then
+  return -1
+end
+
+local acceptedField
+if accountId == accountId0 then
+  acceptedField = 'accepted0'
+elseif accountId == accountId1 then
+  acceptedField = 'accepted1'
+else
+  return -2
+end
+
+if status == 'accepted' then
+  return 0
+end
+
+local seconds, microseconds = unpack(redis.call('TIME'))
+local nowMs = math.floor(seconds * 1000 + microseconds / 1000)
+if nowMs >= tonumber(expiresAt) then
+  return -1
+end
+
+redis.call('HSET', pendingGameKey, acceptedField, 1)
+
+local accepted0, accepted1 = unpack(redis.call('HMGET', pendingGameKey, 'accepted0', 'accepted1'))
+if accepted0 == '1' and accepted1 == '1' then
+  -- The pending game can't time out anymore, only one caller gets to create the game
+  redis.call('HSET', pendingGameKey, 'status', 'accepted')
+  redis.call('ZREM', pendingGameTimeoutsKey, pendingGameId)
+  return 2
+end
+
+return 1
@@ -1,30 +1,26 @@
 --[[
-  Add a player to matchmaking queue.
+  Add a player to one or more matchmaking queues (pools) at once.
 
   Input:
-    KEYS[1] queue key where player should be added
-    KEYS[2] hash key where queue join times are stored
-    KEYS[3] key that holds account status
+    KEYS[1] key that holds account status
+    KEYS[2], KEYS[3], ... queue key and hash key where queue join times are stored, for every
+            pool the player joins
 
-    ARGV[1] new status of the player after joining the queue
+    ARGV[1] new status of the player after joining the queues
     ARGV[2] account id of the player
-    ARGV[3] ranking of the player
-    ARGV[4] queue game type
-    ARGV[5] queue ranked type (true/false)
+    ARGV[3] time the status is kept for (s)
+    ARGV[4], ARGV[5], ... pool id and ranking of the player in that pool, for every pool the
+            player joins, in the same order as the keys
 
   Output:
-    string - current account status if account could not be added to the queue
-    nil - account successfully added to the queue
+    string - current account status if account could not be added to the queues
+    nil - account successfully added to the queues
 ]]
 
-local queueKey = KEYS[1]
-local timesKey = KEYS[2]
-local accountStatusKey = KEYS[3]
+local accountStatusKey = KEYS[1]
 local newStatus = ARGV[1]
 local accountId = ARGV[2]
-local mmr = ARGV[3]
-local gameType = ARGV[4]
-local ranked = ARGV[5]
+local statusTtlSeconds = tonumber(ARGV[3])
 
 -- If player is already in any state (playing, pending, searching, etc.) do not add him to the queue
 local accountStatus = redis.call('HGET', accountStatusKey, 'status')
@@ -36,12 +32,20 @@ end
 local timeCommandResult = redis.call('TIME')
 local nowMs = math.floor(timeCommandResult[1] * 1000 + timeCommandResult[2] / 1000)
 
--- Add player to queue and store join time
-redis.call('ZADD', queueKey, mmr, accountId)
-redis.call('HSET', timesKey, accountId, nowMs)
+local poolIds = {}
 
--- Set new account status, expiring in 1 day
-redis.call('HSET', accountStatusKey, 'status', newStatus, 'gameType', gameType, 'ranked', ranked)
-redis.call('EXPIRE', accountStatusKey, 86400)
+-- Add player to every queue and store join times
+for i = 2, #KEYS, 2 do
+  local poolId = ARGV[i + 2]
+  local mmr = ARGV[i + 3]
+
+  redis.call('ZADD', KEYS[i], mmr, accountId)
+  redis.call('HSET', KEYS[i + 1], accountId, nowMs)
+  table.insert(poolIds, poolId)
+end
+
+-- Set new account status listing the pools
+redis.call('HSET', accountStatusKey, 'status', newStatus, 'pools', table.concat(poolIds, ','))
+redis.call('EXPIRE', accountStatusKey, statusTtlSeconds)
 
 return nil
@@ -0,0 +1,23 @@
+--[[
+  Clear the status of a player once their game is over.
+
+  Input:
+    KEYS[1] key that holds account status
+
+    ARGV[1] id of the game that ended
+
+  Output:
+    1 - status cleared
+    0 - status left untouched (player is not playing that game anymore, e.g. already in a rematch)
+]]
+
+local accountStatusKey = KEYS[1]
+local gameId = ARGV[1]
+
+local currStatus, currGameId = unpack(redis.call('HMGET', accountStatusKey, 'status', 'gameId'))
+if currStatus == 'playing' and currGameId == gameId then
+  redis.call('DEL', accountStatusKey)
+  return 1
+end
+
+return 0
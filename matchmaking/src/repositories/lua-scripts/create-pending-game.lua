@@ -0,0 +1,172 @@
+--[[
+  Turn a pair of matched players into a pending game both players have to accept. Players are
+  removed from every queue (pool) they wait in, not only from the one they were matched in.
+
+  Input:
+    KEYS[1] queue key the players were matched in
+    KEYS[2] hash key where queue join times are stored
+    KEYS[3] key that holds account status of the first player
+    KEYS[4] key that holds account status of the second player
+    KEYS[5] pending game key
+    KEYS[6] sorted set of pending game ids, scored by their expiry
+    KEYS[7] hash key where the recent opponents of every player of the queue are stored
+    KEYS[8] sorted set of the players with recent opponents, scored by when their last one expires
+    KEYS[9] hash key where the wait times of the queue's matches are counted in the current time
+            slot, by rating band and wait time bucket
+    KEYS[10], KEYS[11], ... queue key and hash key where queue join times are stored, for every pool
+
+    ARGV[1] pending game id
+    ARGV[2] account id of the first player
+    ARGV[3] account id of the second player
+    ARGV[4] queue game type
+    ARGV[5] queue ranked type (1/0)
+    ARGV[6] time players have to accept the pending game (ms)
+    ARGV[7] time players are kept as recent opponents (ms)
+    ARGV[8] maximum number of recent opponents kept per player
+    ARGV[9] id of the pool the players were matched in
+    ARGV[10] width of the rating bands wait times are counted in
+    ARGV[11] time wait times are kept for (ms)
+    ARGV[12] time the pending status of the players is kept for (s)
+    ARGV[13], ARGV[14], ... id of every pool, in the same order as the keys
+
+  Output:
+    table - expiry of the pending game (ms) and the mmr of both players
+    nil - pending game not created, one of the players is not searching in this queue anymore
+]]
+
+local queueKey = KEYS[1]
+local timesKey = KEYS[2]
+local accountStatusKeys = { KEYS[3], KEYS[4] }
+local pendingGameKey = KEYS[5]
+local pendingGameTimeoutsKey = KEYS[6]
+local recentOpponentsKey = KEYS[7]
+local recentOpponentExpiriesKey = KEYS[8]
+local pendingGameId = ARGV[1]
+local accountIds = { ARGV[2], ARGV[3] }
+local gameType = ARGV[4]
+local ranked = ARGV[5]
+local timeoutMs = tonumber(ARGV[6])
+local recentOpponentTtlMs = tonumber(ARGV[7])
+local maxRecentOpponents = tonumber(ARGV[8])
+local waitTimesKey = KEYS[9]
+local matchedPoolId = ARGV[9]
+local ratingBandWidth = tonumber(ARGV[10])
+local waitTimesTtlMs = tonumber(ARGV[11])
+local pendingStatusTtlSeconds = tonumber(ARGV[12])
+
+local poolKeys = {}
+for i = 10, #KEYS, 2 do
+  poolKeys[ARGV[(i - 10) / 2 + 13]] = { queueKey = KEYS[i], timesKey = KEYS[i + 1] }
+end
+
+local mmrs = {}
+local joinTimes = {}
+local poolIds = {}
+local stale = false
+
+for i = 1, 2 do
+  local status, pools = unpack(redis.call('HMGET', accountStatusKeys[i], 'status', 'pools'))
+  mmrs[i] = redis.call('ZSCORE', queueKey, accountIds[i])
+  joinTimes[i] = redis.call('HGET', timesKey, accountIds[i])
+
+  poolIds[i] = {}
+  local inMatchedPool = false
+  for poolId in string.gmatch(pools or '', '[^,]+') do
+    table.insert(poolIds[i], poolId)
+    inMatchedPool = inMatchedPool or poolId == matchedPoolId
+  end
+
+  -- Queue entries whose player left the queue in the meantime are dropped
+  if status ~= 'searching' or not inMatchedPool or not mmrs[i] then
+    redis.call('ZREM', queueKey, accountIds[i])
+    redis.call('HDEL', timesKey, accountIds[i])
+    stale = true
+  end
+end
+
+if stale then
+  return nil
+end
+
+local seconds, microseconds = unpack(redis.call('TIME'))
+local nowMs = math.floor(seconds * 1000 + microseconds / 1000)
+local expiresAt = nowMs + timeoutMs
+
+local queueEntries = {}
+
+for i = 1, 2 do
+  -- Leave every pool, remembering the places so that the player can be requeued into all of them
+  queueEntries[i] = {}
+  for _, poolId in ipairs(poolIds[i]) do
+    local keys = poolKeys[poolId]
+    if keys then
+      local mmr = redis.call('ZSCORE', keys.queueKey, accountIds[i])
+      if mmr then
+        local joinTime = redis.call('HGET', keys.timesKey, accountIds[i]) or nowMs
+        table.insert(queueEntries[i], { poolId = poolId, mmr = tonumber(mmr), joinTime = tonumber(joinTime) })
+        redis.call('ZREM', keys.queueKey, accountIds[i])
+        redis.call('HDEL', keys.timesKey, accountIds[i])
+      end
+    end
+  end
+
+  redis.call('ZREM', queueKey, accountIds[i])
+  redis.call('HDEL', timesKey, accountIds[i])
+  redis.call('HSET', accountStatusKeys[i], 'status', 'pending', 'pendingGameId', pendingGameId)
+  redis.call('EXPIRE', accountStatusKeys[i], pendingStatusTtlSeconds)
+end
+
+-- Join times are kept so that players can be requeued at their original place
+redis.call(
+  'HSET', pendingGameKey,
+  'accountId0', accountIds[1], 'accountId1', accountIds[2],
+  'mmr0', mmrs[1], 'mmr1', mmrs[2],
+  'joinTime0', joinTimes[1] or nowMs, 'joinTime1', joinTimes[2] or nowMs,
+  'queueEntries0', cjson.encode(queueEntries[1]), 'queueEntries1', cjson.encode(queueEntries[2]),
+  'gameType', gameType, 'ranked', ranked,
+  'expiresAt', expiresAt
+)
+-- Kept around a little longer than the accept window so the timeout can still be processed
+redis.call('PEXPIRE', pendingGameKey, timeoutMs + 60000)
+redis.call('ZADD', pendingGameTimeoutsKey, expiresAt, pendingGameId)
+
+-- Count the time both players waited to be matched. Bucket b holds waits from 2^b - 1 up to
+-- 2^(b+1) - 1 seconds.
+for i = 1, 2 do
+  local waitedMs = math.max(nowMs - tonumber(joinTimes[i] or nowMs), 0)
+  local bucket = math.floor(math.log(waitedMs / 1000 + 1) / math.log(2))
+  local ratingBand = math.floor(tonumber(mmrs[i]) / ratingBandWidth)
+  redis.call('HINCRBY', waitTimesKey, ratingBand .. ':' .. bucket, 1)
+end
+redis.call('PEXPIRE', waitTimesKey, waitTimesTtlMs)
+
+-- Remember both players as each other's most recent opponent, oldest first
+for i = 1, 2 do
+  local opponentId = accountIds[3 - i]
+  local recentOpponents = {}
+  local encoded = redis.call('HGET', recentOpponentsKey, accountIds[i])
+  if encoded then
+    for _, opponent in ipairs(cjson.decode(encoded)) do
+      if opponent.expiresAt > nowMs and opponent.id ~= opponentId then
+        table.insert(recentOpponents, opponent)
+      end
+    end
+  end
+  table.insert(recentOpponents, { id = opponentId, expiresAt = nowMs + recentOpponentTtlMs })
+  while #recentOpponents > maxRecentOpponents do
+    table.remove(recentOpponents, 1)
+  end
+  redis.call('HSET', recentOpponentsKey, accountIds[i], cjson.encode(recentOpponents))
+  redis.call('ZADD', recentOpponentExpiriesKey, nowMs + recentOpponentTtlMs, accountIds[i])
+end
+
+-- Drop the players whose recent opponents all expired
+local expiredIds = redis.call('ZRANGEBYSCORE', recentOpponentExpiriesKey, '-inf', nowMs, 'LIMIT', 0, 100)
+for _, expiredId in ipairs(expiredIds) do
+  redis.call('HDEL', recentOpponentsKey, expiredId)
+  redis.call('ZREM', recentOpponentExpiriesKey, expiredId)
+end
+redis.call('PEXPIRE', recentOpponentsKey, recentOpponentTtlMs)
+redis.call('PEXPIRE', recentOpponentExpiriesKey, recentOpponentTtlMs)
+
+return { expiresAt, tonumber(mmrs[1]), tonumber(mmrs[2]) }
@@ -0,0 +1,24 @@
+--[[
+  Set the expiry of a queue lock if it is still held by the given matcher, to extend the lock
+  while the matcher runs or to shorten it once it is done.
+
+  Input:
+    KEYS[1] queue lock key
+
+    ARGV[1] id of the matcher replica holding the lock
+    ARGV[2] new expiry of the lock (ms from now)
+
+  Output:
+    1 - lock expiry set
+    0 - lock expired or taken by another matcher
+]]
+
+local lockKey = KEYS[1]
+local owner = ARGV[1]
+local ttlMs = ARGV[2]
+
+if redis.call('GET', lockKey) ~= owner then
+  return 0
+end
+
+return redis.call('PEXPIRE', lockKey, ttlMs)
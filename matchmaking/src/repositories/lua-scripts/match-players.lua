@@ -1,31 +1,63 @@
 --[[
   Match players based on their mmr and time spent in the queue.
 
+  Players are paired with their closest neighbor in mmr order whose search range overlaps. A
+  neighbor the player recently played is skipped in favor of the next one, unless both players
+  have been waiting for longer than the repeat opponent wait.
+
+  The queue is scanned incrementally: every call matches at most one batch of players, starting
+  at the cursor the previous call left off at, so that the work done in one call is bounded by
+  the batch size rather than the queue size. Unmatched players at the end of a batch who could
+  still be paired with players past it are scanned again by the next call.
+
   Input:
     KEYS[1] queue key from which players should be matched
     KEYS[2] hash key where queue join times are stored
+    KEYS[3] hash key where the recent opponents of every player are stored
+    KEYS[4] key where the mmr the next call starts at is stored
 
     ARGV[1] starting mmr search range
     ARGV[2] mmr search range increase per second
     ARGV[3] maximum mmr delta between players
+    ARGV[4] time both players have to wait before they can be matched again (ms)
+    ARGV[5] maximum number of players matched in one call
 
   Output:
-    table - flat array of length % 2 == 0, where each pair of elements represents matched players
+    table - 1 if the scan reached the end of the queue and the next call starts over, 0
+            otherwise, followed by a flat array of length % 2 == 0, where each pair of elements
+            represents matched players
 ]]
 
 local queueKey = KEYS[1]
 local timesKey = KEYS[2]
+local recentOpponentsKey = KEYS[3]
+local cursorKey = KEYS[4]
 local mmrRange = ARGV[1]
 local rangeIncreasePerSecond = ARGV[2]
 local maxMmrDelta = ARGV[3] / 2
+local repeatOpponentWaitMs = tonumber(ARGV[4])
+local batchSize = tonumber(ARGV[5])
+
+-- Keys passed to a single command are kept well below the Lua stack limit
+local HMGET_CHUNK_SIZE = 500
 
 local result = {}
 
-local playersAndScores = redis.call('ZRANGE', queueKey, 0, -1, 'WITHSCORES')
-local queueJoinTimesMsArray = redis.call('HGETALL', timesKey)
+local cursor = redis.call('GET', cursorKey) or '-inf'
+local playersAndScores = redis.call('ZRANGEBYSCORE', queueKey, cursor, '+inf', 'WITHSCORES', 'LIMIT', 0, batchSize)
+local playerCount = #playersAndScores / 2
+
+-- Only the join times of the batch are read
 local queueJoinTimesMs = {}
-for i = 1, #queueJoinTimesMsArray, 2 do
-  queueJoinTimesMs[queueJoinTimesMsArray[i]] = queueJoinTimesMsArray[i + 1]
+for chunkStart = 1, playerCount, HMGET_CHUNK_SIZE do
+  local ids = {}
+  for i = chunkStart, math.min(chunkStart + HMGET_CHUNK_SIZE - 1, playerCount) do
+    table.insert(ids, playersAndScores[i * 2 - 1])
+  end
+  local joinTimes = redis.call('HMGET', timesKey, unpack(ids))
+  for i, id in ipairs(ids) do
+    queueJoinTimesMs[id] = joinTimes[i]
+  end
 end
 
 -- Calculate mmr search range for a player, based on mmr and time spent in the queue
@@ -33,10 +65,12 @@ local function getPlayerRanges(id, mmr, currMs)
   if id == nil then
     return nil
   end
+  local waitedMs = currMs - (tonumber(queueJoinTimesMs[id]) or 0)
   -- Increase mmr search range based on time spent in the queue
-  local mmrRangeBonus = math.floor((currMs - (queueJoinTimesMs[id] or 0)) / 1000) * rangeIncreasePerSecond
+  local mmrRangeBonus = math.floor(waitedMs / 1000) * rangeIncreasePerSecond
   return {
     id = id,
+    waitedMs = waitedMs,
     lowerBound = math.max(mmr - mmrRange - mmrRangeBonus, mmr - maxMmrDelta),
     upperBound = math.min(mmr + mmrRange + mmrRangeBonus, mmr + maxMmrDelta)
   }
@@ -46,22 +80,90 @@ end
 local seconds, microseconds = unpack(redis.call('TIME'))
 local nowMs = math.floor(seconds * 1000 + microseconds / 1000)
 
-local i = 1
-while playersAndScores[i + 2] do
-  local currPlayer = getPlayerRanges(playersAndScores[i], playersAndScores[i + 1], nowMs)
-  local nextPlayer = getPlayerRanges(playersAndScores[i + 2], playersAndScores[i + 3], nowMs)
-  
-  -- Match players if their mmr search ranges overlap
-  if nextPlayer ~= nil then
-    if currPlayer.upperBound >= nextPlayer.lowerBound then
-      table.insert(result, currPlayer.id)
-      table.insert(result, nextPlayer.id)
-      -- Skip next player (already matched with current one)
-      i = i + 2
+-- Recent opponents are read lazily, most players are never checked
+local recentOpponents = {}
+local function isRecentOpponent(id, opponentId)
+  if recentOpponents[id] == nil then
+    recentOpponents[id] = {}
+    local encoded = redis.call('HGET', recentOpponentsKey, id)
+    if encoded then
+      for _, opponent in ipairs(cjson.decode(encoded)) do
+        if opponent.expiresAt > nowMs then
+          recentOpponents[id][opponent.id] = true
+        end
+      end
     end
   end
-  -- Proceed with next player
-  i = i + 2
+  return recentOpponents[id][opponentId] == true
 end
 
-return result
+local function canBeMatched(currPlayer, nextPlayer)
+  if currPlayer.waitedMs >= repeatOpponentWaitMs and nextPlayer.waitedMs >= repeatOpponentWaitMs then
+    return true
+  end
+  return not isRecentOpponent(currPlayer.id, nextPlayer.id) and not isRecentOpponent(nextPlayer.id, currPlayer.id)
+end
+
+local matched = {}
+local players = {}
+
+for i = 1, playerCount do
+  players[i] = getPlayerRanges(playersAndScores[i * 2 - 1], tonumber(playersAndScores[i * 2]), nowMs)
+end
+
+for i = 1, playerCount do
+  local currPlayer = players[i]
+
+  if not matched[currPlayer.id] then
+    for j = i + 1, playerCount do
+      local nextPlayer = players[j]
+
+      if not matched[nextPlayer.id] then
+        -- The search stops at the first unmatched neighbor out of range
+        if currPlayer.upperBound < nextPlayer.lowerBound then
+          break
+        end
+
+        if canBeMatched(currPlayer, nextPlayer) then
+          table.insert(result, currPlayer.id)
+          table.insert(result, nextPlayer.id)
+          matched[currPlayer.id] = true
+          matched[nextPlayer.id] = true
+          break
+        end
+      end
+    end
+  end
+end
+
+-- A batch smaller than the batch size reached the end of the queue, the next call starts over
+if playerCount < batchSize then
+  redis.call('DEL', cursorKey)
+  return { 1, result }
+end
+
+-- Players past the batch have at least the mmr of its last player, so the first unmatched player
+-- who could reach it is where the next call resumes
+local lastMmr = tonumber(playersAndScores[playerCount * 2])
+local cursorMmr = tonumber((string.gsub(cursor, '^%(', ''))) or -math.huge
+local nextCursor = nil
+
+for i = 1, playerCount do
+  local player = players[i]
+  if not matched[player.id] and player.upperBound >= lastMmr - maxMmrDelta then
+    nextCursor = tonumber(playersAndScores[i * 2])
+    break
+  end
+end
+
+-- Every call makes progress, even if all of the batch could still reach past it
+if nextCursor == nil or nextCursor <= cursorMmr then
+  nextCursor = lastMmr
+end
+if nextCursor <= cursorMmr then
+  nextCursor = '(' .. lastMmr
+end
+
+redis.call('SET', cursorKey, nextCursor)
+
+return { 0, result }
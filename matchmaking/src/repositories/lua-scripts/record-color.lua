@@ -0,0 +1,29 @@
+--[[
+  Record the color a player got in a game, keeping only their most recent games.
+
+  Input:
+    KEYS[1] key that holds the player's color history, most recent first
+
+    ARGV[1] history entry, "{gameId}:{w|b}"
+    ARGV[2] max length of the history
+    ARGV[3] ttl of the history (ms)
+
+  Output:
+    1 - color recorded
+    0 - the game was already recorded, e.g. on a redelivered event
+]]
+
+local historyKey = KEYS[1]
+local entry = ARGV[1]
+local maxLength = tonumber(ARGV[2])
+local ttl = tonumber(ARGV[3])
+
+if redis.call('LPOS', historyKey, entry) then
+  return 0
+end
+
+redis.call('LPUSH', historyKey, entry)
+redis.call('LTRIM', historyKey, 0, maxLength - 1)
+redis.call('PEXPIRE', historyKey, ttl)
+
+return 1
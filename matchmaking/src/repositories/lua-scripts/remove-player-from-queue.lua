@@ -1,33 +1,32 @@
 --[[
-  Remove player from queue.
+  Remove player from every queue (pool) they are waiting in.
 
   Input:
-    KEYS[1] queue key from which players should be removed
-    KEYS[2] hash key where queue join times are stored
-    KEYS[3] key that holds account status
+    KEYS[1] key that holds account status
+    KEYS[2], KEYS[3], ... queue key and hash key where queue join times are stored, for every pool
 
     ARGV[1] account id of the player to remove
-    ARGV[2] game type of the queue the player is in
-    ARGV[3] whether the queue is ranked or not
 
   Output:
-    1 - player successfully removed from the queue
-    0 - number not removed from the queue (player wasn't in the queue)
+    table - number of queues the player was removed from and whether their searching status was
+            cleared (1/0)
 ]]
 
-local queueKey = KEYS[1]
-local timesKey = KEYS[2]
-local accountStatusKey = KEYS[3]
+local accountStatusKey = KEYS[1]
 local accountId = ARGV[1]
-local gameType = ARGV[2]
-local ranked = ARGV[3]
 
-local currStatus, currGameType, currRanked = unpack(redis.call('HMGET', accountStatusKey, 'status', 'gameType', 'ranked'))
-if currStatus == 'searching' and currGameType == gameType and currRanked == ranked then
+local statusCleared = 0
+local currStatus = redis.call('HGET', accountStatusKey, 'status')
+if currStatus == 'searching' then
   redis.call('DEL', accountStatusKey)
+  statusCleared = 1
 end
 
-local result = redis.call('ZREM', queueKey, accountId)
-redis.call('HDEL', timesKey, accountId)
+-- Every pool is cleaned up, which also drops entries left behind by an expired status
+local result = 0
+for i = 2, #KEYS, 2 do
+  result = result + redis.call('ZREM', KEYS[i], accountId)
+  redis.call('HDEL', KEYS[i + 1], accountId)
+end
 
-return result
+return { result, statusCleared }
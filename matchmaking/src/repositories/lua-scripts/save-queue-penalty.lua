@@ -0,0 +1,36 @@
+--[[
+  Store the penalty of a player after an offense, unless that offense was already recorded.
+
+  Offenses are remembered in the penalty hash by the id of the game or pending game they were
+  committed in, so that recording one again, e.g. on a redelivered event, leaves the penalty as it
+  is.
+
+  Input:
+    KEYS[1] hash key that holds the player's penalty
+
+    ARGV[1] penalty score
+    ARGV[2] unix timestamp (ms) of the offense
+    ARGV[3] unix timestamp (ms) the player can join queues again at
+    ARGV[4] ttl of the penalty (ms)
+    ARGV[5] id of the game or pending game the offense was committed in
+
+  Output:
+    1 - penalty stored
+    0 - the offense was already recorded
+]]
+
+local penaltyKey = KEYS[1]
+local score = ARGV[1]
+local updatedAt = ARGV[2]
+local cooldownUntil = ARGV[3]
+local ttl = tonumber(ARGV[4])
+local offenseId = ARGV[5]
+
+if redis.call('HSETNX', penaltyKey, 'offense:' .. offenseId, updatedAt) == 0 then
+  return 0
+end
+
+redis.call('HSET', penaltyKey, 'score', score, 'updatedAt', updatedAt, 'cooldownUntil', cooldownUntil)
+redis.call('PEXPIRE', penaltyKey, ttl)
+
+return 1
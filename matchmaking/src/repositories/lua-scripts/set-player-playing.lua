@@ -0,0 +1,47 @@
+--[[
+  Set a player playing a game, if their current status allows it.
+
+  Input:
+    KEYS[1] key that holds account status
+
+    ARGV[1] id of the game
+    ARGV[2] game type
+    ARGV[3] ranked type (1/0)
+    ARGV[4] time the status is kept for (s)
+    ARGV[5], ARGV[6], ... statuses the player can start playing from, 'undefined' for no status
+
+  Output:
+    table - 1 and the previous status if the player is now playing, 0 and the current status if
+            the player can't start playing or is already playing this game
+]]
+
+local accountStatusKey = KEYS[1]
+local gameId = ARGV[1]
+local gameType = ARGV[2]
+local ranked = ARGV[3]
+local ttlSeconds = tonumber(ARGV[4])
+
+local currStatus, currGameId = unpack(redis.call('HMGET', accountStatusKey, 'status', 'gameId'))
+currStatus = currStatus or 'undefined'
+
+-- Redelivered game starts leave the player as is
+if currStatus == 'playing' and currGameId == gameId then
+  return { 0, currStatus }
+end
+
+local allowed = false
+for i = 5, #ARGV do
+  if ARGV[i] == currStatus then
+    allowed = true
+  end
+end
+
+if not allowed then
+  return { 0, currStatus }
+end
+
+redis.call('DEL', accountStatusKey)
+redis.call('HSET', accountStatusKey, 'status', 'playing', 'gameId', gameId, 'gameType', gameType, 'ranked', ranked)
+redis.call('EXPIRE', accountStatusKey, ttlSeconds)
+
+return { 1, currStatus }
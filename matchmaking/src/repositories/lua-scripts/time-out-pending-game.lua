@@ -0,0 +1,125 @@
+--[[
+  Time out a pending game. Players who accepted go back into every queue (pool) they were waiting
+  in, at their original join times, players who didn't are dropped from matchmaking.
+
+  A pending game declined by one of its players ends right away. The declining player is dropped
+  and the other one goes back into their queues whether they accepted yet or not.
+
+  Input:
+    KEYS[1] pending game key
+    KEYS[2] sorted set of pending game ids, scored by their expiry
+    KEYS[3] key that holds account status of the first player
+    KEYS[4] key that holds account status of the second player
+    KEYS[5] queue key the players were matched in
+    KEYS[6] hash key where queue join times are stored
+    KEYS[7], KEYS[8], ... queue key and hash key where queue join times are stored, for every pool
+
+    ARGV[1] pending game id
+    ARGV[2] 1 to time out the pending game even though its accept window is still open or both
+            players accepted (e.g. the game couldn't be created), 0 otherwise
+    ARGV[3] id of the pool the players were matched in
+    ARGV[4] account id of the player declining the pending game, empty if it timed out
+    ARGV[5] time the searching status of requeued players is kept for (s)
+    ARGV[6], ARGV[7], ... id of every pool, in the same order as the keys
+
+  Output:
+    table - whether each player had accepted (1/0), then the new status of each player
+            ('searching' or 'undefined', empty if the player had moved on and was left alone)
+    nil - pending game missing, still open or already accepted
+]]
+
+local pendingGameKey = KEYS[1]
+local pendingGameTimeoutsKey = KEYS[2]
+local accountStatusKeys = { KEYS[3], KEYS[4] }
+local queueKey = KEYS[5]
+local timesKey = KEYS[6]
+local pendingGameId = ARGV[1]
+local force = ARGV[2] == '1'
+local matchedPoolId = ARGV[3]
+local declinedBy = ARGV[4]
+local searchingStatusTtlSeconds = tonumber(ARGV[5])
+
+local poolKeys = {}
+for i = 7, #KEYS, 2 do
+  poolKeys[ARGV[(i - 7) / 2 + 6]] = { queueKey = KEYS[i], timesKey = KEYS[i + 1] }
+end
+
+local accountId0, accountId1, mmr0, mmr1, joinTime0, joinTime1, queueEntries0, queueEntries1, accepted0, accepted1, expiresAt, status = unpack(
+  redis.call(
+    'HMGET', pendingGameKey,
+    'accountId0', 'accountId1', 'mmr0', 'mmr1', 'joinTime0', 'joinTime1', 'queueEntries0', 'queueEntries1',
+    'accepted0', 'accepted1', 'expiresAt', 'status'
+  )
+)
+
+if not accountId0 then
+  redis.call('ZREM', pendingGameTimeoutsKey, pendingGameId)
+  return nil
+end
+
+if not force then
+  local seconds, microseconds = unpack(redis.call('TIME'))
+  local nowMs = math.floor(seconds * 1000 + microseconds / 1000)
+
+  if status == 'accepted' or (declinedBy == '' and nowMs < tonumber(expiresAt)) then
+    return nil
+  end
+end
+
+local players = {
+  { id = accountId0, mmr = mmr0, joinTime = joinTime0, queueEntries = queueEntries0, accepted = accepted0 == '1' },
+  { id = accountId1, mmr = mmr1, joinTime = joinTime1, queueEntries = queueEntries1, accepted = accepted1 == '1' },
+}
+local requeue = {}
+for i, player in ipairs(players) do
+  if declinedBy == '' then
+    requeue[i] = player.accepted
+  else
+    requeue[i] = player.id ~= declinedBy
+  end
+end
+local result = {}
+local statuses = {}
+
+for i, player in ipairs(players) do
+  local accountStatusKey = accountStatusKeys[i]
+  local currStatus, currPendingGameId = unpack(redis.call('HMGET', accountStatusKey, 'status', 'pendingGameId'))
+
+  -- Players who moved on (e.g. accepted a challenge) are left alone
+  if currStatus == 'pending' and currPendingGameId == pendingGameId then
+    if requeue[i] then
+      -- Pending games created before players could join several pools only know the matched one
+      local poolIds = {}
+      if player.queueEntries then
+        for _, entry in ipairs(cjson.decode(player.queueEntries)) do
+          local keys = poolKeys[entry.poolId]
+          if keys then
+            redis.call('ZADD', keys.queueKey, entry.mmr, player.id)
+            redis.call('HSET', keys.timesKey, player.id, entry.joinTime)
+            table.insert(poolIds, entry.poolId)
+          end
+        end
+      else
+        redis.call('ZADD', queueKey, player.mmr, player.id)
+        redis.call('HSET', timesKey, player.id, player.joinTime)
+        table.insert(poolIds, matchedPoolId)
+      end
+      redis.call('HSET', accountStatusKey, 'status', 'searching', 'pools', table.concat(poolIds, ','))
+      redis.call('HDEL', accountStatusKey, 'pendingGameId')
+      redis.call('EXPIRE', accountStatusKey, searchingStatusTtlSeconds)
+      statuses[i] = 'searching'
+    else
+      redis.call('DEL', accountStatusKey)
+      statuses[i] = 'undefined'
+    end
+  else
+    statuses[i] = ''
+  end
+
+  result[i] = player.accepted and 1 or 0
+end
+
+redis.call('DEL', pendingGameKey)
+redis.call('ZREM', pendingGameTimeoutsKey, pendingGameId)
+
+return { result[1], result[2], statuses[1], statuses[2] }
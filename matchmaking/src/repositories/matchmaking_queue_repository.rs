@@ -7,7 +7,9 @@ use tokio::sync::Mutex;
 
 use crate::error::MatchmakingServiceError;
 use crate::repositories::player_status_repository::PlayerStatusRepositoryService;
+use crate::repositories::redis_keys::{self, MATCHMAKING_KEY_PREFIX};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerStatus {
     Undefined,
     Searching,
@@ -56,13 +58,78 @@ impl FromStr for PlayerStatus {
 pub struct QueueKeys {
    pub queue_key: String,
    pub times_key: String,
+   pub lock_key: String,
+   pub recent_opponents_key: String,
+   pub recent_opponent_expiries_key: String,
+   /// Prefix of the keys the wait times of the queue are recorded in, one key per time slot
+   pub wait_times_key: String,
+   /// Mmr the next match of the queue resumes scanning at
+   pub cursor_key: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct QueueType {
     pub game_type: GameType,
     pub ranked: bool,
 }
 
+impl QueueType {
+    /// Every queue a pool can be defined for: each real-time game type, ranked and normal. Scripts
+    /// leaving the queues go through all of them, so players are still removed from the queues of
+    /// pools that were disabled or dropped meanwhile.
+    pub fn all() -> Vec<QueueType> {
+        let game_types = vec![
+            GameType::Rapid10_0,
+            GameType::Blitz5_3,
+            GameType::Blitz5_0,
+            GameType::Blitz3_2,
+            GameType::Blitz3_0,
+            GameType::Bullet1_0,
+        ];
+
+        game_types.into_iter()
+            .flat_map(|game_type| {
+                [true, false]
+                    .into_iter()
+                    .map(move |ranked| QueueType {
+                        game_type,
+                        ranked,
+                    })
+            })
+            .collect()
+    }
+
+    /// Id of the queue as listed in the account status, e.g. `Blitz5_0:ranked`.
+    pub fn pool_id(&self) -> String {
+        format!("{}:{}", self.game_type.to_str(), if self.ranked { "ranked" } else { "normal" })
+    }
+
+    pub fn from_pool_id(pool_id: &str) -> Option<QueueType> {
+        let (game_type, ranked) = pool_id.split_once(':')?;
+
+        Some(QueueType {
+            game_type: GameType::from_str(game_type).ok()?,
+            ranked: match ranked {
+                "ranked" => true,
+                "normal" => false,
+                _ => return None,
+            },
+        })
+    }
+}
+
+/// Players matched in one batch of a queue scan.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MatchBatch {
+    /// Flat list where every two consecutive players are a match
+    pub matched_players: Vec<String>,
+    /// Whether the scan reached the end of the queue, the next batch starts over from its lowest
+    /// mmr
+    pub scan_complete: bool,
+}
+
+/// Search window of a queue, widening with the time a player waited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct QueueConfig {
     pub base_mmr_range: i32,
     pub mmr_range_increase_per_second: i32,
@@ -71,37 +138,70 @@ pub struct QueueConfig {
 
 #[async_trait::async_trait]
 pub trait MatchmakingQueueContract: Send + Sync {
+    /// Matches the next batch of at most `batch_size` players of the queue, resuming where the
+    /// previous batch left off. Players who recently played each other are only matched again
+    /// once both waited for `repeat_opponent_wait_ms`.
     async fn match_players_in_queue(
         &self,
         game_type: &GameType,
         ranked: bool,
         queue_config: &QueueConfig,
-    ) -> redis::RedisResult<Vec<String>>;
+        repeat_opponent_wait_ms: u64,
+        batch_size: usize,
+    ) -> redis::RedisResult<MatchBatch>;
 
+    /// Adds a player to every given queue at once, each with the player's mmr in that queue.
+    /// Adds a player to the queues unless they already have a status, which is returned then.
     async fn add_player_to_queue(
         &self,
         account_id: &str,
-        mmr: u16,
-        game_type: &GameType,
-        ranked: bool
-    ) -> redis::RedisResult<()>;
+        pools: &[(QueueType, u16)],
+        status_ttl_seconds: u64,
+    ) -> redis::RedisResult<Option<PlayerStatus>>;
 
+    /// Removes a player from every queue. Returns the number of queues the player was in and
+    /// whether their searching status was cleared.
     async fn remove_player_from_queue(
         &self,
         account_id: &str,
-        game_type: GameType,
-        ranked: bool,
-    ) -> redis::RedisResult<i32>;
+    ) -> redis::RedisResult<(i32, bool)>;
 
     async fn get_queue_sizes(
         &self,
         queue_types: Vec<QueueType>,
     ) -> Result<HashMap<String, QueueSize>, MatchmakingServiceError>;
+
+    /// Mmr the player waits with in a queue, `None` if the player isn't in it.
+    async fn get_player_mmr(
+        &self,
+        account_id: &str,
+        queue_type: &QueueType,
+    ) -> redis::RedisResult<Option<u16>>;
+
+    /// Takes the queue's lock for `ttl_ms` unless another matcher holds it.
+    async fn acquire_queue_lock(
+        &self,
+        game_type: &GameType,
+        ranked: bool,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> redis::RedisResult<bool>;
+
+    /// Makes the queue's lock expire in `ttl_ms` if `owner` still holds it. Returns false if the
+    /// lock was lost.
+    async fn extend_queue_lock(
+        &self,
+        game_type: &GameType,
+        ranked: bool,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> redis::RedisResult<bool>;
 }
 
 const MATCH_PLAYERS_SCRIPT: &str = include_str!("lua-scripts/match-players.lua");
 const ADD_PLAYER_TO_QUEUE_SCRIPT: &str = include_str!("lua-scripts/add-player-to-queue.lua");
 const REMOVE_PLAYER_FROM_QUEUE_SCRIPT: &str = include_str!("lua-scripts/remove-player-from-queue.lua");
+const EXTEND_QUEUE_LOCK_SCRIPT: &str = include_str!("lua-scripts/extend-queue-lock.lua");
 
 pub struct RedisMatchmakingQueue {
     client: redis::Client,
@@ -131,17 +231,60 @@ impl RedisMatchmakingQueue {
         }
     }
 
-    fn get_queue_keys(game_type: &GameType, ranked: bool) -> QueueKeys {
+    pub fn get_queue_keys(game_type: &GameType, ranked: bool) -> QueueKeys {
         let ranked_value = match ranked {
             true => "ranked",
             false => "normal"
         };
 
+        let queue_key = format!("{}:queue:{}:{}", MATCHMAKING_KEY_PREFIX, game_type.to_str(), ranked_value);
+
         QueueKeys {
-            queue_key: format!("matchmaking:queue:{}:{}", game_type.to_str(), ranked_value),
-            times_key: format!("matchmaking:queue:{}:{}:times", game_type.to_str(), ranked_value)
+            times_key: format!("{}:times", queue_key),
+            lock_key: format!("{}:lock", queue_key),
+            recent_opponents_key: format!("{}:recent-opponents", queue_key),
+            recent_opponent_expiries_key: format!("{}:recent-opponents:expiries", queue_key),
+            wait_times_key: format!("{}:wait-times", queue_key),
+            cursor_key: format!("{}:cursor", queue_key),
+            queue_key,
         }
     }
+
+    /// Queue key and join times key of every queue, in the order scripts expect them.
+    pub fn pool_keys(queue_types: &[QueueType]) -> Vec<String> {
+        queue_types
+            .iter()
+            .flat_map(|queue_type| {
+                let queue_keys = Self::get_queue_keys(&queue_type.game_type, queue_type.ranked);
+                [queue_keys.queue_key, queue_keys.times_key]
+            })
+            .collect()
+    }
+
+    pub fn match_players_keys(queue_type: &QueueType) -> Vec<String> {
+        let queue_keys = Self::get_queue_keys(&queue_type.game_type, queue_type.ranked);
+
+        vec![
+            queue_keys.queue_key,
+            queue_keys.times_key,
+            queue_keys.recent_opponents_key,
+            queue_keys.cursor_key,
+        ]
+    }
+
+    pub fn add_player_to_queue_keys(account_id: &str, pools: &[(QueueType, u16)]) -> Vec<String> {
+        let queue_types: Vec<QueueType> = pools.iter().map(|(queue_type, _)| *queue_type).collect();
+
+        let mut keys = vec![PlayerStatusRepositoryService::get_account_status_key(account_id)];
+        keys.extend(Self::pool_keys(&queue_types));
+        keys
+    }
+
+    pub fn remove_player_from_queue_keys(account_id: &str) -> Vec<String> {
+        let mut keys = vec![PlayerStatusRepositoryService::get_account_status_key(account_id)];
+        keys.extend(Self::pool_keys(&QueueType::all()));
+        keys
+    }
 }
 
 #[async_trait::async_trait]
@@ -151,8 +294,10 @@ impl MatchmakingQueueContract for RedisMatchmakingQueue {
         game_type: &GameType,
         ranked: bool,
         queue_config: &QueueConfig,
-    ) -> redis::RedisResult<Vec<String>> {
-        let queue_keys = Self::get_queue_keys(&game_type, ranked);
+        repeat_opponent_wait_ms: u64,
+        batch_size: usize,
+    ) -> redis::RedisResult<MatchBatch> {
+        let queue_type = QueueType { game_type: *game_type, ranked };
         let scripts = self.scripts.lock().await;
         let script = scripts.get("matchPlayers")
             .ok_or_else(|| redis::RedisError::from((
@@ -160,25 +305,24 @@ impl MatchmakingQueueContract for RedisMatchmakingQueue {
                 "matchPlayers script not found",
             )))?;
 
-        let result: Vec<String> = script
-            .key(&queue_keys.queue_key)
-            .key(&queue_keys.times_key)
+        let (scan_complete, matched_players): (bool, Vec<String>) = redis_keys::prepare_invoke(script, &Self::match_players_keys(&queue_type))?
             .arg(queue_config.base_mmr_range)
             .arg(queue_config.mmr_range_increase_per_second)
             .arg(queue_config.max_mmr_delta)
+            .arg(repeat_opponent_wait_ms)
+            .arg(batch_size)
             .invoke_async(&mut self.client.get_multiplexed_async_connection().await?)
             .await?;
 
-        Ok(result)
+        Ok(MatchBatch { matched_players, scan_complete })
     }
 
     async fn add_player_to_queue(
         &self,
         account_id: &str,
-        mmr: u16,
-        game_type: &GameType,
-        ranked: bool
-    ) -> redis::RedisResult<()> {
+        pools: &[(QueueType, u16)],
+        status_ttl_seconds: u64,
+    ) -> redis::RedisResult<Option<PlayerStatus>> {
         let scripts = self.scripts.lock().await;
         let script = scripts.get("addPlayerToQueue")
             .ok_or_else(|| redis::RedisError::from((
@@ -186,34 +330,31 @@ impl MatchmakingQueueContract for RedisMatchmakingQueue {
                 "addPlayerToQueue script not found",
             )))?;
 
-        let queue_keys = Self::get_queue_keys(&game_type, ranked);
-        let account_status_key = PlayerStatusRepositoryService::get_account_status_key(account_id);
         let player_status = PlayerStatus::Searching;
 
-        let result: () = script
-            .key(&queue_keys.queue_key)
-            .key(&queue_keys.times_key)
-            .key(&account_status_key)
+        let mut invocation = redis_keys::prepare_invoke(script, &Self::add_player_to_queue_keys(account_id, pools))?;
+        invocation
             .arg(player_status.as_str())
             .arg(account_id)
-            .arg(mmr)
-            .arg(ranked)
+            .arg(status_ttl_seconds);
+
+        for (queue_type, mmr) in pools {
+            invocation
+                .arg(queue_type.pool_id())
+                .arg(mmr);
+        }
+
+        let status: Option<String> = invocation
             .invoke_async(&mut self.client.get_multiplexed_async_connection().await?)
             .await?;
 
-        Ok(result)
+        Ok(status.map(|status| status.parse().unwrap_or(PlayerStatus::Undefined)))
     }
 
     async fn remove_player_from_queue(
         &self,
         account_id: &str,
-        game_type: GameType,
-        ranked: bool,
-    ) -> redis::RedisResult<i32> {
-
-        let account_status_key = PlayerStatusRepositoryService::get_account_status_key(account_id);
-        let queue_keys = Self::get_queue_keys(&game_type, ranked);
-
+    ) -> redis::RedisResult<(i32, bool)> {
         let scripts = self.scripts.lock().await;
         let script = scripts.get("removePlayerFromQueue")
             .ok_or_else(|| redis::RedisError::from((
@@ -221,14 +362,10 @@ impl MatchmakingQueueContract for RedisMatchmakingQueue {
                 "removePlayerFromQueue script not found",
             )))?;
 
-        let result: i32 = script
-            .key(&queue_keys.queue_key)
-            .key(&queue_keys.times_key)
-            .key(&account_status_key)
-            .arg(account_id)
-            .arg(game_type)
-            .arg(ranked)
-            // .invoke_async(&mut self.client.get_async_connection().await?)
+        let mut invocation = redis_keys::prepare_invoke(script, &Self::remove_player_from_queue_keys(account_id))?;
+        invocation.arg(account_id);
+
+        let result: (i32, bool) = invocation
             .invoke_async(&mut self.client.get_multiplexed_async_connection().await?)
             .await?;
 
@@ -270,4 +407,61 @@ impl MatchmakingQueueContract for RedisMatchmakingQueue {
 
         Ok(queue_sizes)
     }
+
+    async fn get_player_mmr(
+        &self,
+        account_id: &str,
+        queue_type: &QueueType,
+    ) -> redis::RedisResult<Option<u16>> {
+        let queue_keys = Self::get_queue_keys(&queue_type.game_type, queue_type.ranked);
+
+        let mmr: Option<f64> = redis::cmd("ZSCORE")
+            .arg(&queue_keys.queue_key)
+            .arg(account_id)
+            .query_async(&mut self.client.get_multiplexed_async_connection().await?)
+            .await?;
+
+        Ok(mmr.map(|mmr| mmr as u16))
+    }
+
+    async fn acquire_queue_lock(
+        &self,
+        game_type: &GameType,
+        ranked: bool,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> redis::RedisResult<bool> {
+        let queue_keys = Self::get_queue_keys(game_type, ranked);
+
+        let result: Option<String> = redis::cmd("SET")
+            .arg(&queue_keys.lock_key)
+            .arg(owner)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_ms)
+            .query_async(&mut self.client.get_multiplexed_async_connection().await?)
+            .await?;
+
+        Ok(result.is_some())
+    }
+
+    async fn extend_queue_lock(
+        &self,
+        game_type: &GameType,
+        ranked: bool,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> redis::RedisResult<bool> {
+        let queue_keys = Self::get_queue_keys(game_type, ranked);
+
+        let script = redis::Script::new(EXTEND_QUEUE_LOCK_SCRIPT);
+        let result: i32 = script
+            .key(&queue_keys.lock_key)
+            .arg(owner)
+            .arg(ttl_ms)
+            .invoke_async(&mut self.client.get_multiplexed_async_connection().await?)
+            .await?;
+
+        Ok(result == 1)
+    }
 }
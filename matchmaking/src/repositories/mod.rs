@@ -1,3 +1,11 @@
 pub mod matchmaking_queue_repository;
+pub mod pending_game_repository;
 pub mod player_status_repository;
 pub mod ranking_repository;
+pub mod leaderboard_repository;
+pub mod season_repository;
+pub mod color_history_repository;
+pub mod wait_time_repository;
+pub mod in_memory_matchmaking_queue;
+pub mod redis_keys;
+pub mod queue_penalty_repository;
@@ -0,0 +1,384 @@
+use std::{collections::HashMap, str::FromStr};
+
+use redis::AsyncCommands;
+use shared::primitives::GameType;
+
+use crate::{error::MatchmakingServiceError, repositories::{matchmaking_queue_repository::{PlayerStatus, QueueType, RedisMatchmakingQueue}, player_status_repository::PlayerStatusRepositoryService, redis_keys::{self, MATCHMAKING_KEY_PREFIX}, wait_time_repository::{RedisWaitTimeRepository, WAIT_TIME_SLOTS, WAIT_TIME_SLOT_MS}}, services::wait_time::RATING_BAND_WIDTH};
+
+const CREATE_PENDING_GAME_SCRIPT: &str = include_str!("lua-scripts/create-pending-game.lua");
+const ACCEPT_PENDING_GAME_SCRIPT: &str = include_str!("lua-scripts/accept-pending-game.lua");
+const TIME_OUT_PENDING_GAME_SCRIPT: &str = include_str!("lua-scripts/time-out-pending-game.lua");
+
+/// Recent opponents kept per player and queue
+const MAX_RECENT_OPPONENTS: usize = 3;
+
+/// Sorted set of pending game ids, scored by the time their accept window closes (ms).
+pub const PENDING_GAME_TIMEOUTS_KEY: &str = "{matchmaking}:pending-games:timeouts";
+
+pub struct PendingGame {
+    pub id: String,
+    pub game_type: GameType,
+    pub ranked: bool,
+    pub account_id_0: String,
+    pub account_id_1: String,
+    pub mmr_0: i64,
+    pub mmr_1: i64,
+    /// Unix timestamp (ms) after which the pending game times out
+    pub expires_at: i64,
+}
+
+pub enum AcceptPendingGameOutcome {
+    /// Waiting for the other player
+    Waiting,
+    /// Both players accepted, the caller creates the game
+    Ready,
+    /// Both players had already accepted
+    AlreadyAccepted,
+}
+
+/// How a timed out or declined pending game ended for its players.
+pub struct PendingGameEnd {
+    /// Whether each player had accepted
+    pub accepted: [bool; 2],
+    /// Status each player went to, `None` if they had moved on and were left alone
+    pub statuses: [Option<PlayerStatus>; 2],
+}
+
+#[async_trait::async_trait]
+pub trait PendingGameRepository: Send + Sync {
+    /// Moves two matched players out of every queue they wait in into a new pending game and
+    /// records them as each other's recent opponent. Returns `None` if one of them is not
+    /// searching in the queue they were matched in anymore.
+    #[allow(clippy::too_many_arguments)]
+    async fn create_pending_game(
+        &self,
+        game_type: &GameType,
+        ranked: bool,
+        account_id_0: &str,
+        account_id_1: &str,
+        timeout_ms: u64,
+        recent_opponent_ttl_ms: u64,
+        status_ttl_seconds: u64,
+    ) -> Result<Option<PendingGame>, MatchmakingServiceError>;
+
+    async fn find_pending_game(
+        &self,
+        pending_game_id: &str,
+    ) -> Result<Option<PendingGame>, MatchmakingServiceError>;
+
+    async fn accept_pending_game(
+        &self,
+        pending_game_id: &str,
+        account_id: &str,
+    ) -> Result<AcceptPendingGameOutcome, MatchmakingServiceError>;
+
+    /// Ids of the pending games whose accept window has closed.
+    async fn find_expired_pending_game_ids(
+        &self,
+        now_ms: i64,
+    ) -> Result<Vec<String>, MatchmakingServiceError>;
+
+    /// Requeues the players who accepted, searching for `status_ttl_seconds`, and drops the
+    /// others. Returns `None` if the pending game is gone, still open or already accepted and
+    /// `force` is not set.
+    async fn time_out_pending_game(
+        &self,
+        pending_game: &PendingGame,
+        force: bool,
+        status_ttl_seconds: u64,
+    ) -> Result<Option<PendingGameEnd>, MatchmakingServiceError>;
+
+    /// Ends a pending game declined by one of its players, the other one goes back into their
+    /// queues. Returns `None` if the pending game is gone or already accepted.
+    async fn decline_pending_game(
+        &self,
+        pending_game: &PendingGame,
+        account_id: &str,
+        status_ttl_seconds: u64,
+    ) -> Result<Option<PendingGameEnd>, MatchmakingServiceError>;
+
+    async fn remove_pending_game_timeout(
+        &self,
+        pending_game_id: &str,
+    ) -> Result<(), MatchmakingServiceError>;
+
+    async fn delete_pending_game(
+        &self,
+        pending_game_id: &str,
+    ) -> Result<(), MatchmakingServiceError>;
+}
+
+pub struct RedisPendingGameRepository {
+    client: redis::Client,
+}
+
+impl RedisPendingGameRepository {
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+
+    pub fn get_pending_game_key(pending_game_id: &str) -> String {
+        format!("{}:pending-game:{}", MATCHMAKING_KEY_PREFIX, pending_game_id)
+    }
+
+    pub fn create_pending_game_keys(
+        queue_type: &QueueType,
+        account_id_0: &str,
+        account_id_1: &str,
+        pending_game_id: &str,
+        now_ms: u64,
+    ) -> Vec<String> {
+        let queue_keys = RedisMatchmakingQueue::get_queue_keys(&queue_type.game_type, queue_type.ranked);
+
+        let mut keys = vec![
+            queue_keys.queue_key,
+            queue_keys.times_key,
+            PlayerStatusRepositoryService::get_account_status_key(account_id_0),
+            PlayerStatusRepositoryService::get_account_status_key(account_id_1),
+            Self::get_pending_game_key(pending_game_id),
+            PENDING_GAME_TIMEOUTS_KEY.to_owned(),
+            queue_keys.recent_opponents_key,
+            queue_keys.recent_opponent_expiries_key,
+            RedisWaitTimeRepository::get_wait_times_key(queue_type, now_ms),
+        ];
+        keys.extend(RedisMatchmakingQueue::pool_keys(&QueueType::all()));
+        keys
+    }
+
+    pub fn accept_pending_game_keys(pending_game_id: &str) -> Vec<String> {
+        vec![Self::get_pending_game_key(pending_game_id), PENDING_GAME_TIMEOUTS_KEY.to_owned()]
+    }
+
+    pub fn time_out_pending_game_keys(
+        queue_type: &QueueType,
+        account_id_0: &str,
+        account_id_1: &str,
+        pending_game_id: &str,
+    ) -> Vec<String> {
+        let queue_keys = RedisMatchmakingQueue::get_queue_keys(&queue_type.game_type, queue_type.ranked);
+
+        let mut keys = vec![
+            Self::get_pending_game_key(pending_game_id),
+            PENDING_GAME_TIMEOUTS_KEY.to_owned(),
+            PlayerStatusRepositoryService::get_account_status_key(account_id_0),
+            PlayerStatusRepositoryService::get_account_status_key(account_id_1),
+            queue_keys.queue_key,
+            queue_keys.times_key,
+        ];
+        keys.extend(RedisMatchmakingQueue::pool_keys(&QueueType::all()));
+        keys
+    }
+
+    fn map_redis_data_to_pending_game(
+        pending_game_id: &str,
+        data: HashMap<String, String>,
+    ) -> Option<PendingGame> {
+        let number = |field: &str| data.get(field).and_then(|value| value.parse::<i64>().ok());
+
+        Some(PendingGame {
+            id: pending_game_id.to_owned(),
+            game_type: GameType::from_str(data.get("gameType")?).ok()?,
+            ranked: data.get("ranked")? == "1",
+            account_id_0: data.get("accountId0")?.to_owned(),
+            account_id_1: data.get("accountId1")?.to_owned(),
+            mmr_0: number("mmr0")?,
+            mmr_1: number("mmr1")?,
+            expires_at: number("expiresAt")?,
+        })
+    }
+
+    /// Runs `time-out-pending-game.lua`, declined by `declined_by` if given.
+    async fn end_pending_game(
+        &self,
+        pending_game: &PendingGame,
+        force: bool,
+        declined_by: Option<&str>,
+        status_ttl_seconds: u64,
+    ) -> Result<Option<PendingGameEnd>, MatchmakingServiceError> {
+        let queue_type = QueueType { game_type: pending_game.game_type, ranked: pending_game.ranked };
+        let keys = Self::time_out_pending_game_keys(
+            &queue_type,
+            &pending_game.account_id_0,
+            &pending_game.account_id_1,
+            &pending_game.id,
+        );
+
+        let script = redis::Script::new(TIME_OUT_PENDING_GAME_SCRIPT);
+        let mut invocation = redis_keys::prepare_invoke(&script, &keys)?;
+        invocation
+            .arg(&pending_game.id)
+            .arg(force)
+            .arg(queue_type.pool_id())
+            .arg(declined_by.unwrap_or(""))
+            .arg(status_ttl_seconds);
+        for pool in QueueType::all() {
+            invocation.arg(pool.pool_id());
+        }
+
+        let result: Option<(bool, bool, String, String)> = invocation
+            .invoke_async(&mut self.client.get_multiplexed_async_connection().await?)
+            .await?;
+
+        Ok(result.map(|(accepted_0, accepted_1, status_0, status_1)| PendingGameEnd {
+            accepted: [accepted_0, accepted_1],
+            statuses: [status_0.parse().ok(), status_1.parse().ok()],
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+impl PendingGameRepository for RedisPendingGameRepository {
+    async fn create_pending_game(
+        &self,
+        game_type: &GameType,
+        ranked: bool,
+        account_id_0: &str,
+        account_id_1: &str,
+        timeout_ms: u64,
+        recent_opponent_ttl_ms: u64,
+        status_ttl_seconds: u64,
+    ) -> Result<Option<PendingGame>, MatchmakingServiceError> {
+        let pending_game_id = uuid::Uuid::new_v4().to_string();
+        let queue_type = QueueType { game_type: *game_type, ranked };
+        let keys = Self::create_pending_game_keys(
+            &queue_type,
+            account_id_0,
+            account_id_1,
+            &pending_game_id,
+            chrono::Utc::now().timestamp_millis() as u64,
+        );
+
+        let script = redis::Script::new(CREATE_PENDING_GAME_SCRIPT);
+        let mut invocation = redis_keys::prepare_invoke(&script, &keys)?;
+        invocation
+            .arg(&pending_game_id)
+            .arg(account_id_0)
+            .arg(account_id_1)
+            .arg(game_type)
+            .arg(ranked)
+            .arg(timeout_ms)
+            .arg(recent_opponent_ttl_ms)
+            .arg(MAX_RECENT_OPPONENTS)
+            .arg(queue_type.pool_id())
+            .arg(RATING_BAND_WIDTH)
+            .arg(WAIT_TIME_SLOT_MS * WAIT_TIME_SLOTS)
+            .arg(status_ttl_seconds);
+        for pool in QueueType::all() {
+            invocation.arg(pool.pool_id());
+        }
+
+        let result: Option<(i64, i64, i64)> = invocation
+            .invoke_async(&mut self.client.get_multiplexed_async_connection().await?)
+            .await?;
+
+        Ok(result.map(|(expires_at, mmr_0, mmr_1)| PendingGame {
+            id: pending_game_id,
+            game_type: *game_type,
+            ranked,
+            account_id_0: account_id_0.to_owned(),
+            account_id_1: account_id_1.to_owned(),
+            mmr_0,
+            mmr_1,
+            expires_at,
+        }))
+    }
+
+    async fn find_pending_game(
+        &self,
+        pending_game_id: &str,
+    ) -> Result<Option<PendingGame>, MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let data: HashMap<String, String> = conn
+            .hgetall(Self::get_pending_game_key(pending_game_id))
+            .await?;
+
+        Ok(Self::map_redis_data_to_pending_game(pending_game_id, data))
+    }
+
+    async fn accept_pending_game(
+        &self,
+        pending_game_id: &str,
+        account_id: &str,
+    ) -> Result<AcceptPendingGameOutcome, MatchmakingServiceError> {
+        let script = redis::Script::new(ACCEPT_PENDING_GAME_SCRIPT);
+        let result: i32 = redis_keys::prepare_invoke(&script, &Self::accept_pending_game_keys(pending_game_id))?
+            .arg(pending_game_id)
+            .arg(account_id)
+            .invoke_async(&mut self.client.get_multiplexed_async_connection().await?)
+            .await?;
+
+        match result {
+            2 => Ok(AcceptPendingGameOutcome::Ready),
+            1 => Ok(AcceptPendingGameOutcome::Waiting),
+            0 => Ok(AcceptPendingGameOutcome::AlreadyAccepted),
+            -2 => Err(MatchmakingServiceError::PendingGameError(
+                format!("Account {} is not a player of pending game {}", account_id, pending_game_id)
+            )),
+            _ => Err(MatchmakingServiceError::PendingGameError(
+                format!("Pending game {} not found or expired", pending_game_id)
+            )),
+        }
+    }
+
+    async fn find_expired_pending_game_ids(
+        &self,
+        now_ms: i64,
+    ) -> Result<Vec<String>, MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let pending_game_ids: Vec<String> = conn
+            .zrangebyscore(PENDING_GAME_TIMEOUTS_KEY, "-inf", now_ms)
+            .await?;
+
+        Ok(pending_game_ids)
+    }
+
+    async fn time_out_pending_game(
+        &self,
+        pending_game: &PendingGame,
+        force: bool,
+        status_ttl_seconds: u64,
+    ) -> Result<Option<PendingGameEnd>, MatchmakingServiceError> {
+        self.end_pending_game(pending_game, force, None, status_ttl_seconds).await
+    }
+
+    async fn decline_pending_game(
+        &self,
+        pending_game: &PendingGame,
+        account_id: &str,
+        status_ttl_seconds: u64,
+    ) -> Result<Option<PendingGameEnd>, MatchmakingServiceError> {
+        self.end_pending_game(pending_game, false, Some(account_id), status_ttl_seconds).await
+    }
+
+    async fn remove_pending_game_timeout(
+        &self,
+        pending_game_id: &str,
+    ) -> Result<(), MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let _: () = conn.zrem(PENDING_GAME_TIMEOUTS_KEY, pending_game_id).await?;
+
+        Ok(())
+    }
+
+    async fn delete_pending_game(
+        &self,
+        pending_game_id: &str,
+    ) -> Result<(), MatchmakingServiceError> {
+        let pending_game_key = Self::get_pending_game_key(pending_game_id);
+        redis_keys::check_same_slot(&[pending_game_key.as_str(), PENDING_GAME_TIMEOUTS_KEY])?;
+
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let _: () = redis::pipe()
+            .atomic()
+            .del(&pending_game_key)
+            .zrem(PENDING_GAME_TIMEOUTS_KEY, pending_game_id)
+            .query_async(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use redis::AsyncCommands;
+use shared::primitives::GameType;
 
-use crate::{error::MatchmakingServiceError, repositories::matchmaking_queue_repository::PlayerStatus, services::player_status_service::MatchMakingStatus};
+use crate::{error::MatchmakingServiceError, repositories::{matchmaking_queue_repository::{PlayerStatus, QueueType}, redis_keys::MATCHMAKING_KEY_PREFIX}, services::player_status_service::MatchMakingStatus};
 
 #[async_trait::async_trait]
 pub trait PlayerStatusRepository: Send + Sync {
@@ -9,8 +10,47 @@ pub trait PlayerStatusRepository: Send + Sync {
         &self,
         account_id: &str,
     ) -> Result<MatchMakingStatus, MatchmakingServiceError>;
+
+    /// Sets a player playing if they are in one of the `from` statuses and not already playing the
+    /// game. Returns whether the status changed, with the previous status if so and the current
+    /// one otherwise.
+    async fn set_player_playing(
+        &self,
+        account_id: &str,
+        game_id: &str,
+        game_type: &GameType,
+        ranked: bool,
+        from: &[PlayerStatus],
+        ttl_seconds: u64,
+    ) -> Result<(bool, PlayerStatus), MatchmakingServiceError>;
+
+    /// Clears the status of a player who is playing the game. Returns whether it was cleared.
+    async fn clear_player_game(
+        &self,
+        account_id: &str,
+        game_id: &str,
+    ) -> Result<bool, MatchmakingServiceError>;
+
+    /// Reads the statuses of up to about `count` players, starting at `cursor` (0 to start a scan).
+    /// Returns the cursor to continue at, 0 once every status was read.
+    async fn scan_player_statuses(
+        &self,
+        cursor: u64,
+        count: usize,
+    ) -> Result<(u64, Vec<(String, MatchMakingStatus)>), MatchmakingServiceError>;
+
+    async fn acquire_reconcile_lock(
+        &self,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> Result<bool, MatchmakingServiceError>;
 }
 
+const SET_PLAYER_PLAYING_SCRIPT: &str = include_str!("lua-scripts/set-player-playing.lua");
+const CLEAR_PLAYER_GAME_SCRIPT: &str = include_str!("lua-scripts/clear-player-game.lua");
+
+const RECONCILE_LOCK_KEY: &str = "matchmaking:player-status:reconcile-lock";
+
 pub struct PlayerStatusRepositoryService {
     client: redis::Client,
 }
@@ -23,7 +63,14 @@ impl PlayerStatusRepositoryService {
     }
 
     pub fn get_account_status_key(account_id: &str) -> String {
-        format!("matchmaking:account:{}:status", account_id)
+        format!("{}:account:{}:status", MATCHMAKING_KEY_PREFIX, account_id)
+    }
+
+    fn get_account_id_from_status_key(account_status_key: &str) -> Option<&str> {
+        account_status_key
+            .strip_prefix(MATCHMAKING_KEY_PREFIX)?
+            .strip_prefix(":account:")?
+            .strip_suffix(":status")
     }
 
     fn map_redis_data_to_status(&self, data: HashMap<String, String>) -> MatchMakingStatus {
@@ -32,14 +79,20 @@ impl PlayerStatusRepositoryService {
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(PlayerStatus::Undefined),
 
-            game_type: data.get("game_type")
+            game_type: data.get("gameType")
                 .and_then(|gt| gt.parse().ok()),
 
+            // Written by the queue scripts as redis booleans (1/0)
             ranked: data.get("ranked")
-                .and_then(|r| r.parse().ok()),
+                .map(|r| r == "1" || r == "true"),
 
-            game_id: data.get("game_id")
+            game_id: data.get("gameId")
                 .map(|id| id.to_string()),
+
+            // Comma separated pool ids, written when joining the queues
+            pools: data.get("pools")
+                .map(|pools| pools.split(',').filter_map(QueueType::from_pool_id).collect())
+                .unwrap_or_default(),
         }
     }
 }
@@ -63,9 +116,112 @@ impl PlayerStatusRepository for PlayerStatusRepositoryService {
                 game_type: None,
                 ranked: None,
                 game_id: None,
+                pools: Vec::new(),
             })
         } else {
             Ok(self.map_redis_data_to_status(data))
         }
     }
+
+    async fn set_player_playing(
+        &self,
+        account_id: &str,
+        game_id: &str,
+        game_type: &GameType,
+        ranked: bool,
+        from: &[PlayerStatus],
+        ttl_seconds: u64,
+    ) -> Result<(bool, PlayerStatus), MatchmakingServiceError> {
+        let account_status_key = Self::get_account_status_key(account_id);
+
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let script = redis::Script::new(SET_PLAYER_PLAYING_SCRIPT);
+        let mut invocation = script.key(&account_status_key);
+        invocation
+            .arg(game_id)
+            .arg(game_type.to_str())
+            .arg(ranked)
+            .arg(ttl_seconds);
+        for status in from {
+            invocation.arg(status.as_str());
+        }
+
+        let (playing, status): (bool, String) = invocation
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok((playing, status.parse().unwrap_or(PlayerStatus::Undefined)))
+    }
+
+    async fn clear_player_game(
+        &self,
+        account_id: &str,
+        game_id: &str,
+    ) -> Result<bool, MatchmakingServiceError> {
+        let account_status_key = Self::get_account_status_key(account_id);
+
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let cleared: bool = redis::Script::new(CLEAR_PLAYER_GAME_SCRIPT)
+            .key(&account_status_key)
+            .arg(game_id)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(cleared)
+    }
+
+    async fn scan_player_statuses(
+        &self,
+        cursor: u64,
+        count: usize,
+    ) -> Result<(u64, Vec<(String, MatchMakingStatus)>), MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let (next_cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(Self::get_account_status_key("*"))
+            .arg("COUNT")
+            .arg(count)
+            .query_async(&mut conn)
+            .await?;
+
+        let mut pipeline = redis::pipe();
+        for key in &keys {
+            pipeline.hgetall(key);
+        }
+        let data: Vec<HashMap<String, String>> = pipeline.query_async(&mut conn).await?;
+
+        // Statuses that expired since the scan are left out
+        let statuses = keys
+            .iter()
+            .zip(data)
+            .filter(|(_, data)| !data.is_empty())
+            .filter_map(|(key, data)| Some((
+                Self::get_account_id_from_status_key(key)?.to_owned(),
+                self.map_redis_data_to_status(data),
+            )))
+            .collect();
+
+        Ok((next_cursor, statuses))
+    }
+
+    async fn acquire_reconcile_lock(
+        &self,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> Result<bool, MatchmakingServiceError> {
+        let result: Option<String> = redis::cmd("SET")
+            .arg(RECONCILE_LOCK_KEY)
+            .arg(owner)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_ms)
+            .query_async(&mut self.client.get_multiplexed_async_connection().await?)
+            .await?;
+
+        Ok(result.is_some())
+    }
 }
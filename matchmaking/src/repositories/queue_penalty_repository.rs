@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use redis::AsyncCommands;
+
+use crate::{error::MatchmakingServiceError, services::queue_penalty_service::QueuePenalty};
+
+const SAVE_QUEUE_PENALTY_SCRIPT: &str = include_str!("lua-scripts/save-queue-penalty.lua");
+
+#[async_trait::async_trait]
+pub trait QueuePenaltyRepository: Send + Sync {
+    async fn find_penalty(
+        &self,
+        account_id: &str,
+    ) -> Result<Option<QueuePenalty>, MatchmakingServiceError>;
+
+    /// Stores the penalty of a player after the offense committed in game or pending game
+    /// `offense_id`, dropped after `ttl_ms`. Returns false, leaving the stored penalty untouched,
+    /// if that offense was already recorded.
+    async fn save_penalty(
+        &self,
+        account_id: &str,
+        offense_id: &str,
+        penalty: &QueuePenalty,
+        ttl_ms: u64,
+    ) -> Result<bool, MatchmakingServiceError>;
+}
+
+pub struct RedisQueuePenaltyRepository {
+    client: redis::Client,
+}
+
+impl RedisQueuePenaltyRepository {
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+
+    pub fn get_queue_penalty_key(account_id: &str) -> String {
+        format!("matchmaking:account:{}:queue-penalty", account_id)
+    }
+
+    fn map_redis_data_to_penalty(data: HashMap<String, String>) -> Option<QueuePenalty> {
+        Some(QueuePenalty {
+            score: data.get("score")?.parse().ok()?,
+            updated_at_ms: data.get("updatedAt")?.parse().ok()?,
+            cooldown_until_ms: data.get("cooldownUntil")?.parse().ok()?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl QueuePenaltyRepository for RedisQueuePenaltyRepository {
+    async fn find_penalty(
+        &self,
+        account_id: &str,
+    ) -> Result<Option<QueuePenalty>, MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let data: HashMap<String, String> = conn
+            .hgetall(Self::get_queue_penalty_key(account_id))
+            .await?;
+
+        Ok(Self::map_redis_data_to_penalty(data))
+    }
+
+    async fn save_penalty(
+        &self,
+        account_id: &str,
+        offense_id: &str,
+        penalty: &QueuePenalty,
+        ttl_ms: u64,
+    ) -> Result<bool, MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let saved: i32 = redis::Script::new(SAVE_QUEUE_PENALTY_SCRIPT)
+            .key(Self::get_queue_penalty_key(account_id))
+            .arg(penalty.score)
+            .arg(penalty.updated_at_ms)
+            .arg(penalty.cooldown_until_ms)
+            .arg(ttl_ms)
+            .arg(offense_id)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(saved == 1)
+    }
+}
@@ -1,7 +1,17 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
+use chrono::{DateTime, Utc};
+use shared::primitives::RatingCategory;
 use uuid::Uuid;
 
-use crate::{client::database::{Database, PostgresDB}, error::MatchmakingServiceError, services::ranking_service::Ranking};
+use crate::{client::database::{Database, PostgresDB}, error::MatchmakingServiceError, services::{glicko2::Rating, ranking_service::{AppliedChange, Ranking, RankingUpdate, RatingPool}, rating_history::{GameResult, RatingHistoryPoint}}};
+
+pub enum RankingUpdateOutcome {
+    Updated,
+    /// The changes of that game were already recorded
+    AlreadyApplied,
+    /// A rating changed since it was read, e.g. by another game that ended at the same time
+    Conflict,
+}
 
 #[async_trait::async_trait]
 pub trait RankingRepository: Send + Sync {
@@ -14,6 +24,34 @@ pub trait RankingRepository: Send + Sync {
         &self,
         ranking: &Ranking,
     ) -> Result<(), MatchmakingServiceError>;
+
+    /// Records the MMR changes of a game and stores the players' new ratings, as long as their
+    /// ratings didn't change since they were read.
+    async fn update_rankings(
+        &self,
+        updates: &[RankingUpdate],
+    ) -> Result<RankingUpdateOutcome, MatchmakingServiceError>;
+
+    /// MMR changes recorded for a game.
+    async fn find_game_changes(
+        &self,
+        game_id: &str,
+    ) -> Result<Vec<AppliedChange>, MatchmakingServiceError>;
+
+    /// Rating after each game of a player in a pool, oldest first.
+    async fn find_rating_history(
+        &self,
+        account_id: &str,
+        ranked: bool,
+    ) -> Result<Vec<(RatingCategory, RatingHistoryPoint)>, MatchmakingServiceError>;
+
+    /// Ratings of a pool that were updated since `rated_since`, with their account ids. Ranked
+    /// ratings are those of the open season.
+    async fn find_pool_ratings(
+        &self,
+        pool: RatingPool,
+        rated_since: DateTime<Utc>,
+    ) -> Result<Vec<(String, Rating)>, MatchmakingServiceError>;
 }
 
 pub struct RankingRepositoryService {
@@ -25,10 +63,6 @@ impl RankingRepositoryService
     pub fn new(client: PostgresDB) -> Self {
         Self { client }
     }
-
-    // pub async fn update_rankings(updates: &[RankingUpdate]) {
-    //     todo!()
-    // }
 }
 
 #[async_trait::async_trait]
@@ -43,7 +77,7 @@ impl RankingRepository for RankingRepositoryService {
 
         let row = sqlx::query!(
             r#"
-            SELECT id, account_id, ranked_mmr, normal_mmr, created_at
+            SELECT id, account_id, created_at
             FROM rankings
             WHERE account_id = $1
             "#,
@@ -53,21 +87,59 @@ impl RankingRepository for RankingRepositoryService {
         .await
         .map_err(|e| MatchmakingServiceError::Db(e))?;
 
+        let Some(record) = row else {
+            tx.commit().await?;
+            return Ok(None);
+        };
+
+        // Ranked ratings are those of the open season, a closed one's results are final. While
+        // none is open, they are the ones from before the first season
+        let season_id = sqlx::query_scalar!(
+            "SELECT id FROM seasons WHERE closed_at IS NULL"
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let rating_rows = sqlx::query!(
+            r#"
+            SELECT category, is_ranked, rating, deviation, volatility, rated_at
+            FROM ratings
+            WHERE account_id = $1 AND (NOT is_ranked OR season_id IS NOT DISTINCT FROM $2)
+            "#,
+            account_id,
+            season_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
         tx.commit()
             .await
             .map_err(|e| MatchmakingServiceError::Db(e))?;
 
-        if let Some(record) = row {
-            return Ok(Some(Ranking {
-                id: record.id.to_string(),
-                account_id: record.account_id,
-                ranked_mmr: record.ranked_mmr as u16,
-                normal_mmr: record.normal_mmr as u16,
-                created_at: record.created_at.unwrap(),
-            }));
+        let mut ratings = HashMap::new();
+
+        for rating_row in rating_rows {
+            let category = RatingCategory::from_str(&rating_row.category)
+                .map_err(|e| MatchmakingServiceError::UnknownGameTypeError(e.to_string()))?;
+
+            ratings.insert(
+                RatingPool { category, ranked: rating_row.is_ranked },
+                Rating {
+                    rating: rating_row.rating,
+                    deviation: rating_row.deviation,
+                    volatility: rating_row.volatility,
+                    rated_at: Some(rating_row.rated_at),
+                },
+            );
         }
 
-        Ok(None)
+        Ok(Some(Ranking {
+            id: record.id.to_string(),
+            account_id: record.account_id,
+            ratings,
+            season_id: season_id.map(|id| id.to_string()),
+            created_at: record.created_at.unwrap(),
+        }))
     }
 
     async fn insert_ranking(
@@ -78,11 +150,9 @@ impl RankingRepository for RankingRepositoryService {
             .await?;
 
         sqlx::query!(
-            "INSERT INTO rankings (id, account_id, ranked_mmr, normal_mmr, created_at) VALUES ($1, $2, $3, $4, $5)",
+            "INSERT INTO rankings (id, account_id, created_at) VALUES ($1, $2, $3)",
             Uuid::from_str(ranking.id.as_str())?,
             ranking.account_id,
-            ranking.ranked_mmr as i32,
-            ranking.normal_mmr as i32,
             ranking.created_at
         )
         .execute(&mut *tx)
@@ -92,4 +162,207 @@ impl RankingRepository for RankingRepositoryService {
 
         Ok(())
     }
+
+    async fn update_rankings(
+        &self,
+        updates: &[RankingUpdate],
+    ) -> Result<RankingUpdateOutcome, MatchmakingServiceError> {
+        let mut tx = self.client.begin_tx()
+            .await?;
+
+        for update in updates {
+            let season_id = update.season_id.as_deref().map(Uuid::from_str).transpose()?;
+
+            // The unique (game_id, account_id) index makes redelivered game results a no-op
+            let inserted = sqlx::query!(
+                r#"
+                INSERT INTO mmr_changes (id, account_id, game_id, game_type, mmr_change, is_ranked, category, rating, result, season_id)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                ON CONFLICT (game_id, account_id) DO NOTHING
+                "#,
+                Uuid::new_v4(),
+                update.account_id,
+                update.game_id,
+                update.game_type.to_str(),
+                update.mmr_change,
+                update.pool.ranked,
+                update.pool.category.to_str(),
+                update.rating.rating,
+                update.result.to_str(),
+                season_id
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            if inserted == 0 {
+                tx.rollback().await?;
+                return Ok(RankingUpdateOutcome::AlreadyApplied);
+            }
+
+            let Rating { rating, deviation, volatility, rated_at } = update.rating;
+            let rated_at = rated_at.unwrap_or_else(chrono::Utc::now);
+
+            // A player's first rated game in a pool creates its rating
+            let updated = match update.previous_rated_at {
+                None => sqlx::query!(
+                    r#"
+                    INSERT INTO ratings (id, account_id, category, is_ranked, rating, deviation, volatility, rated_at, season_id)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                    ON CONFLICT (account_id, category, is_ranked, season_id) DO NOTHING
+                    "#,
+                    Uuid::new_v4(),
+                    update.account_id,
+                    update.pool.category.to_str(),
+                    update.pool.ranked,
+                    rating,
+                    deviation,
+                    volatility,
+                    rated_at,
+                    season_id
+                )
+                .execute(&mut *tx)
+                .await?
+                .rows_affected(),
+                Some(previous_rated_at) => sqlx::query!(
+                    r#"
+                    UPDATE ratings
+                    SET rating = $4, deviation = $5, volatility = $6, rated_at = $7,
+                        updated_at = current_timestamp
+                    WHERE account_id = $1 AND category = $2 AND is_ranked = $3 AND rated_at = $8
+                        AND season_id IS NOT DISTINCT FROM $9
+                    "#,
+                    update.account_id,
+                    update.pool.category.to_str(),
+                    update.pool.ranked,
+                    rating,
+                    deviation,
+                    volatility,
+                    rated_at,
+                    previous_rated_at,
+                    season_id
+                )
+                .execute(&mut *tx)
+                .await?
+                .rows_affected(),
+            };
+
+            if updated == 0 {
+                tx.rollback().await?;
+                return Ok(RankingUpdateOutcome::Conflict);
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(RankingUpdateOutcome::Updated)
+    }
+
+    async fn find_game_changes(
+        &self,
+        game_id: &str,
+    ) -> Result<Vec<AppliedChange>, MatchmakingServiceError> {
+        let mut tx = self.client.begin_tx()
+            .await?;
+
+        // Changes recorded before ratings were kept can't be announced again
+        let rows = sqlx::query!(
+            r#"
+            SELECT account_id, is_ranked, category AS "category!", mmr_change, rating AS "rating!"
+            FROM mmr_changes
+            WHERE game_id = $1 AND category IS NOT NULL AND rating IS NOT NULL
+            "#,
+            game_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        rows.into_iter().map(|row| Ok(AppliedChange {
+            account_id: row.account_id,
+            pool: RatingPool {
+                category: RatingCategory::from_str(&row.category)
+                    .map_err(|e| MatchmakingServiceError::UnknownGameTypeError(e.to_string()))?,
+                ranked: row.is_ranked,
+            },
+            mmr_change: row.mmr_change,
+            rating: row.rating,
+        })).collect()
+    }
+
+    async fn find_pool_ratings(
+        &self,
+        pool: RatingPool,
+        rated_since: DateTime<Utc>,
+    ) -> Result<Vec<(String, Rating)>, MatchmakingServiceError> {
+        let mut tx = self.client.begin_tx()
+            .await?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT account_id, rating, deviation, volatility, rated_at
+            FROM ratings
+            WHERE category = $1 AND is_ranked = $2 AND rated_at >= $3
+                AND season_id IS NOT DISTINCT FROM (
+                    CASE WHEN $2 THEN (SELECT id FROM seasons WHERE closed_at IS NULL) END
+                )
+            "#,
+            pool.category.to_str(),
+            pool.ranked,
+            rated_since
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(rows.into_iter().map(|row| (
+            row.account_id,
+            Rating {
+                rating: row.rating,
+                deviation: row.deviation,
+                volatility: row.volatility,
+                rated_at: Some(row.rated_at),
+            },
+        )).collect())
+    }
+
+    async fn find_rating_history(
+        &self,
+        account_id: &str,
+        ranked: bool,
+    ) -> Result<Vec<(RatingCategory, RatingHistoryPoint)>, MatchmakingServiceError> {
+        let mut tx = self.client.begin_tx()
+            .await?;
+
+        // Changes recorded before ratings and results were kept have no history point
+        let rows = sqlx::query!(
+            r#"
+            SELECT game_id, category AS "category!", rating AS "rating!", result AS "result!", created_at AS "created_at!"
+            FROM mmr_changes
+            WHERE account_id = $1 AND is_ranked = $2
+                AND category IS NOT NULL AND rating IS NOT NULL AND result IS NOT NULL
+                AND created_at IS NOT NULL
+            ORDER BY created_at, id
+            "#,
+            account_id,
+            ranked
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        rows.into_iter().map(|row| Ok((
+            RatingCategory::from_str(&row.category)
+                .map_err(|e| MatchmakingServiceError::UnknownGameTypeError(e.to_string()))?,
+            RatingHistoryPoint {
+                game_id: row.game_id,
+                rating: row.rating,
+                result: GameResult::from_str(&row.result)?,
+                played_at: row.created_at,
+            },
+        ))).collect()
+    }
 }
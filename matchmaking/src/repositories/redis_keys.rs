@@ -0,0 +1,66 @@
+//! Key layout of the matchmaking state, for Redis Cluster.
+//!
+//! Redis Cluster only runs a script or a transaction if all of its keys hash to one slot, so
+//! every key touched together with another one carries a hash tag:
+//!
+//! - Queues, join times, account statuses, pending games and everything else the queue scripts
+//!   touch start with `MATCHMAKING_KEY_PREFIX`. Players search in several queues at once and a
+//!   match moves both players, their pending game and their entries in every queue in a single
+//!   script, so this state can't be split by queue or by account without losing that atomicity.
+//!   The cost is that all queue traffic is served by the one shard owning that slot.
+//! - A leaderboard and the key it is rebuilt in share the category as their tag.
+//! - Keys only ever touched on their own, like color histories, aren't tagged and spread over the
+//!   cluster.
+//!
+//! Every script and transaction touching several keys goes through `check_same_slot`, which
+//! enforces the invariant when key slot checks are on.
+
+pub use shared::redis_keys::{check_same_slot, prepare_invoke, set_key_slot_checks};
+
+/// Prefix of every key the queue scripts touch, its hash tag maps them all to a single slot
+pub const MATCHMAKING_KEY_PREFIX: &str = "{matchmaking}";
+
+#[cfg(test)]
+mod tests {
+    use shared::{primitives::{GameType, RatingCategory}, redis_keys::key_slot};
+
+    use super::{check_same_slot, set_key_slot_checks};
+    use crate::{repositories::{leaderboard_repository::RedisLeaderboardRepository, matchmaking_queue_repository::{QueueType, RedisMatchmakingQueue}, pending_game_repository::RedisPendingGameRepository}};
+
+    #[test]
+    fn test_key_slot() {
+        set_key_slot_checks(true);
+
+        // Slots as reported by CLUSTER KEYSLOT
+        assert_eq!(key_slot("123456789"), 12739);
+        assert_eq!(key_slot("foo"), 12182);
+        assert_eq!(key_slot("{foo}:bar"), 12182);
+        // An empty hash tag hashes the whole key
+        assert_ne!(key_slot("{}foo"), key_slot("foo"));
+        assert!(check_same_slot(&["{a}:1", "b"]).is_err());
+    }
+
+    #[test]
+    fn test_script_keys_share_a_slot() {
+        set_key_slot_checks(true);
+
+        let pools = [
+            (QueueType { game_type: GameType::Blitz5_0, ranked: true }, 1500),
+            (QueueType { game_type: GameType::Bullet1_0, ranked: false }, 1400),
+        ];
+
+        for queue_type in QueueType::all() {
+            check_same_slot(&RedisMatchmakingQueue::match_players_keys(&queue_type)).unwrap();
+            check_same_slot(&RedisPendingGameRepository::create_pending_game_keys(&queue_type, "a", "b", "pending-game", 0)).unwrap();
+            check_same_slot(&RedisPendingGameRepository::time_out_pending_game_keys(&queue_type, "a", "b", "pending-game")).unwrap();
+        }
+
+        check_same_slot(&RedisMatchmakingQueue::add_player_to_queue_keys("a", &pools)).unwrap();
+        check_same_slot(&RedisMatchmakingQueue::remove_player_from_queue_keys("a")).unwrap();
+        check_same_slot(&RedisPendingGameRepository::accept_pending_game_keys("pending-game")).unwrap();
+
+        for category in RatingCategory::ALL {
+            check_same_slot(&RedisLeaderboardRepository::replace_leaderboard_keys(category)).unwrap();
+        }
+    }
+}
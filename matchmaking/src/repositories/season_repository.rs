@@ -0,0 +1,224 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use shared::primitives::RatingCategory;
+use uuid::Uuid;
+
+use crate::{client::database::{Database, PostgresDB}, error::MatchmakingServiceError, services::season_service::{Season, SeasonResult, SeasonStanding, SoftReset}};
+
+#[async_trait::async_trait]
+pub trait SeasonRepository: Send + Sync {
+    /// The season that wasn't closed yet, if any.
+    async fn find_open_season(
+        &self,
+    ) -> Result<Option<Season>, MatchmakingServiceError>;
+
+    /// Stores a new season and gives it the ranked ratings of the previous season, soft reset.
+    async fn insert_season(
+        &self,
+        season: &Season,
+        soft_reset: &SoftReset,
+    ) -> Result<(), MatchmakingServiceError>;
+
+    /// Ranked ratings of a season with the number of games played in it.
+    async fn find_season_standings(
+        &self,
+        season_id: &str,
+    ) -> Result<Vec<SeasonStanding>, MatchmakingServiceError>;
+
+    /// Closes a season and stores its results. Returns `false` if it was already closed.
+    async fn close_season(
+        &self,
+        season_id: &str,
+        closed_at: DateTime<Utc>,
+        results: &[SeasonResult],
+    ) -> Result<bool, MatchmakingServiceError>;
+}
+
+pub struct SeasonRepositoryService {
+    client: PostgresDB,
+}
+
+impl SeasonRepositoryService {
+    pub fn new(client: PostgresDB) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait::async_trait]
+impl SeasonRepository for SeasonRepositoryService {
+    async fn find_open_season(
+        &self,
+    ) -> Result<Option<Season>, MatchmakingServiceError> {
+        let mut tx = self.client.begin_tx()
+            .await?;
+
+        let row = sqlx::query!(
+            r#"
+            SELECT id, name, starts_at, ends_at, closed_at
+            FROM seasons
+            WHERE closed_at IS NULL
+            "#
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(row.map(|row| Season {
+            id: row.id.to_string(),
+            name: row.name,
+            starts_at: row.starts_at,
+            ends_at: row.ends_at,
+            closed_at: row.closed_at,
+        }))
+    }
+
+    async fn insert_season(
+        &self,
+        season: &Season,
+        soft_reset: &SoftReset,
+    ) -> Result<(), MatchmakingServiceError> {
+        let mut tx = self.client.begin_tx()
+            .await?;
+
+        let previous_season_id = sqlx::query_scalar!(
+            "SELECT id FROM seasons ORDER BY created_at DESC LIMIT 1"
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let season_id = Uuid::from_str(&season.id)?;
+
+        sqlx::query!(
+            "INSERT INTO seasons (id, name, starts_at, ends_at) VALUES ($1, $2, $3, $4)",
+            season_id,
+            season.name,
+            season.starts_at,
+            season.ends_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Ratings are pulled toward the mean of their category. They keep their rated_at, so the
+        // deviation of inactive players keeps growing and leaderboards stay limited to active ones.
+        sqlx::query!(
+            r#"
+            INSERT INTO ratings (id, account_id, category, is_ranked, rating, deviation, volatility, rated_at, season_id)
+            SELECT
+                gen_random_uuid(),
+                r.account_id,
+                r.category,
+                true,
+                r.rating - (r.rating - m.mean_rating) * $3,
+                GREATEST(r.deviation, $4),
+                r.volatility,
+                r.rated_at,
+                $1
+            FROM ratings r
+            JOIN (
+                SELECT category, AVG(rating) AS mean_rating
+                FROM ratings
+                WHERE is_ranked AND season_id IS NOT DISTINCT FROM $2
+                GROUP BY category
+            ) m ON m.category = r.category
+            WHERE r.is_ranked AND r.season_id IS NOT DISTINCT FROM $2
+            "#,
+            season_id,
+            previous_season_id,
+            soft_reset.factor,
+            soft_reset.min_deviation
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn find_season_standings(
+        &self,
+        season_id: &str,
+    ) -> Result<Vec<SeasonStanding>, MatchmakingServiceError> {
+        let mut tx = self.client.begin_tx()
+            .await?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT r.account_id, r.category, r.rating, r.deviation, COALESCE(g.games, 0) AS "games!"
+            FROM ratings r
+            LEFT JOIN (
+                SELECT account_id, category, COUNT(*) AS games
+                FROM mmr_changes
+                WHERE season_id = $1
+                GROUP BY account_id, category
+            ) g ON g.account_id = r.account_id AND g.category = r.category
+            WHERE r.is_ranked AND r.season_id = $1
+            "#,
+            Uuid::from_str(season_id)?
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        rows.into_iter().map(|row| Ok(SeasonStanding {
+            account_id: row.account_id,
+            category: RatingCategory::from_str(&row.category)
+                .map_err(|e| MatchmakingServiceError::UnknownGameTypeError(e.to_string()))?,
+            rating: row.rating,
+            deviation: row.deviation,
+            games: row.games,
+        })).collect()
+    }
+
+    async fn close_season(
+        &self,
+        season_id: &str,
+        closed_at: DateTime<Utc>,
+        results: &[SeasonResult],
+    ) -> Result<bool, MatchmakingServiceError> {
+        let season_id = Uuid::from_str(season_id)?;
+
+        let mut tx = self.client.begin_tx()
+            .await?;
+
+        let closed = sqlx::query!(
+            "UPDATE seasons SET closed_at = $2 WHERE id = $1 AND closed_at IS NULL",
+            season_id,
+            closed_at
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if closed == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO season_results (id, season_id, account_id, category, rating, deviation, games, rank, reward)
+            SELECT gen_random_uuid(), $1, result.*
+            FROM UNNEST($2::VARCHAR[], $3::VARCHAR[], $4::FLOAT8[], $5::FLOAT8[], $6::INT4[], $7::INT4[], $8::VARCHAR[])
+                AS result(account_id, category, rating, deviation, games, rank, reward)
+            "#,
+            season_id,
+            &results.iter().map(|result| result.account_id.clone()).collect::<Vec<_>>(),
+            &results.iter().map(|result| result.category.to_str().to_owned()).collect::<Vec<_>>(),
+            &results.iter().map(|result| result.rating).collect::<Vec<_>>(),
+            &results.iter().map(|result| result.deviation).collect::<Vec<_>>(),
+            &results.iter().map(|result| result.games as i32).collect::<Vec<_>>(),
+            &results.iter().map(|result| result.rank).collect::<Vec<_>>() as &[Option<i32>],
+            &results.iter().map(|result| result.reward.map(|reward| reward.to_str().to_owned())).collect::<Vec<_>>() as &[Option<String>]
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+}
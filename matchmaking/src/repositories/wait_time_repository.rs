@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use crate::{error::MatchmakingServiceError, repositories::matchmaking_queue_repository::{QueueType, RedisMatchmakingQueue}, services::wait_time::WaitTimeHistogram};
+
+/// Wait times are recorded per time slot, so that old slots expire and the histogram keeps rolling
+pub const WAIT_TIME_SLOT_MS: u64 = 5 * 60 * 1000;
+
+/// Slots estimates are based on, the last hour
+pub const WAIT_TIME_SLOTS: u64 = 12;
+
+#[async_trait::async_trait]
+pub trait WaitTimeRepository: Send + Sync {
+    /// Wait times of the matches of a queue over the last slots, limited to a rating band if given.
+    async fn find_wait_times(
+        &self,
+        queue_type: &QueueType,
+        rating_band: Option<u16>,
+        now_ms: u64,
+    ) -> Result<WaitTimeHistogram, MatchmakingServiceError>;
+}
+
+pub struct RedisWaitTimeRepository {
+    client: redis::Client,
+}
+
+impl RedisWaitTimeRepository {
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+
+    /// Key the wait times of a queue's matches are recorded in at `now_ms`. Its fields are
+    /// `{ratingBand}:{bucket}`, counting the matches of each band and wait time bucket.
+    pub fn get_wait_times_key(queue_type: &QueueType, now_ms: u64) -> String {
+        let queue_keys = RedisMatchmakingQueue::get_queue_keys(&queue_type.game_type, queue_type.ranked);
+
+        format!("{}:{}", queue_keys.wait_times_key, now_ms / WAIT_TIME_SLOT_MS)
+    }
+}
+
+#[async_trait::async_trait]
+impl WaitTimeRepository for RedisWaitTimeRepository {
+    async fn find_wait_times(
+        &self,
+        queue_type: &QueueType,
+        rating_band: Option<u16>,
+        now_ms: u64,
+    ) -> Result<WaitTimeHistogram, MatchmakingServiceError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let mut pipeline = redis::pipe();
+        for slot in 0..WAIT_TIME_SLOTS {
+            pipeline.hgetall(Self::get_wait_times_key(queue_type, now_ms.saturating_sub(slot * WAIT_TIME_SLOT_MS)));
+        }
+
+        let slots: Vec<HashMap<String, u64>> = pipeline.query_async(&mut conn).await?;
+
+        let mut histogram = WaitTimeHistogram::default();
+
+        for (field, count) in slots.iter().flatten() {
+            let Some((band, bucket)) = field.split_once(':') else {
+                continue;
+            };
+            let (Ok(band), Ok(bucket)) = (band.parse::<u16>(), bucket.parse::<u8>()) else {
+                continue;
+            };
+
+            if rating_band.is_none_or(|rating_band| rating_band == band) {
+                histogram.add(bucket, *count);
+            }
+        }
+
+        Ok(histogram)
+    }
+}
@@ -0,0 +1,122 @@
+use std::{cmp::Ordering, str::FromStr, sync::Arc};
+
+use shared::events::GameStartEvent;
+
+use crate::{error::MatchmakingServiceError, repositories::color_history_repository::ColorHistoryRepository};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceColor {
+    White,
+    Black,
+}
+
+impl PieceColor {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            PieceColor::White => "w",
+            PieceColor::Black => "b",
+        }
+    }
+}
+
+impl FromStr for PieceColor {
+    type Err = MatchmakingServiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "w" => Ok(PieceColor::White),
+            "b" => Ok(PieceColor::Black),
+            _ => Err(MatchmakingServiceError::UnknownGameTypeError(format!("Unknown color {}", s))),
+        }
+    }
+}
+
+/// How much a player is owed white: their blacks minus their whites, plus the length of their
+/// current streak, counted positive for a streak of blacks. `history` is most recent first.
+pub fn white_debt(history: &[PieceColor]) -> i32 {
+    let balance: i32 = history
+        .iter()
+        .map(|color| match color {
+            PieceColor::White => -1,
+            PieceColor::Black => 1,
+        })
+        .sum();
+
+    let streak = match history.first() {
+        Some(last_color) => {
+            let length = history.iter().take_while(|color| *color == last_color).count() as i32;
+
+            match last_color {
+                PieceColor::White => -length,
+                PieceColor::Black => length,
+            }
+        }
+        None => 0,
+    };
+
+    balance + streak
+}
+
+/// Gives the players of matched games alternating colors over their recent games.
+pub struct ColorService {
+    color_history_repository: Arc<dyn ColorHistoryRepository>,
+}
+
+impl ColorService {
+    pub fn new(color_history_repository: Arc<dyn ColorHistoryRepository>) -> Self {
+        Self { color_history_repository }
+    }
+
+    /// The player who should play white. The player owed white the most gets it, colors are
+    /// random when both are owed as much.
+    pub async fn pick_white_account_id<'a>(
+        &self,
+        account_id_0: &'a str,
+        account_id_1: &'a str,
+    ) -> Result<&'a str, MatchmakingServiceError> {
+        let debt_0 = white_debt(&self.color_history_repository.find_color_history(account_id_0).await?);
+        let debt_1 = white_debt(&self.color_history_repository.find_color_history(account_id_1).await?);
+
+        Ok(match debt_0.cmp(&debt_1) {
+            Ordering::Greater => account_id_0,
+            Ordering::Less => account_id_1,
+            Ordering::Equal if rand::random::<bool>() => account_id_0,
+            Ordering::Equal => account_id_1,
+        })
+    }
+
+    /// Records the colors of every started game, including challenges and rematches.
+    pub async fn record_game_start(&self, game_start: &GameStartEvent) -> Result<(), MatchmakingServiceError> {
+        // Events from before colors were sent list white first
+        let (white_account_id, black_account_id) = if game_start.white_account_id.is_empty() {
+            (&game_start.account_id_0, &game_start.account_id_1)
+        } else {
+            (&game_start.white_account_id, &game_start.black_account_id)
+        };
+
+        for (account_id, color) in [
+            (white_account_id, PieceColor::White),
+            (black_account_id, PieceColor::Black),
+        ] {
+            self.color_history_repository.record_color(account_id, &game_start.game_id, color).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{white_debt, PieceColor::{Black, White}};
+
+    #[test]
+    fn test_white_debt() {
+        assert_eq!(white_debt(&[]), 0);
+        assert_eq!(white_debt(&[White, Black]), -1);
+        assert_eq!(white_debt(&[Black, Black, White]), 3);
+        assert_eq!(white_debt(&[White, White, Black, Black, Black]), -1);
+
+        // A streak outweighs an even balance
+        assert!(white_debt(&[Black, Black, White, White]) > white_debt(&[White, Black, White, Black]));
+    }
+}
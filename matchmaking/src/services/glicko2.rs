@@ -0,0 +1,200 @@
+//! Glicko-2 rating system, see http://www.glicko.net/glicko/glicko2.pdf
+//!
+//! Every game is rated as its own rating period. The deviation of a player grows with the time
+//! since their last rated game, so returning players converge quickly again.
+
+use chrono::{DateTime, Utc};
+
+/// Converts between the Glicko and the Glicko-2 scale
+const SCALE: f64 = 173.7178;
+const SCALE_CENTER: f64 = 1500.0;
+
+/// Constrains the change of volatility over time
+const TAU: f64 = 0.5;
+const CONVERGENCE_TOLERANCE: f64 = 0.000001;
+
+pub const STARTING_RATING: f64 = 1000.0;
+pub const STARTING_DEVIATION: f64 = 350.0;
+pub const STARTING_VOLATILITY: f64 = 0.06;
+
+/// Keeps very active players from getting a rating that barely moves
+pub const MIN_DEVIATION: f64 = 45.0;
+
+/// Ratings above this deviation are provisional
+pub const PROVISIONAL_DEVIATION: f64 = 110.0;
+
+/// Inactive time over which the deviation grows as if a rating period without games passed
+pub const RATING_PERIOD_SECONDS: i64 = 86400;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+    /// When the rating was last updated, `None` if the player never played a rated game
+    pub rated_at: Option<DateTime<Utc>>,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            rating: STARTING_RATING,
+            deviation: STARTING_DEVIATION,
+            volatility: STARTING_VOLATILITY,
+            rated_at: None,
+        }
+    }
+}
+
+impl Rating {
+    /// The rating as used by the queue.
+    pub fn mmr(&self) -> u16 {
+        self.rating.round().clamp(0.0, u16::MAX as f64) as u16
+    }
+
+    pub fn is_provisional(&self) -> bool {
+        self.deviation > PROVISIONAL_DEVIATION
+    }
+
+    /// The rating with its deviation grown for the rating periods passed since `rated_at`.
+    pub fn at(&self, now: DateTime<Utc>) -> Rating {
+        let Some(rated_at) = self.rated_at else {
+            return *self;
+        };
+
+        let periods = (now - rated_at).num_seconds().max(0) as f64 / RATING_PERIOD_SECONDS as f64;
+        let phi = self.deviation / SCALE;
+        let deviation = (phi.powi(2) + periods * self.volatility.powi(2)).sqrt() * SCALE;
+
+        Rating {
+            deviation: deviation.min(STARTING_DEVIATION),
+            ..*self
+        }
+    }
+
+    /// The rating after a rating period with the given results, each a pair of the opponent's
+    /// rating and the score against them (1 win, 0.5 draw, 0 loss).
+    pub fn rate(&self, results: &[(Rating, f64)], rated_at: DateTime<Utc>) -> Rating {
+        let mu = (self.rating - SCALE_CENTER) / SCALE;
+        let phi = self.deviation / SCALE;
+
+        if results.is_empty() {
+            return Rating {
+                deviation: ((phi.powi(2) + self.volatility.powi(2)).sqrt() * SCALE).min(STARTING_DEVIATION),
+                rated_at: Some(rated_at),
+                ..*self
+            };
+        }
+
+        // Estimated variance of the rating based on the game outcomes, and the improvement
+        let mut variance_inv = 0.0;
+        let mut improvement_sum = 0.0;
+
+        for (opponent, score) in results {
+            let opponent_mu = (opponent.rating - SCALE_CENTER) / SCALE;
+            let opponent_g = g(opponent.deviation / SCALE);
+            let expected_score = 1.0 / (1.0 + (-opponent_g * (mu - opponent_mu)).exp());
+
+            variance_inv += opponent_g.powi(2) * expected_score * (1.0 - expected_score);
+            improvement_sum += opponent_g * (score - expected_score);
+        }
+
+        let variance = 1.0 / variance_inv;
+        let delta = variance * improvement_sum;
+
+        let volatility = new_volatility(phi, self.volatility, variance, delta);
+
+        let pre_period_phi = (phi.powi(2) + volatility.powi(2)).sqrt();
+        let new_phi = 1.0 / (1.0 / pre_period_phi.powi(2) + 1.0 / variance).sqrt();
+        let new_mu = mu + new_phi.powi(2) * improvement_sum;
+
+        Rating {
+            rating: new_mu * SCALE + SCALE_CENTER,
+            deviation: (new_phi * SCALE).clamp(MIN_DEVIATION, STARTING_DEVIATION),
+            volatility,
+            rated_at: Some(rated_at),
+        }
+    }
+}
+
+fn g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi.powi(2) / std::f64::consts::PI.powi(2)).sqrt()
+}
+
+/// Step 5 of the paper, the Illinois algorithm to find the new volatility.
+fn new_volatility(phi: f64, volatility: f64, variance: f64, delta: f64) -> f64 {
+    let a = volatility.powi(2).ln();
+
+    let f = |x: f64| {
+        let ex = x.exp();
+        ex * (delta.powi(2) - phi.powi(2) - variance - ex) / (2.0 * (phi.powi(2) + variance + ex).powi(2))
+            - (x - a) / TAU.powi(2)
+    };
+
+    let mut lower = a;
+    let mut upper = if delta.powi(2) > phi.powi(2) + variance {
+        (delta.powi(2) - phi.powi(2) - variance).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * TAU
+    };
+
+    let mut f_lower = f(lower);
+    let mut f_upper = f(upper);
+
+    while (upper - lower).abs() > CONVERGENCE_TOLERANCE {
+        let c = lower + (lower - upper) * f_lower / (f_upper - f_lower);
+        let f_c = f(c);
+
+        if f_c * f_upper <= 0.0 {
+            lower = upper;
+            f_lower = f_upper;
+        } else {
+            f_lower /= 2.0;
+        }
+
+        upper = c;
+        f_upper = f_c;
+    }
+
+    (lower / 2.0).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rating;
+
+    fn rating(rating: f64, deviation: f64) -> Rating {
+        Rating { rating, deviation, ..Default::default() }
+    }
+
+    #[test]
+    fn test_rate_paper_example() {
+        let now = chrono::Utc::now();
+
+        let rated = rating(1500.0, 200.0).rate(&[
+            (rating(1400.0, 30.0), 1.0),
+            (rating(1550.0, 100.0), 0.0),
+            (rating(1700.0, 300.0), 0.0),
+        ], now);
+
+        assert!((rated.rating - 1464.06).abs() < 0.01, "{}", rated.rating);
+        assert!((rated.deviation - 151.52).abs() < 0.01, "{}", rated.deviation);
+        assert!((rated.volatility - 0.05999).abs() < 0.00001, "{}", rated.volatility);
+        assert_eq!(rated.rated_at, Some(now));
+    }
+
+    #[test]
+    fn test_deviation_grows_while_inactive() {
+        let now = chrono::Utc::now();
+        let rated = Rating { rated_at: Some(now - chrono::Duration::days(365)), ..rating(1500.0, 50.0) };
+
+        assert!(rated.at(now).deviation > 50.0);
+        assert!(rated.at(now).deviation <= 350.0);
+        assert!(!rated.is_provisional());
+        assert!(rated.at(now + chrono::Duration::days(3650)).is_provisional());
+    }
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use shared::primitives::RatingCategory;
+
+use crate::{config::LeaderboardConfig, error::MatchmakingServiceError, repositories::{leaderboard_repository::LeaderboardRepository, ranking_repository::RankingRepository}, services::{glicko2::Rating, ranking_service::RatingPool}};
+
+pub struct LeaderboardEntry {
+    /// 1-based
+    pub rank: u64,
+    pub account_id: String,
+    pub rating: f64,
+}
+
+pub struct Leaderboard {
+    pub top: Vec<LeaderboardEntry>,
+    /// Players ranked around the requesting player, empty if they are not on the leaderboard
+    pub around_me: Vec<LeaderboardEntry>,
+}
+
+/// Leaderboards of the ranked ratings of every category. Only players with a non-provisional
+/// rating who played recently are ranked.
+pub struct LeaderboardService {
+    config: LeaderboardConfig,
+    leaderboard_repository: Arc<dyn LeaderboardRepository>,
+    ranking_repository: Arc<dyn RankingRepository>,
+}
+
+impl LeaderboardService {
+    pub const MAX_LIMIT: u32 = 100;
+    /// Players shown above and below the requesting player
+    pub const AROUND_ME_RADIUS: u64 = 5;
+
+    pub fn new(
+        config: LeaderboardConfig,
+        leaderboard_repository: Arc<dyn LeaderboardRepository>,
+        ranking_repository: Arc<dyn RankingRepository>,
+    ) -> Self {
+        Self { config, leaderboard_repository, ranking_repository }
+    }
+
+    fn is_ranked(&self, rating: &Rating) -> bool {
+        let active_since = Utc::now() - chrono::Duration::days(self.config.active_days);
+
+        !rating.at(Utc::now()).is_provisional()
+            && rating.rated_at.is_some_and(|rated_at| rated_at >= active_since)
+    }
+
+    /// Keeps the leaderboard of a pool in sync with a player's new rating.
+    pub async fn update_player_rating(
+        &self,
+        account_id: &str,
+        pool: RatingPool,
+        rating: &Rating,
+    ) -> Result<(), MatchmakingServiceError> {
+        if !pool.ranked {
+            return Ok(());
+        }
+
+        self.leaderboard_repository.set_player_rating(
+            pool.category,
+            account_id,
+            self.is_ranked(rating).then_some(rating.rating),
+        ).await
+    }
+
+    pub async fn get_leaderboard(
+        &self,
+        category: RatingCategory,
+        limit: u32,
+        account_id: Option<&str>,
+    ) -> Result<Leaderboard, MatchmakingServiceError> {
+        let limit = limit.clamp(1, Self::MAX_LIMIT) as u64;
+
+        let top = self.get_entries(category, 0, limit - 1).await?;
+
+        let around_me = match account_id {
+            Some(account_id) => match self.leaderboard_repository.find_rank(category, account_id).await? {
+                Some(rank) => self.get_entries(
+                    category,
+                    rank.saturating_sub(Self::AROUND_ME_RADIUS),
+                    rank + Self::AROUND_ME_RADIUS,
+                ).await?,
+                None => Vec::new(),
+            },
+            None => Vec::new(),
+        };
+
+        Ok(Leaderboard { top, around_me })
+    }
+
+    async fn get_entries(
+        &self,
+        category: RatingCategory,
+        start: u64,
+        stop: u64,
+    ) -> Result<Vec<LeaderboardEntry>, MatchmakingServiceError> {
+        let ratings = self.leaderboard_repository.find_range(category, start, stop).await?;
+
+        Ok(ratings.into_iter().zip(start + 1..).map(|((account_id, rating), rank)| LeaderboardEntry {
+            rank,
+            account_id,
+            rating,
+        }).collect())
+    }
+
+    /// Rebuilds every leaderboard from Postgres, which also drops players who stopped playing.
+    pub async fn rebuild_leaderboards(&self) -> Result<(), MatchmakingServiceError> {
+        let active_since = Utc::now() - chrono::Duration::days(self.config.active_days);
+
+        for category in RatingCategory::ALL {
+            let ratings: Vec<(String, f64)> = self.ranking_repository
+                .find_pool_ratings(RatingPool { category, ranked: true }, active_since)
+                .await?
+                .into_iter()
+                .filter(|(_, rating)| self.is_ranked(rating))
+                .map(|(account_id, rating)| (account_id, rating.rating))
+                .collect();
+
+            self.leaderboard_repository.replace_leaderboard(category, &ratings).await?;
+
+            tracing::debug!("Rebuilt {} leaderboard with {} players", category.to_str(), ratings.len());
+        }
+
+        Ok(())
+    }
+
+    /// Takes the rebuild lock for `ttl_ms`, so other replicas skip the rebuild until it expires.
+    pub async fn try_lock_rebuild(&self, owner: &str, ttl_ms: u64) -> Result<bool, MatchmakingServiceError> {
+        self.leaderboard_repository.acquire_rebuild_lock(owner, ttl_ms).await
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::repositories::matchmaking_queue_repository::QueueConfig;
+
+/// A player waiting in a queue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedPlayer {
+    pub account_id: String,
+    pub mmr: u16,
+    /// Unix timestamp (ms) the player joined the queue at
+    pub join_time_ms: u64,
+}
+
+/// Everything but the players a strategy needs to match a queue.
+pub struct MatchingContext<'a> {
+    pub queue_config: &'a QueueConfig,
+    /// Players who recently played each other are only matched again once both waited this long
+    pub repeat_opponent_wait_ms: u64,
+    /// Recent opponents of every player that haven't expired yet
+    pub recent_opponents: &'a HashMap<String, HashSet<String>>,
+    pub now_ms: u64,
+}
+
+/// Mmr search range of a player, based on mmr and time spent in the queue.
+struct PlayerRange {
+    waited_ms: u64,
+    lower_bound: f64,
+    upper_bound: f64,
+}
+
+impl MatchingContext<'_> {
+    fn player_range(&self, player: &QueuedPlayer) -> PlayerRange {
+        let mmr = player.mmr as f64;
+        let waited_ms = self.now_ms.saturating_sub(player.join_time_ms);
+        let mmr_range = self.queue_config.base_mmr_range as f64;
+        // Increase mmr search range based on time spent in the queue
+        let mmr_range_bonus = (waited_ms / 1000) as f64 * self.queue_config.mmr_range_increase_per_second as f64;
+        let max_mmr_delta = self.queue_config.max_mmr_delta as f64 / 2.0;
+
+        PlayerRange {
+            waited_ms,
+            lower_bound: (mmr - mmr_range - mmr_range_bonus).max(mmr - max_mmr_delta),
+            upper_bound: (mmr + mmr_range + mmr_range_bonus).min(mmr + max_mmr_delta),
+        }
+    }
+
+    fn is_recent_opponent(&self, account_id: &str, opponent_id: &str) -> bool {
+        self.recent_opponents
+            .get(account_id)
+            .is_some_and(|opponents| opponents.contains(opponent_id))
+    }
+
+    fn can_be_matched(&self, player: (&QueuedPlayer, &PlayerRange), other: (&QueuedPlayer, &PlayerRange)) -> bool {
+        if player.1.waited_ms >= self.repeat_opponent_wait_ms && other.1.waited_ms >= self.repeat_opponent_wait_ms {
+            return true;
+        }
+
+        !self.is_recent_opponent(&player.0.account_id, &other.0.account_id)
+            && !self.is_recent_opponent(&other.0.account_id, &player.0.account_id)
+    }
+}
+
+/// Mmr the next scan of a queue resumes at after a batch of it was matched, as in
+/// `match-players.lua`: players past the batch have at least the mmr of its last player, so the
+/// first unmatched player who could still reach it is scanned again. `None` if there is none.
+pub fn resume_mmr(batch: &[QueuedPlayer], matched: &HashSet<&str>, context: &MatchingContext) -> Option<u16> {
+    let last_mmr = batch.last()?.mmr as f64;
+    let max_mmr_delta = context.queue_config.max_mmr_delta as f64 / 2.0;
+
+    batch
+        .iter()
+        .find(|player| {
+            !matched.contains(player.account_id.as_str())
+                && context.player_range(player).upper_bound >= last_mmr - max_mmr_delta
+        })
+        .map(|player| player.mmr)
+}
+
+/// Pairs up the players of a queue.
+pub trait MatchingStrategy: Send + Sync {
+    /// `players` are sorted by mmr, then account id, as in the queue's sorted set. Returns the
+    /// matched pairs, each player in at most one of them.
+    fn match_players(&self, players: &[QueuedPlayer], context: &MatchingContext) -> Vec<(String, String)>;
+}
+
+/// The algorithm of `match-players.lua`: every player in mmr order is paired with their closest
+/// unmatched neighbor whose search range overlaps, skipping recent opponents.
+pub struct GreedyWindowStrategy;
+
+impl MatchingStrategy for GreedyWindowStrategy {
+    fn match_players(&self, players: &[QueuedPlayer], context: &MatchingContext) -> Vec<(String, String)> {
+        let ranges: Vec<PlayerRange> = players.iter().map(|player| context.player_range(player)).collect();
+        let mut matched = vec![false; players.len()];
+        let mut pairs = Vec::new();
+
+        for i in 0..players.len() {
+            if matched[i] {
+                continue;
+            }
+
+            for j in i + 1..players.len() {
+                if matched[j] {
+                    continue;
+                }
+
+                // The search stops at the first unmatched neighbor out of range
+                if ranges[i].upper_bound < ranges[j].lower_bound {
+                    break;
+                }
+
+                if context.can_be_matched((&players[i], &ranges[i]), (&players[j], &ranges[j])) {
+                    pairs.push((players[i].account_id.clone(), players[j].account_id.clone()));
+                    matched[i] = true;
+                    matched[j] = true;
+                    break;
+                }
+            }
+        }
+
+        pairs
+    }
+}
+
+/// Pairs the players of a batch at once, minimizing the mmr gaps of the pairs plus, for every
+/// player left unmatched, the widest gap allowed. Only an approximation of the best pairing:
+/// partners are looked for among the next `MAX_LOOKAHEAD` players in mmr order, so a player whose
+/// only possible partners are further away stays unmatched.
+pub struct LookaheadPairingStrategy;
+
+impl LookaheadPairingStrategy {
+    pub const MAX_LOOKAHEAD: usize = 8;
+}
+
+impl MatchingStrategy for LookaheadPairingStrategy {
+    fn match_players(&self, players: &[QueuedPlayer], context: &MatchingContext) -> Vec<(String, String)> {
+        const STATES: usize = 1 << LookaheadPairingStrategy::MAX_LOOKAHEAD;
+
+        let count = players.len();
+        let ranges: Vec<PlayerRange> = players.iter().map(|player| context.player_range(player)).collect();
+        let unmatched_cost = context.queue_config.max_mmr_delta.max(0) as u64 + 1;
+
+        let can_pair = |i: usize, j: usize| {
+            ranges[i].upper_bound >= ranges[j].lower_bound
+                && context.can_be_matched((&players[i], &ranges[i]), (&players[j], &ranges[j]))
+        };
+
+        // costs[i][taken] is the lowest cost of pairing players i.. where bit d of `taken` is set
+        // if player i + d is already paired with an earlier player. The partner of player i is
+        // stored alongside, 0 if player i is taken or left unmatched.
+        let mut costs = vec![[0u64; STATES]; count + 1];
+        let mut partners = vec![[0u8; STATES]; count];
+
+        for i in (0..count).rev() {
+            for taken in 0..STATES {
+                if taken & 1 == 1 {
+                    costs[i][taken] = costs[i + 1][taken >> 1];
+                    continue;
+                }
+
+                let mut best_cost = unmatched_cost + costs[i + 1][taken >> 1];
+                let mut best_partner = 0;
+
+                for offset in 1..=Self::MAX_LOOKAHEAD.min(count - 1 - i) {
+                    let partner_taken = offset < Self::MAX_LOOKAHEAD && taken & (1 << offset) != 0;
+
+                    if partner_taken || !can_pair(i, i + offset) {
+                        continue;
+                    }
+
+                    let gap = players[i + offset].mmr.abs_diff(players[i].mmr) as u64;
+                    let cost = gap + costs[i + 1][(taken | (1 << offset)) >> 1];
+
+                    if cost < best_cost {
+                        best_cost = cost;
+                        best_partner = offset as u8;
+                    }
+                }
+
+                costs[i][taken] = best_cost;
+                partners[i][taken] = best_partner;
+            }
+        }
+
+        let mut pairs = Vec::new();
+        let mut taken = 0;
+
+        for i in 0..count {
+            let offset = partners[i][taken] as usize;
+
+            if taken & 1 == 0 && offset > 0 {
+                pairs.push((players[i].account_id.clone(), players[i + offset].account_id.clone()));
+                taken |= 1 << offset;
+            }
+
+            taken >>= 1;
+        }
+
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use proptest::prelude::*;
+
+    use super::{GreedyWindowStrategy, MatchingContext, MatchingStrategy, LookaheadPairingStrategy, QueuedPlayer};
+    use crate::repositories::matchmaking_queue_repository::QueueConfig;
+
+    const NOW_MS: u64 = 1_000_000_000;
+
+    fn queued_players(players: Vec<(u16, u64)>) -> Vec<QueuedPlayer> {
+        let mut players: Vec<QueuedPlayer> = players
+            .into_iter()
+            .enumerate()
+            .map(|(index, (mmr, waited_ms))| QueuedPlayer {
+                account_id: format!("account-{:04}", index),
+                mmr,
+                join_time_ms: NOW_MS - waited_ms,
+            })
+            .collect();
+        players.sort_by(|a, b| a.mmr.cmp(&b.mmr).then_with(|| a.account_id.cmp(&b.account_id)));
+        players
+    }
+
+    fn recent_opponents(players: &[QueuedPlayer], pairs: Vec<(usize, usize)>) -> HashMap<String, HashSet<String>> {
+        let mut recent_opponents: HashMap<String, HashSet<String>> = HashMap::new();
+
+        for (i, j) in pairs {
+            let (i, j) = (i % players.len(), j % players.len());
+            recent_opponents.entry(players[i].account_id.clone()).or_default().insert(players[j].account_id.clone());
+            recent_opponents.entry(players[j].account_id.clone()).or_default().insert(players[i].account_id.clone());
+        }
+
+        recent_opponents
+    }
+
+    fn check_pairs(players: &[QueuedPlayer], pairs: &[(String, String)], max_mmr_delta: i32) {
+        let mmrs: HashMap<&str, u16> = players.iter().map(|player| (player.account_id.as_str(), player.mmr)).collect();
+        let mut matched = HashSet::new();
+
+        for (account_id_0, account_id_1) in pairs {
+            assert!(matched.insert(account_id_0), "{} matched twice", account_id_0);
+            assert!(matched.insert(account_id_1), "{} matched twice", account_id_1);
+            assert!(mmrs[account_id_0.as_str()].abs_diff(mmrs[account_id_1.as_str()]) as i32 <= max_mmr_delta);
+        }
+    }
+
+    #[test]
+    fn test_lookahead_pairing_prefers_smaller_gaps() {
+        let players = queued_players(vec![(1500, 0), (1550, 0), (1560, 0)]);
+        let recent_opponents = HashMap::new();
+        let queue_config = QueueConfig { base_mmr_range: 100, mmr_range_increase_per_second: 5, max_mmr_delta: 400 };
+        let context = MatchingContext { queue_config: &queue_config, repeat_opponent_wait_ms: 60_000, recent_opponents: &recent_opponents, now_ms: NOW_MS };
+
+        // The closest neighbor of the first player is taken greedily
+        assert_eq!(
+            GreedyWindowStrategy.match_players(&players, &context),
+            vec![("account-0000".to_string(), "account-0001".to_string())]
+        );
+        assert_eq!(
+            LookaheadPairingStrategy.match_players(&players, &context),
+            vec![("account-0001".to_string(), "account-0002".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_lookahead_pairing_is_bounded() {
+        let players = queued_players((0..10).map(|index| (1500 + index, 0)).collect());
+        // Everyone but the last player recently played the first one
+        let recent_opponents = recent_opponents(&players, (1..9).map(|index| (0, index)).collect());
+        let queue_config = QueueConfig { base_mmr_range: 100, mmr_range_increase_per_second: 5, max_mmr_delta: 400 };
+        let context = MatchingContext { queue_config: &queue_config, repeat_opponent_wait_ms: 60_000, recent_opponents: &recent_opponents, now_ms: NOW_MS };
+
+        // Every player is matched only if the first one is paired with the last, 9 places away
+        let greedy_pairs = GreedyWindowStrategy.match_players(&players, &context);
+        assert_eq!(greedy_pairs.len(), 5);
+        assert!(greedy_pairs.contains(&("account-0000".to_string(), "account-0009".to_string())));
+
+        let lookahead_pairs = LookaheadPairingStrategy.match_players(&players, &context);
+        assert_eq!(lookahead_pairs.len(), 4);
+        assert!(lookahead_pairs.iter().all(|(account_id_0, _)| account_id_0 != "account-0000"));
+    }
+
+    proptest! {
+        #[test]
+        fn test_strategies_respect_max_mmr_delta(
+            players in prop::collection::vec((0u16..3000, 0u64..600_000), 0..80),
+            recent_pairs in prop::collection::vec((0usize..80, 0usize..80), 0..20),
+            base_mmr_range in 0i32..200,
+            mmr_range_increase_per_second in 0i32..50,
+            max_mmr_delta in 0i32..800,
+            repeat_opponent_wait_ms in 0u64..120_000,
+        ) {
+            let players = queued_players(players);
+            let recent_opponents = if players.is_empty() { HashMap::new() } else { recent_opponents(&players, recent_pairs) };
+            let queue_config = QueueConfig { base_mmr_range, mmr_range_increase_per_second, max_mmr_delta };
+            let context = MatchingContext { queue_config: &queue_config, repeat_opponent_wait_ms, recent_opponents: &recent_opponents, now_ms: NOW_MS };
+
+            check_pairs(&players, &GreedyWindowStrategy.match_players(&players, &context), max_mmr_delta);
+            check_pairs(&players, &LookaheadPairingStrategy.match_players(&players, &context), max_mmr_delta);
+        }
+    }
+}
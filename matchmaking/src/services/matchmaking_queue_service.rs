@@ -2,12 +2,12 @@ use std::{collections::HashMap, sync::Arc};
 
 use shared::{QueueSize, primitives::GameType};
 
-use crate::{error::MatchmakingServiceError, repositories::matchmaking_queue_repository::{MatchmakingQueueContract, PlayerStatus, QueueConfig, QueueType}, services::{player_status_service::{MatchMakingStatus, PlayerStatusServiceContract}, ranking_service::RankingServiceContract}};
+use crate::{error::MatchmakingServiceError, repositories::{matchmaking_queue_repository::{MatchBatch, MatchmakingQueueContract, PlayerStatus, QueueType}, wait_time_repository::WaitTimeRepository}, services::{player_status_service::{MatchMakingStatus, PlayerStatusServiceContract}, player_status_transitions::PlayerStatusTransition, queue_penalty_service::QueuePenaltyService, queue_pool_service::QueuePoolService, ranking_service::{RankingServiceContract, RatingPool}, season_service::SeasonService, wait_time::rating_band}};
 
 pub struct AddToQueue {
     pub account_id: String,
-    pub game_type: GameType,
-    pub ranked: bool,
+    /// Queues the player searches in at once, matching in any of them leaves all the others
+    pub pools: Vec<QueueType>,
 }
 
 // Repository service (similar to NestJS)
@@ -15,44 +15,49 @@ pub struct MatchmakingQueueService {
     matchmaking_queue_repository: Arc<dyn MatchmakingQueueContract>,
     player_status_service: Arc<dyn PlayerStatusServiceContract>,
     ranking_service: Arc<dyn RankingServiceContract>,
+    season_service: Arc<SeasonService>,
+    wait_time_repository: Arc<dyn WaitTimeRepository>,
+    queue_penalty_service: Arc<QueuePenaltyService>,
+    queue_pool_service: Arc<QueuePoolService>,
 }
 
 impl MatchmakingQueueService {
     pub fn new(
         matchmaking_queue_repository: Arc<dyn MatchmakingQueueContract>,
         player_status_service: Arc<dyn PlayerStatusServiceContract>,
-        ranking_service: Arc<dyn RankingServiceContract>
+        ranking_service: Arc<dyn RankingServiceContract>,
+        season_service: Arc<SeasonService>,
+        wait_time_repository: Arc<dyn WaitTimeRepository>,
+        queue_penalty_service: Arc<QueuePenaltyService>,
+        queue_pool_service: Arc<QueuePoolService>,
     ) -> Self {
-        Self { matchmaking_queue_repository, player_status_service, ranking_service }
+        Self { matchmaking_queue_repository, player_status_service, ranking_service, season_service, wait_time_repository, queue_penalty_service, queue_pool_service }
     }
 
-    const RANKED_CONFIG: QueueConfig = QueueConfig {
-        base_mmr_range: 50,
-        mmr_range_increase_per_second: 5,
-        max_mmr_delta: 400
-    };
-
-    const NORMAL_CONFIG: QueueConfig = QueueConfig {
-        base_mmr_range: 100,
-        mmr_range_increase_per_second: 10,
-        max_mmr_delta: 600
-    };
+    /// Matches a rating band needs before its own wait times are trusted over the whole queue's
+    const MIN_BAND_MATCHES: u64 = 10;
 
+    /// Matches the next batch of a queue with the search window of its pool. Nothing is matched
+    /// once the pool is disabled.
     pub async fn match_players(
         &self,
         game_type: &GameType,
         ranked: bool,
-    ) -> redis::RedisResult<Vec<String>> {
-
-        let queue_config = match  ranked {
-            true => Self::RANKED_CONFIG,
-            false => Self::NORMAL_CONFIG
+        repeat_opponent_wait_ms: u64,
+        batch_size: usize,
+    ) -> redis::RedisResult<MatchBatch> {
+        let Some(pool) = self.queue_pool_service.find_enabled_pool(&QueueType { game_type: *game_type, ranked }) else {
+            return Ok(MatchBatch { matched_players: vec![], scan_complete: true });
         };
 
+        let queue_config = pool.queue_config();
+
         self.matchmaking_queue_repository.match_players_in_queue(
             game_type,
             ranked,
             &queue_config,
+            repeat_opponent_wait_ms,
+            batch_size,
         ).await
     }
 
@@ -63,30 +68,57 @@ impl MatchmakingQueueService {
 
         let AddToQueue {
             account_id,
-            ranked,
-            game_type,
+            mut pools,
         } = payload;
 
+        let mut seen = Vec::with_capacity(pools.len());
+        pools.retain(|pool| {
+            let is_new = !seen.contains(pool);
+            seen.push(*pool);
+            is_new
+        });
+
+        if pools.is_empty() {
+            return Err(MatchmakingServiceError::UnknownGameTypeError("No queue to join".into()));
+        }
+
+        if let Some(pool) = pools.iter().find(|pool| self.queue_pool_service.find_enabled_pool(pool).is_none()) {
+            return Err(MatchmakingServiceError::UnknownGameTypeError(
+                format!("No queue for game type {}", pool.game_type.to_str())
+            ));
+        }
+
+        self.queue_penalty_service.check_queue_cooldown(&account_id).await?;
+
+        if pools.iter().any(|pool| pool.ranked) && self.season_service.get_active_season().await?.is_none() {
+            return Err(MatchmakingServiceError::NoActiveSeasonError);
+        }
+
         let ranking = self.ranking_service.get_or_create_ranking(&account_id).await?;
 
-        let mmr = match ranked {
-            true => ranking.ranked_mmr,
-            false => ranking.normal_mmr,
-        };
+        let pools_with_mmr: Vec<(QueueType, u16)> = pools
+            .iter()
+            .map(|pool| (*pool, ranking.rating(RatingPool::of_game(&pool.game_type, pool.ranked)).mmr()))
+            .collect();
 
-        self.matchmaking_queue_repository.add_player_to_queue(
+        let status = self.matchmaking_queue_repository.add_player_to_queue(
             account_id.as_str(),
-            mmr,
-            &game_type,
-            ranked,
+            &pools_with_mmr,
+            self.player_status_service.ttl_seconds(PlayerStatus::Searching),
         ).await?;
 
+        if let Some(status) = status {
+            return Err(MatchmakingServiceError::PlayerStatusTransitionError { from: status, to: PlayerStatus::Searching });
+        }
+
+        self.player_status_service.record_transitions(vec![
+            PlayerStatusTransition::new(&account_id, PlayerStatus::Undefined, PlayerStatus::Searching)
+        ]).await?;
 
         tracing::debug!(
-            "Player {} added to {} {} queue",
+            "Player {} added to queues {}",
             &account_id.as_str(),
-            if ranked { "ranked" } else { "normal" },
-            game_type.to_str()
+            pools.iter().map(QueueType::pool_id).collect::<Vec<_>>().join(", ")
         );
 
         Ok(())
@@ -98,55 +130,122 @@ impl MatchmakingQueueService {
     ) -> Result<(), MatchmakingServiceError> {
         let MatchMakingStatus {
             status,
-            game_type,
-            ranked,
             ..
         } = self.player_status_service.get_player_status(account_id).await?;
 
-        if !(matches!(status, PlayerStatus::Searching) && game_type.is_some() && ranked.is_some()) {
+        if !matches!(status, PlayerStatus::Searching) {
             return Ok(());
         }
 
-        let game_type = game_type.unwrap();
-        let ranked = ranked.unwrap();
+        let (removed, status_cleared) = self.matchmaking_queue_repository.remove_player_from_queue(account_id).await?;
 
-        self.matchmaking_queue_repository.remove_player_from_queue(
-            account_id,
-            game_type,
-            ranked
-        ).await?;
+        tracing::debug!("Player {} removed from {} queues", account_id, removed);
 
-        tracing::debug!(
-           "Player {} removed from {} {} queue",
-           account_id,
-           if ranked { "ranked" } else { "normal" },
-           game_type.to_str()
-        );
+        if status_cleared {
+            self.player_status_service.record_transitions(vec![
+                PlayerStatusTransition::new(account_id, PlayerStatus::Searching, PlayerStatus::Undefined)
+            ]).await?;
+        }
 
         Ok(())
     }
 
+    /// Queues of the enabled pools, the ones players can join.
+    pub fn queue_types(&self) -> Vec<QueueType> {
+        self.queue_pool_service.enabled_pools().iter().map(|pool| pool.queue_type()).collect()
+    }
+
+    /// Queue sizes with the estimated wait of every queue.
     pub async fn get_queue_sizes(&self) -> Result<HashMap<String, QueueSize>, MatchmakingServiceError> {
-        let game_types = vec![
-            GameType::Rapid10_0,
-            GameType::Blitz5_3,
-            GameType::Blitz5_0,
-            GameType::Blitz3_2,
-            GameType::Blitz3_0,
-            GameType::Bullet1_0,
-        ];
-
-        let queue_types: Vec<QueueType> = game_types.into_iter()
-            .flat_map(|game_type| {
-                [true, false]
-                    .into_iter()
-                    .map(move |ranked| QueueType {
-                        game_type,
-                        ranked,
-                    })
-            })
-            .collect();
+        let queue_types = self.queue_types();
+        let mut queue_sizes = self.matchmaking_queue_repository.get_queue_sizes(queue_types.clone()).await?;
+
+        for queue_type in queue_types {
+            let estimated_wait_seconds = self.estimate_wait_seconds(&queue_type, None).await?;
+
+            if let Some(queue_size) = queue_sizes.get_mut(queue_type.game_type.to_str()) {
+                if queue_type.ranked {
+                    queue_size.ranked_estimated_wait_seconds = estimated_wait_seconds;
+                } else {
+                    queue_size.normal_estimated_wait_seconds = estimated_wait_seconds;
+                }
+            }
+        }
+
+        Ok(queue_sizes)
+    }
+
+    /// Median time from joining a queue to being matched over the last hour, for players of the
+    /// given mmr if enough of them were matched. `None` while nobody was matched.
+    pub async fn estimate_wait_seconds(
+        &self,
+        queue_type: &QueueType,
+        mmr: Option<u16>,
+    ) -> Result<Option<u32>, MatchmakingServiceError> {
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+
+        if let Some(mmr) = mmr {
+            let band_wait_times = self.wait_time_repository
+                .find_wait_times(queue_type, Some(rating_band(mmr)), now_ms)
+                .await?;
+
+            if band_wait_times.total() >= Self::MIN_BAND_MATCHES {
+                return Ok(band_wait_times.estimated_wait_seconds());
+            }
+        }
+
+        Ok(self.wait_time_repository
+            .find_wait_times(queue_type, None, now_ms)
+            .await?
+            .estimated_wait_seconds())
+    }
 
-        Ok(self.matchmaking_queue_repository.get_queue_sizes(queue_types).await?)
+    /// Estimated wait of a player searching in several queues, the shortest of them.
+    pub async fn estimate_player_wait_seconds(
+        &self,
+        account_id: &str,
+        pools: &[QueueType],
+    ) -> Result<Option<u32>, MatchmakingServiceError> {
+        let mut estimated_wait_seconds: Option<u32> = None;
+
+        for pool in pools {
+            let mmr = self.matchmaking_queue_repository.get_player_mmr(account_id, pool).await?;
+
+            if let Some(pool_wait_seconds) = self.estimate_wait_seconds(pool, mmr).await? {
+                estimated_wait_seconds = Some(estimated_wait_seconds.map_or(pool_wait_seconds, |wait| wait.min(pool_wait_seconds)));
+            }
+        }
+
+        Ok(estimated_wait_seconds)
+    }
+
+    /// Makes `owner` the only matcher of a queue until `ttl_ms` elapses.
+    pub async fn try_lock_queue(
+        &self,
+        queue_type: &QueueType,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> Result<bool, MatchmakingServiceError> {
+        Ok(self.matchmaking_queue_repository.acquire_queue_lock(
+            &queue_type.game_type,
+            queue_type.ranked,
+            owner,
+            ttl_ms,
+        ).await?)
+    }
+
+    /// Makes the queue's lock expire in `ttl_ms` if `owner` still holds it.
+    pub async fn extend_queue_lock(
+        &self,
+        queue_type: &QueueType,
+        owner: &str,
+        ttl_ms: u64,
+    ) -> Result<bool, MatchmakingServiceError> {
+        Ok(self.matchmaking_queue_repository.extend_queue_lock(
+            &queue_type.game_type,
+            queue_type.ranked,
+            owner,
+            ttl_ms,
+        ).await?)
     }
 }
@@ -1,4 +1,15 @@
 pub mod ranking_service;
+pub mod rating_history;
+pub mod glicko2;
+pub mod color_service;
+pub mod leaderboard_service;
 pub mod matchmaking_queue_service;
+pub mod pending_game_service;
 pub mod player_status_service;
+pub mod player_status_transitions;
+pub mod season_service;
 pub mod streaming_service;
+pub mod wait_time;
+pub mod matching_strategy;
+pub mod queue_penalty_service;
+pub mod queue_pool_service;
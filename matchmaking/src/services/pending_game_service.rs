@@ -0,0 +1,341 @@
+use std::sync::Arc;
+
+use shared::events::{PendingGameReadyEvent, PendingGameTimeoutEvent};
+
+use crate::{error::MatchmakingServiceError, repositories::{matchmaking_queue_repository::{PlayerStatus, QueueType}, pending_game_repository::{AcceptPendingGameOutcome, PendingGame, PendingGameEnd, PendingGameRepository}}, services::{color_service::ColorService, matchmaking_queue_service::MatchmakingQueueService, player_status_service::PlayerStatusServiceContract, player_status_transitions::PlayerStatusTransition, queue_penalty_service::{QueueOffense, QueuePenaltyService}}, state::state::AppState};
+
+pub struct PendingGameService {
+    state: AppState,
+    matchmaking_queue_service: Arc<MatchmakingQueueService>,
+    player_status_service: Arc<dyn PlayerStatusServiceContract>,
+    pending_game_repository: Arc<dyn PendingGameRepository>,
+    color_service: Arc<ColorService>,
+    queue_penalty_service: Arc<QueuePenaltyService>,
+}
+
+impl PendingGameService {
+    pub fn new(
+        state: AppState,
+        matchmaking_queue_service: Arc<MatchmakingQueueService>,
+        player_status_service: Arc<dyn PlayerStatusServiceContract>,
+        pending_game_repository: Arc<dyn PendingGameRepository>,
+        color_service: Arc<ColorService>,
+        queue_penalty_service: Arc<QueuePenaltyService>,
+    ) -> Self {
+        Self { state, matchmaking_queue_service, player_status_service, pending_game_repository, color_service, queue_penalty_service }
+    }
+
+    /// Matches the players of a queue batch by batch and turns every matched pair into a pending
+    /// game. A scan of a long queue that doesn't finish within the tick continues on the next one.
+    /// `lock_owner` holds the queue's lock, which is extended before every batch and stops the
+    /// run once it is lost.
+    pub async fn create_pending_games(
+        &self,
+        queue_type: &QueueType,
+        lock_owner: &str,
+    ) -> Result<Vec<PendingGame>, MatchmakingServiceError> {
+        let matcher_config = &self.state.config.matcher;
+        let mut pending_games = Vec::new();
+
+        // Matched players leave the queue before the next batch is read
+        for _ in 0..matcher_config.max_match_batches_per_tick {
+            if !self.matchmaking_queue_service.extend_queue_lock(queue_type, lock_owner, matcher_config.queue_lock_lease_ms).await? {
+                tracing::warn!("Lost the lock of the {} queue while matching it", queue_type.pool_id());
+                break;
+            }
+
+            let batch = self.matchmaking_queue_service.match_players(
+                &queue_type.game_type,
+                queue_type.ranked,
+                matcher_config.repeat_opponent_wait_seconds * 1000,
+                matcher_config.match_batch_size,
+            ).await?;
+
+            for pair in batch.matched_players.chunks_exact(2) {
+                if let Some(pending_game) = self.create_pending_game(queue_type, &pair[0], &pair[1]).await? {
+                    pending_games.push(pending_game);
+                }
+            }
+
+            if batch.scan_complete {
+                break;
+            }
+        }
+
+        Ok(pending_games)
+    }
+
+    /// Turns a matched pair into a pending game, `None` if the match went stale meanwhile.
+    async fn create_pending_game(
+        &self,
+        queue_type: &QueueType,
+        account_id_0: &str,
+        account_id_1: &str,
+    ) -> Result<Option<PendingGame>, MatchmakingServiceError> {
+        let matcher_config = &self.state.config.matcher;
+
+        let pending_game = self.pending_game_repository.create_pending_game(
+            &queue_type.game_type,
+            queue_type.ranked,
+            account_id_0,
+            account_id_1,
+            matcher_config.pending_game_timeout_seconds * 1000,
+            matcher_config.recent_opponent_ttl_seconds * 1000,
+            self.player_status_service.ttl_seconds(PlayerStatus::Pending),
+        ).await?;
+
+        match &pending_game {
+            Some(pending_game) => {
+                self.player_status_service.record_transitions(
+                    [&pending_game.account_id_0, &pending_game.account_id_1]
+                        .into_iter()
+                        .map(|account_id| PlayerStatusTransition::new(account_id, PlayerStatus::Searching, PlayerStatus::Pending))
+                        .collect()
+                ).await?;
+
+                tracing::debug!(
+                    "Pending game {} ({} {}) created, {} vs {}",
+                    pending_game.id,
+                    if queue_type.ranked { "ranked" } else { "normal" },
+                    queue_type.game_type.to_str(),
+                    pending_game.account_id_0,
+                    pending_game.account_id_1
+                );
+
+                crate::services::streaming_service::emit_pending_game_ready(
+                    &self.state,
+                    PendingGameReadyEvent {
+                        account_id_0: pending_game.account_id_0.clone(),
+                        account_id_1: pending_game.account_id_1.clone(),
+                        pending_game_id: pending_game.id.clone(),
+                    }
+                ).await?;
+            }
+            None => tracing::debug!("Dropped stale match {} vs {}", account_id_0, account_id_1),
+        }
+
+        Ok(pending_game)
+    }
+
+    /// Records a player's acceptance. Once both players have accepted, the game is created.
+    pub async fn accept_pending_game(
+        &self,
+        account_id: &str,
+        pending_game_id: &str,
+    ) -> Result<(), MatchmakingServiceError> {
+        let outcome = self.pending_game_repository.accept_pending_game(
+            pending_game_id,
+            account_id,
+        ).await?;
+
+        if !matches!(outcome, AcceptPendingGameOutcome::Ready) {
+            tracing::debug!("Player {} accepted pending game {}", account_id, pending_game_id);
+            return Ok(());
+        }
+
+        let pending_game = self.pending_game_repository.find_pending_game(pending_game_id).await?
+            .ok_or_else(|| MatchmakingServiceError::PendingGameError(
+                format!("Pending game {} not found or expired", pending_game_id)
+            ))?;
+
+        let game_id = match self.create_game(&pending_game).await {
+            Ok(game_id) => game_id,
+            Err(e) => {
+                tracing::error!("Failed to create the game of pending game {}: {}", pending_game.id, e);
+
+                // Both players accepted, so both go back into the queue
+                self.time_out_pending_game(&pending_game, true).await?;
+
+                return Err(e);
+            }
+        };
+
+        // The game exists from here on, requeuing would leave both players searching while they
+        // play it, so the remaining writes are retried instead
+        let mut attempt = 1;
+        loop {
+            match self.finish_starting_game(&pending_game, &game_id).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < Self::FINISH_STARTING_GAME_ATTEMPTS => {
+                    tracing::warn!("Failed to finish starting game {} (attempt {}): {}", game_id, attempt, e);
+                    tokio::time::sleep(std::time::Duration::from_millis(100 * attempt as u64)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    // The game start event still sets both players playing, and the pending game
+                    // expires on its own without requeuing them as both accepted
+                    tracing::error!("Failed to finish starting game {} of pending game {}: {}", game_id, pending_game.id, e);
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// Attempts at the writes that follow the creation of a game
+    const FINISH_STARTING_GAME_ATTEMPTS: u32 = 3;
+
+    /// Creates the game of a pending game both players accepted, returns its id.
+    async fn create_game(&self, pending_game: &PendingGame) -> Result<String, MatchmakingServiceError> {
+        let metadata = serde_json::json!({
+            "pendingGameId": pending_game.id,
+            "mmr": {
+                pending_game.account_id_0.as_str(): pending_game.mmr_0,
+                pending_game.account_id_1.as_str(): pending_game.mmr_1,
+            },
+        });
+
+        let white_account_id = self.color_service.pick_white_account_id(
+            &pending_game.account_id_0,
+            &pending_game.account_id_1,
+        ).await?;
+
+        let shared::CreateGameResponse { game_id, .. } = self.state.game_client.clone()
+            .create_game(shared::CreateGameRequest {
+                account_id0: pending_game.account_id_0.clone(),
+                account_id1: pending_game.account_id_1.clone(),
+                game_type: pending_game.game_type.to_str().to_owned(),
+                metadata: metadata.to_string(),
+                ranked: pending_game.ranked,
+                white_account_id: Some(white_account_id.to_owned()),
+            })
+            .await?
+            .into_inner();
+
+        Ok(game_id)
+    }
+
+    /// Sets both players playing the created game and drops the pending game. Safe to repeat.
+    async fn finish_starting_game(&self, pending_game: &PendingGame, game_id: &str) -> Result<(), MatchmakingServiceError> {
+        for account_id in [&pending_game.account_id_0, &pending_game.account_id_1] {
+            self.player_status_service.set_player_playing(
+                account_id,
+                game_id,
+                &pending_game.game_type,
+                pending_game.ranked,
+            ).await?;
+        }
+
+        self.pending_game_repository.delete_pending_game(&pending_game.id).await?;
+
+        tracing::debug!("Pending game {} started as game {}", pending_game.id, game_id);
+
+        Ok(())
+    }
+
+    /// Times out every pending game whose accept window has closed. A pending game that can't be
+    /// timed out is skipped, so it doesn't hold up the others.
+    pub async fn time_out_pending_games(&self) -> Result<(), MatchmakingServiceError> {
+        let pending_game_ids = self.pending_game_repository.find_expired_pending_game_ids(
+            chrono::Utc::now().timestamp_millis()
+        ).await?;
+
+        for pending_game_id in pending_game_ids {
+            if let Err(e) = self.time_out_expired_pending_game(&pending_game_id).await {
+                tracing::error!("Failed to time out pending game {}: {}", pending_game_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn time_out_expired_pending_game(&self, pending_game_id: &str) -> Result<(), MatchmakingServiceError> {
+        match self.pending_game_repository.find_pending_game(pending_game_id).await? {
+            Some(pending_game) => self.time_out_pending_game(&pending_game, false).await,
+            None => self.pending_game_repository.remove_pending_game_timeout(pending_game_id).await,
+        }
+    }
+
+    async fn time_out_pending_game(
+        &self,
+        pending_game: &PendingGame,
+        force: bool,
+    ) -> Result<(), MatchmakingServiceError> {
+        let pending_game_end = self.pending_game_repository.time_out_pending_game(
+            pending_game,
+            force,
+            self.player_status_service.ttl_seconds(PlayerStatus::Searching),
+        ).await?;
+
+        let Some(pending_game_end) = pending_game_end else {
+            return Ok(());
+        };
+        let [accepted_0, accepted_1] = pending_game_end.accepted;
+
+        tracing::debug!(
+            "Pending game {} timed out, accepted: {} {}, {} {}",
+            pending_game.id,
+            pending_game.account_id_0,
+            accepted_0,
+            pending_game.account_id_1,
+            accepted_1
+        );
+
+        // Players aren't to blame for a game that couldn't be created
+        if !force {
+            for (account_id, accepted) in [(&pending_game.account_id_0, accepted_0), (&pending_game.account_id_1, accepted_1)] {
+                if !accepted {
+                    self.queue_penalty_service.record_offense(account_id, QueueOffense::PendingGameTimeout, &pending_game.id).await?;
+                }
+            }
+        }
+
+        self.emit_pending_game_ended(pending_game, pending_game_end).await
+    }
+
+    /// Ends a pending game one of its players declined, the other one goes back into their queues.
+    pub async fn decline_pending_game(
+        &self,
+        account_id: &str,
+        pending_game_id: &str,
+    ) -> Result<(), MatchmakingServiceError> {
+        let pending_game = self.pending_game_repository.find_pending_game(pending_game_id).await?
+            .ok_or_else(|| MatchmakingServiceError::PendingGameError(
+                format!("Pending game {} not found or expired", pending_game_id)
+            ))?;
+
+        if account_id != pending_game.account_id_0 && account_id != pending_game.account_id_1 {
+            return Err(MatchmakingServiceError::PendingGameError(
+                format!("Account {} is not a player of pending game {}", account_id, pending_game_id)
+            ));
+        }
+
+        let pending_game_end = self.pending_game_repository.decline_pending_game(
+            &pending_game,
+            account_id,
+            self.player_status_service.ttl_seconds(PlayerStatus::Searching),
+        ).await?
+            .ok_or_else(|| MatchmakingServiceError::PendingGameError(
+                format!("Pending game {} can't be declined anymore", pending_game_id)
+            ))?;
+
+        tracing::debug!("Player {} declined pending game {}", account_id, pending_game_id);
+
+        self.queue_penalty_service.record_offense(account_id, QueueOffense::Decline, &pending_game.id).await?;
+
+        self.emit_pending_game_ended(&pending_game, pending_game_end).await
+    }
+
+    /// Tells both players their pending game is over, along with the status they went to.
+    async fn emit_pending_game_ended(
+        &self,
+        pending_game: &PendingGame,
+        pending_game_end: PendingGameEnd,
+    ) -> Result<(), MatchmakingServiceError> {
+        self.player_status_service.record_transitions(
+            [&pending_game.account_id_0, &pending_game.account_id_1]
+                .into_iter()
+                .zip(pending_game_end.statuses)
+                .filter_map(|(account_id, status)| Some(PlayerStatusTransition::new(account_id, PlayerStatus::Pending, status?)))
+                .collect()
+        ).await?;
+
+        crate::services::streaming_service::emit_pending_game_timeout(
+            &self.state,
+            PendingGameTimeoutEvent {
+                account_id_0: pending_game.account_id_0.clone(),
+                account_id_1: pending_game.account_id_1.clone(),
+                pending_game_id: pending_game.id.clone(),
+            }
+        ).await
+    }
+}
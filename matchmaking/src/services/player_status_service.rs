@@ -1,31 +1,121 @@
-use std::sync::Arc;
-use shared::primitives::GameType;
+use std::{collections::HashMap, sync::Arc};
+use shared::{events::PlayerStatusChangedEvent, primitives::GameType};
 
-use crate::{error::MatchmakingServiceError, repositories::{matchmaking_queue_repository::PlayerStatus, player_status_repository::PlayerStatusRepository}};
+use crate::{error::MatchmakingServiceError, repositories::{matchmaking_queue_repository::{PlayerStatus, QueueType}, player_status_repository::PlayerStatusRepository}, services::player_status_transitions::{PlayerStatusTransition, PlayerStatusTtls}, state::state::AppState};
 
 // #[derive(Serialize, Deserialize)]
 pub struct MatchMakingStatus {
     pub status: PlayerStatus,
     pub game_type: Option<GameType>,
     pub ranked: Option<bool>,
-    pub game_id: Option<String>
+    pub game_id: Option<String>,
+    /// Queues the player is searching in
+    pub pools: Vec<QueueType>,
 }
 
 #[async_trait::async_trait]
 pub trait PlayerStatusServiceContract: Send + Sync {
     async fn get_player_status(&self, account_id: &str) -> Result<MatchMakingStatus, MatchmakingServiceError>;
+    /// Time a status is kept for, passed to the scripts that write it.
+    fn ttl_seconds(&self, status: PlayerStatus) -> u64;
+    /// Publishes status changes the queue and pending game scripts made.
+    async fn record_transitions(&self, transitions: Vec<PlayerStatusTransition>) -> Result<(), MatchmakingServiceError>;
+    async fn set_player_playing(&self, account_id: &str, game_id: &str, game_type: &GameType, ranked: bool) -> Result<(), MatchmakingServiceError>;
+    /// Frees a player once their game is over, unless they already moved on.
+    async fn clear_player_game(&self, account_id: &str, game_id: &str) -> Result<(), MatchmakingServiceError>;
 }
 
+/// The only writer of player statuses besides the queue and pending game scripts, see
+/// [`crate::services::player_status_transitions`]. Every transition is published to the gateway.
 pub struct PlayerStatusService {
+    state: AppState,
+    ttls: PlayerStatusTtls,
     pub player_status_repository: Arc<dyn PlayerStatusRepository>,
 }
 
 impl PlayerStatusService {
-    pub fn new(player_status_repository: Arc<dyn PlayerStatusRepository>) -> Self {
+    pub fn new(state: AppState, player_status_repository: Arc<dyn PlayerStatusRepository>) -> Self {
         Self {
+            ttls: PlayerStatusTtls::from_config(&state.config.player_status),
+            state,
             player_status_repository,
         }
     }
+
+    pub async fn try_lock_reconcile(&self, owner: &str, ttl_ms: u64) -> Result<bool, MatchmakingServiceError> {
+        self.player_status_repository.acquire_reconcile_lock(owner, ttl_ms).await
+    }
+
+    /// Frees every player whose status says they are playing a game the game service doesn't
+    /// know anymore, e.g. because it crashed before publishing the result. A player who can't be
+    /// checked or freed is skipped until the next run. Returns the number of players freed.
+    pub async fn reconcile_player_statuses(&self) -> Result<usize, MatchmakingServiceError> {
+        let batch_size = self.state.config.player_status.reconcile_batch_size;
+        // Both players of a game are usually seen in the same scan
+        let mut game_exists: HashMap<String, bool> = HashMap::new();
+        let mut freed = 0;
+        let mut cursor = 0;
+
+        loop {
+            let (next_cursor, statuses) = self.player_status_repository.scan_player_statuses(cursor, batch_size).await?;
+
+            for (account_id, status) in statuses {
+                let (PlayerStatus::Playing, Some(game_id)) = (status.status, status.game_id) else {
+                    continue;
+                };
+
+                match self.reconcile_player_status(&account_id, &game_id, &mut game_exists).await {
+                    Ok(true) => freed += 1,
+                    Ok(false) => {}
+                    Err(e) => tracing::error!("Failed to reconcile the status of player {} in game {}: {}", account_id, game_id, e),
+                }
+            }
+
+            if next_cursor == 0 {
+                return Ok(freed);
+            }
+            cursor = next_cursor;
+        }
+    }
+
+    /// Frees a player from their game if it is gone. Returns whether the player was freed.
+    async fn reconcile_player_status(
+        &self,
+        account_id: &str,
+        game_id: &str,
+        game_exists: &mut HashMap<String, bool>,
+    ) -> Result<bool, MatchmakingServiceError> {
+        let exists = match game_exists.get(game_id) {
+            Some(exists) => *exists,
+            None => {
+                let exists = self.game_exists(game_id).await?;
+                game_exists.insert(game_id.to_owned(), exists);
+                exists
+            }
+        };
+
+        if exists {
+            return Ok(false);
+        }
+
+        tracing::warn!("Freeing player {} from game {}, which is gone", account_id, game_id);
+        self.clear_player_game(account_id, game_id).await?;
+
+        Ok(true)
+    }
+
+    async fn game_exists(&self, game_id: &str) -> Result<bool, MatchmakingServiceError> {
+        let result = self.state.game_client.clone()
+            .get_game_state(shared::GetGameStateRequest { game_id: game_id.to_owned() })
+            .await;
+
+        match result {
+            Ok(_) => Ok(true),
+            // Games are deleted once over
+            Err(status) if status.code() == tonic::Code::NotFound => Ok(false),
+            Err(status) => Err(status.into()),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -33,4 +123,65 @@ impl PlayerStatusServiceContract for PlayerStatusService  {
     async fn get_player_status(&self, account_id: &str) -> Result<MatchMakingStatus, MatchmakingServiceError> {
         self.player_status_repository.get_player_status(account_id).await
     }
+
+    fn ttl_seconds(&self, status: PlayerStatus) -> u64 {
+        self.ttls.ttl_seconds(status)
+    }
+
+    async fn record_transitions(&self, transitions: Vec<PlayerStatusTransition>) -> Result<(), MatchmakingServiceError> {
+        for transition in transitions {
+            transition.validate()?;
+
+            tracing::debug!(
+                "Player {} went from {} to {}",
+                transition.account_id,
+                transition.from.as_str(),
+                transition.to.as_str()
+            );
+
+            crate::services::streaming_service::emit_player_status_changed(
+                &self.state,
+                PlayerStatusChangedEvent {
+                    account_id: transition.account_id,
+                    previous_status: transition.from.as_str().to_owned(),
+                    status: transition.to.as_str().to_owned(),
+                    game_id: transition.game_id,
+                }
+            ).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn set_player_playing(&self, account_id: &str, game_id: &str, game_type: &GameType, ranked: bool) -> Result<(), MatchmakingServiceError> {
+        let (playing, status) = self.player_status_repository.set_player_playing(
+            account_id,
+            game_id,
+            game_type,
+            ranked,
+            &PlayerStatus::Playing.previous_statuses(),
+            self.ttls.ttl_seconds(PlayerStatus::Playing),
+        ).await?;
+
+        match (playing, status) {
+            (true, _) => {}
+            // Already playing this game
+            (false, PlayerStatus::Playing) => return Ok(()),
+            (false, status) => return Err(MatchmakingServiceError::PlayerStatusTransitionError { from: status, to: PlayerStatus::Playing }),
+        }
+
+        self.record_transitions(vec![
+            PlayerStatusTransition::new(account_id, status, PlayerStatus::Playing).with_game_id(game_id)
+        ]).await
+    }
+
+    async fn clear_player_game(&self, account_id: &str, game_id: &str) -> Result<(), MatchmakingServiceError> {
+        if !self.player_status_repository.clear_player_game(account_id, game_id).await? {
+            return Ok(());
+        }
+
+        self.record_transitions(vec![
+            PlayerStatusTransition::new(account_id, PlayerStatus::Playing, PlayerStatus::Undefined).with_game_id(game_id)
+        ]).await
+    }
 }
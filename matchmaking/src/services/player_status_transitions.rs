@@ -0,0 +1,130 @@
+//! Every status a player can be in and the transitions between them.
+//!
+//! - `undefined` -> `searching` when joining the queues, -> `playing` when a challenge or a
+//!   rematch starts
+//! - `searching` -> `pending` when matched, -> `undefined` when leaving the queues, -> `playing`
+//!   when a challenge starts
+//! - `pending` -> `playing` when the game is created, -> `searching` when requeued after the other
+//!   player declined or let the pending game time out, -> `undefined` otherwise
+//! - `playing` -> `undefined` when the game is over or gone, -> `playing` when the next game (a
+//!   rematch) starts before the result of the last one is applied
+//!
+//! Statuses are written atomically with the queues and pending games by the Lua scripts, which
+//! only apply these edges, and by [`crate::services::player_status_service`]. Every status
+//! but `undefined` expires, so a player is never stuck even if a transition is missed.
+
+use crate::{config::PlayerStatusConfig, error::MatchmakingServiceError, repositories::matchmaking_queue_repository::PlayerStatus};
+
+impl PlayerStatus {
+    pub const ALL: [PlayerStatus; 4] = [
+        PlayerStatus::Undefined,
+        PlayerStatus::Searching,
+        PlayerStatus::Pending,
+        PlayerStatus::Playing,
+    ];
+
+    /// Statuses a player in this status can move to.
+    pub fn next_statuses(&self) -> &'static [PlayerStatus] {
+        match self {
+            PlayerStatus::Undefined => &[PlayerStatus::Searching, PlayerStatus::Playing],
+            PlayerStatus::Searching => &[PlayerStatus::Undefined, PlayerStatus::Pending, PlayerStatus::Playing],
+            PlayerStatus::Pending => &[PlayerStatus::Undefined, PlayerStatus::Searching, PlayerStatus::Playing],
+            PlayerStatus::Playing => &[PlayerStatus::Undefined, PlayerStatus::Playing],
+        }
+    }
+
+    pub fn can_transition_to(&self, status: PlayerStatus) -> bool {
+        self.next_statuses().contains(&status)
+    }
+
+    /// Statuses a player can move to this status from.
+    pub fn previous_statuses(&self) -> Vec<PlayerStatus> {
+        PlayerStatus::ALL
+            .into_iter()
+            .filter(|previous| previous.can_transition_to(*self))
+            .collect()
+    }
+}
+
+/// A status change of a player, published to the gateway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerStatusTransition {
+    pub account_id: String,
+    pub from: PlayerStatus,
+    pub to: PlayerStatus,
+    /// Game the player is playing, or stopped playing
+    pub game_id: Option<String>,
+}
+
+impl PlayerStatusTransition {
+    pub fn new(account_id: &str, from: PlayerStatus, to: PlayerStatus) -> Self {
+        Self { account_id: account_id.to_owned(), from, to, game_id: None }
+    }
+
+    pub fn with_game_id(mut self, game_id: &str) -> Self {
+        self.game_id = Some(game_id.to_owned());
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), MatchmakingServiceError> {
+        match self.from.can_transition_to(self.to) {
+            true => Ok(()),
+            false => Err(MatchmakingServiceError::PlayerStatusTransitionError { from: self.from, to: self.to }),
+        }
+    }
+}
+
+/// How long a player stays in each status unless they move on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerStatusTtls {
+    pub searching_seconds: u64,
+    pub pending_seconds: u64,
+    pub playing_seconds: u64,
+}
+
+impl PlayerStatusTtls {
+    pub fn from_config(config: &PlayerStatusConfig) -> Self {
+        Self {
+            searching_seconds: config.searching_ttl_seconds,
+            pending_seconds: config.pending_ttl_seconds,
+            playing_seconds: config.playing_ttl_seconds,
+        }
+    }
+
+    /// `0` for `undefined`, which isn't stored.
+    pub fn ttl_seconds(&self, status: PlayerStatus) -> u64 {
+        match status {
+            PlayerStatus::Undefined => 0,
+            PlayerStatus::Searching => self.searching_seconds,
+            PlayerStatus::Pending => self.pending_seconds,
+            PlayerStatus::Playing => self.playing_seconds,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repositories::matchmaking_queue_repository::PlayerStatus;
+
+    use super::PlayerStatusTransition;
+
+    #[test]
+    fn test_player_status_transitions() {
+        let transition = |from, to| PlayerStatusTransition::new("a", from, to).validate().is_ok();
+
+        assert!(transition(PlayerStatus::Undefined, PlayerStatus::Searching));
+        assert!(transition(PlayerStatus::Searching, PlayerStatus::Pending));
+        assert!(transition(PlayerStatus::Pending, PlayerStatus::Searching));
+        assert!(transition(PlayerStatus::Pending, PlayerStatus::Playing));
+        assert!(transition(PlayerStatus::Playing, PlayerStatus::Undefined));
+
+        // Matched or playing players have to be freed before they can search again
+        assert!(!transition(PlayerStatus::Undefined, PlayerStatus::Pending));
+        assert!(!transition(PlayerStatus::Pending, PlayerStatus::Pending));
+        assert!(!transition(PlayerStatus::Playing, PlayerStatus::Searching));
+        assert!(!transition(PlayerStatus::Playing, PlayerStatus::Pending));
+
+        assert_eq!(PlayerStatus::Searching.previous_statuses(), [PlayerStatus::Undefined, PlayerStatus::Pending]);
+        assert_eq!(PlayerStatus::Playing.previous_statuses(), PlayerStatus::ALL);
+    }
+}
@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use crate::{config::MatcherConfig, error::MatchmakingServiceError, repositories::queue_penalty_repository::QueuePenaltyRepository};
+
+/// Something a player did that keeps others from playing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueOffense {
+    /// Declined a pending game
+    Decline,
+    /// Let a pending game time out without accepting it
+    PendingGameTimeout,
+    /// Ran out of time in a game without making a move
+    Abandon,
+}
+
+impl QueueOffense {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueueOffense::Decline => "decline",
+            QueueOffense::PendingGameTimeout => "pending-game-timeout",
+            QueueOffense::Abandon => "abandon",
+        }
+    }
+
+    /// How much the offense adds to the penalty score, an abandoned game wastes more of the
+    /// opponent's time than a declined match.
+    fn weight(&self) -> f64 {
+        match self {
+            QueueOffense::Decline | QueueOffense::PendingGameTimeout => 1.0,
+            QueueOffense::Abandon => 2.0,
+        }
+    }
+}
+
+/// How offenses decay and how long the cooldowns they lead to are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueuePenaltyPolicy {
+    /// Time after which the score of past offenses has halved
+    pub half_life_ms: u64,
+    /// Cooldown after a second recent offense, doubling with every further one
+    pub base_cooldown_ms: u64,
+    pub max_cooldown_ms: u64,
+}
+
+impl QueuePenaltyPolicy {
+    pub fn from_config(config: &MatcherConfig) -> Self {
+        Self {
+            half_life_ms: config.queue_penalty_half_life_seconds * 1000,
+            base_cooldown_ms: config.queue_cooldown_base_seconds * 1000,
+            max_cooldown_ms: config.queue_cooldown_max_seconds * 1000,
+        }
+    }
+}
+
+/// Recent offenses of a player, as a score decaying over time, and the cooldown they led to.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QueuePenalty {
+    /// Weighted count of offenses as of `updated_at_ms`
+    pub score: f64,
+    /// Unix timestamp (ms) of the last offense
+    pub updated_at_ms: u64,
+    /// Unix timestamp (ms) the player can join queues again at
+    pub cooldown_until_ms: u64,
+}
+
+impl QueuePenalty {
+    /// Score left at `now_ms`, halving every half life.
+    pub fn score_at(&self, now_ms: u64, policy: &QueuePenaltyPolicy) -> f64 {
+        let elapsed_ms = now_ms.saturating_sub(self.updated_at_ms) as f64;
+
+        self.score * 0.5f64.powf(elapsed_ms / policy.half_life_ms.max(1) as f64)
+    }
+
+    pub fn remaining_cooldown_ms(&self, now_ms: u64) -> u64 {
+        self.cooldown_until_ms.saturating_sub(now_ms)
+    }
+
+    /// Penalty after an offense at `now_ms`. The score rounded is the number of recent offenses, a
+    /// single one is forgiven and every further one doubles the cooldown.
+    pub fn record(&self, offense: QueueOffense, now_ms: u64, policy: &QueuePenaltyPolicy) -> QueuePenalty {
+        let score = self.score_at(now_ms, policy) + offense.weight();
+
+        let cooldown_ms = match score.round() as u32 {
+            0 | 1 => 0,
+            offenses => policy.base_cooldown_ms
+                .saturating_mul(1 << (offenses - 2).min(32))
+                .min(policy.max_cooldown_ms),
+        };
+
+        QueuePenalty {
+            score,
+            updated_at_ms: now_ms,
+            cooldown_until_ms: self.cooldown_until_ms.max(now_ms + cooldown_ms),
+        }
+    }
+}
+
+pub struct QueuePenaltyService {
+    policy: QueuePenaltyPolicy,
+    queue_penalty_repository: Arc<dyn QueuePenaltyRepository>,
+}
+
+impl QueuePenaltyService {
+    pub fn new(policy: QueuePenaltyPolicy, queue_penalty_repository: Arc<dyn QueuePenaltyRepository>) -> Self {
+        Self { policy, queue_penalty_repository }
+    }
+
+    /// Records an offense committed in game or pending game `offense_id`. An offense is only
+    /// recorded once, `None` if it already was.
+    pub async fn record_offense(
+        &self,
+        account_id: &str,
+        offense: QueueOffense,
+        offense_id: &str,
+    ) -> Result<Option<QueuePenalty>, MatchmakingServiceError> {
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+        let penalty = self.queue_penalty_repository
+            .find_penalty(account_id)
+            .await?
+            .unwrap_or_default()
+            .record(offense, now_ms, &self.policy);
+
+        // Kept until the score has decayed to a negligible 1/1024 and the cooldown is over
+        let ttl_ms = (self.policy.half_life_ms * 10).max(penalty.remaining_cooldown_ms(now_ms));
+        if !self.queue_penalty_repository.save_penalty(account_id, offense_id, &penalty, ttl_ms).await? {
+            tracing::debug!("Player {} was already penalized for {} in {}", account_id, offense.as_str(), offense_id);
+            return Ok(None);
+        }
+
+        tracing::debug!(
+            "Player {} penalized for {} in {}, score {:.2}, cooldown {} s",
+            account_id,
+            offense.as_str(),
+            offense_id,
+            penalty.score,
+            penalty.remaining_cooldown_ms(now_ms) / 1000
+        );
+
+        Ok(Some(penalty))
+    }
+
+    /// Current score and remaining cooldown (s) of a player.
+    pub async fn get_penalty(
+        &self,
+        account_id: &str,
+    ) -> Result<(f64, u64), MatchmakingServiceError> {
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+        let penalty = self.queue_penalty_repository.find_penalty(account_id).await?.unwrap_or_default();
+
+        Ok((
+            penalty.score_at(now_ms, &self.policy),
+            penalty.remaining_cooldown_ms(now_ms).div_ceil(1000),
+        ))
+    }
+
+    /// Fails while the player is on a queue cooldown.
+    pub async fn check_queue_cooldown(
+        &self,
+        account_id: &str,
+    ) -> Result<(), MatchmakingServiceError> {
+        let (_, remaining_seconds) = self.get_penalty(account_id).await?;
+
+        match remaining_seconds {
+            0 => Ok(()),
+            remaining_seconds => Err(MatchmakingServiceError::QueueCooldownError { remaining_seconds }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QueueOffense, QueuePenalty, QueuePenaltyPolicy};
+
+    const MINUTE_MS: u64 = 60_000;
+
+    #[test]
+    fn test_queue_penalty_escalates_and_decays() {
+        let policy = QueuePenaltyPolicy {
+            half_life_ms: 60 * MINUTE_MS,
+            base_cooldown_ms: MINUTE_MS,
+            max_cooldown_ms: 4 * MINUTE_MS,
+        };
+        let now_ms = 1_000_000_000;
+
+        // The first decline is forgiven, the next ones double the cooldown
+        let penalty = QueuePenalty::default().record(QueueOffense::Decline, now_ms, &policy);
+        assert_eq!(penalty.remaining_cooldown_ms(now_ms), 0);
+
+        let penalty = penalty.record(QueueOffense::PendingGameTimeout, now_ms, &policy);
+        assert_eq!(penalty.remaining_cooldown_ms(now_ms), MINUTE_MS);
+
+        let penalty = penalty.record(QueueOffense::Decline, now_ms, &policy);
+        assert_eq!(penalty.remaining_cooldown_ms(now_ms), 2 * MINUTE_MS);
+
+        let penalty = penalty.record(QueueOffense::Abandon, now_ms, &policy);
+        assert_eq!(penalty.remaining_cooldown_ms(now_ms), 4 * MINUTE_MS);
+
+        // Half of the score is left after a half life, an older decline is forgiven after two
+        let later_ms = now_ms + 60 * MINUTE_MS;
+        assert_eq!(penalty.score_at(later_ms, &policy), 2.5);
+
+        let penalty = QueuePenalty::default().record(QueueOffense::Decline, now_ms, &policy);
+        let penalty = penalty.record(QueueOffense::Decline, now_ms + 60 * MINUTE_MS, &policy);
+        assert_eq!(penalty.remaining_cooldown_ms(now_ms + 60 * MINUTE_MS), MINUTE_MS);
+
+        let penalty = QueuePenalty::default().record(QueueOffense::Decline, now_ms, &policy);
+        let penalty = penalty.record(QueueOffense::Decline, now_ms + 120 * MINUTE_MS, &policy);
+        assert_eq!(penalty.remaining_cooldown_ms(now_ms + 120 * MINUTE_MS), 0);
+    }
+}
@@ -0,0 +1,235 @@
+//! Queue pools players can join, with the search window of each.
+//!
+//! Pools are read from the JSON file at `QUEUE_POOLS_PATH`, a list of objects such as
+//! `{"id": "blitz-5-0-ranked", "time_control": "Blitz5_0", "variant": "standard", "rated": true,
+//! "base_mmr_range": 50, "mmr_range_increase_per_second": 5, "max_mmr_delta": 400, "enabled": true}`,
+//! or are the built-in ones without a file. The file is read again on every reload, an invalid
+//! file leaves the current pools in place. A reload requested through the admin RPC is broadcast
+//! over NATS, so every replica reads its file again.
+//!
+//! Every pool is backed by one of the fixed queues of `QueueType::all()`, one per real-time time
+//! control and rated flag, and no two pools can share a queue. Pools tune the search window of
+//! those queues and open or close them, but can't add a time control or a second pool, e.g. of
+//! another variant, to a queue.
+
+use std::{collections::HashSet, sync::{Arc, RwLock}};
+
+use serde::{Deserialize, Serialize};
+use shared::primitives::GameType;
+
+use crate::{config::MatcherConfig, error::MatchmakingServiceError, repositories::matchmaking_queue_repository::{QueueConfig, QueueType}};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    #[default]
+    Standard,
+}
+
+impl Variant {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Variant::Standard => "standard",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolDefinition {
+    pub id: String,
+    pub time_control: GameType,
+    #[serde(default)]
+    pub variant: Variant,
+    pub rated: bool,
+    pub base_mmr_range: i32,
+    pub mmr_range_increase_per_second: i32,
+    pub max_mmr_delta: i32,
+    /// Disabled pools can't be joined and aren't matched, players already in them stay matchable
+    /// in their other pools
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl PoolDefinition {
+    pub fn queue_type(&self) -> QueueType {
+        QueueType { game_type: self.time_control, ranked: self.rated }
+    }
+
+    pub fn queue_config(&self) -> QueueConfig {
+        QueueConfig {
+            base_mmr_range: self.base_mmr_range,
+            mmr_range_increase_per_second: self.mmr_range_increase_per_second,
+            max_mmr_delta: self.max_mmr_delta,
+        }
+    }
+
+    /// Pools used without a pools file: every real-time game type, ranked with a narrower window
+    /// than normal.
+    pub fn defaults() -> Vec<PoolDefinition> {
+        QueueType::all()
+            .into_iter()
+            .map(|queue_type| {
+                let (base_mmr_range, mmr_range_increase_per_second, max_mmr_delta) = match queue_type.ranked {
+                    true => (50, 5, 400),
+                    false => (100, 10, 600),
+                };
+
+                PoolDefinition {
+                    id: queue_type.pool_id(),
+                    time_control: queue_type.game_type,
+                    variant: Variant::Standard,
+                    rated: queue_type.ranked,
+                    base_mmr_range,
+                    mmr_range_increase_per_second,
+                    max_mmr_delta,
+                    enabled: true,
+                }
+            })
+            .collect()
+    }
+
+    /// Parses and validates a list of pools.
+    pub fn parse_all(json: &str) -> Result<Vec<PoolDefinition>, MatchmakingServiceError> {
+        let pools: Vec<PoolDefinition> = serde_json::from_str(json)
+            .map_err(|e| MatchmakingServiceError::QueuePoolConfigError(e.to_string()))?;
+
+        Self::validate_all(&pools)?;
+
+        Ok(pools)
+    }
+
+    pub fn validate_all(pools: &[PoolDefinition]) -> Result<(), MatchmakingServiceError> {
+        let error = |message: String| Err(MatchmakingServiceError::QueuePoolConfigError(message));
+        let queue_types = QueueType::all();
+        let mut ids = HashSet::new();
+        let mut pool_queue_types = HashSet::new();
+
+        for pool in pools {
+            if pool.id.is_empty() {
+                return error("Pool without an id".into());
+            }
+
+            if !ids.insert(pool.id.as_str()) {
+                return error(format!("Pool {} is defined twice", pool.id));
+            }
+
+            // Only real-time games are matched in queues
+            if !queue_types.contains(&pool.queue_type()) {
+                return error(format!("Pool {} has no queue for time control {}", pool.id, pool.time_control.to_str()));
+            }
+
+            if !pool_queue_types.insert(pool.queue_type()) {
+                return error(format!("Pool {} shares its queue with another pool", pool.id));
+            }
+
+            if pool.base_mmr_range < 0 || pool.mmr_range_increase_per_second < 0 || pool.max_mmr_delta < pool.base_mmr_range {
+                return error(format!("Pool {} has an invalid search window", pool.id));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct QueuePoolService {
+    pools_path: Option<String>,
+    pools: RwLock<Arc<Vec<PoolDefinition>>>,
+}
+
+impl QueuePoolService {
+    pub fn new(config: &MatcherConfig) -> Result<Self, MatchmakingServiceError> {
+        let pools_path = config.queue_pools_path.clone().filter(|path| !path.is_empty());
+        let pools = Self::load(pools_path.as_deref())?;
+
+        Ok(Self { pools_path, pools: RwLock::new(Arc::new(pools)) })
+    }
+
+    pub fn with_pools(pools: Vec<PoolDefinition>) -> Result<Self, MatchmakingServiceError> {
+        PoolDefinition::validate_all(&pools)?;
+
+        Ok(Self { pools_path: None, pools: RwLock::new(Arc::new(pools)) })
+    }
+
+    fn load(pools_path: Option<&str>) -> Result<Vec<PoolDefinition>, MatchmakingServiceError> {
+        match pools_path {
+            Some(pools_path) => {
+                let json = std::fs::read_to_string(pools_path)
+                    .map_err(|e| MatchmakingServiceError::QueuePoolConfigError(format!("{}: {}", pools_path, e)))?;
+
+                PoolDefinition::parse_all(&json)
+            }
+            None => Ok(PoolDefinition::defaults()),
+        }
+    }
+
+    /// Reads the pools again, keeping the current ones if they are invalid.
+    pub fn reload(&self) -> Result<Arc<Vec<PoolDefinition>>, MatchmakingServiceError> {
+        let pools = Arc::new(Self::load(self.pools_path.as_deref())?);
+
+        *self.pools.write().expect("queue pools lock poisoned") = pools.clone();
+
+        tracing::info!(
+            "Queue pools reloaded: {}",
+            pools.iter().filter(|pool| pool.enabled).map(|pool| pool.id.as_str()).collect::<Vec<_>>().join(", ")
+        );
+
+        Ok(pools)
+    }
+
+    pub fn pools(&self) -> Arc<Vec<PoolDefinition>> {
+        self.pools.read().expect("queue pools lock poisoned").clone()
+    }
+
+    pub fn enabled_pools(&self) -> Vec<PoolDefinition> {
+        self.pools().iter().filter(|pool| pool.enabled).cloned().collect()
+    }
+
+    /// Enabled pool of the queue, `None` if players can't join it.
+    pub fn find_enabled_pool(&self, queue_type: &QueueType) -> Option<PoolDefinition> {
+        self.pools().iter().find(|pool| pool.enabled && pool.queue_type() == *queue_type).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::primitives::GameType;
+
+    use crate::repositories::matchmaking_queue_repository::QueueType;
+
+    use super::{PoolDefinition, QueuePoolService};
+
+    #[test]
+    fn test_queue_pools() {
+        assert!(PoolDefinition::validate_all(&PoolDefinition::defaults()).is_ok());
+
+        let pool = |id: &str, time_control: &str, extra: &str| format!(
+            r#"{{"id": "{}", "time_control": "{}", "rated": true, "base_mmr_range": 50, "mmr_range_increase_per_second": 5, "max_mmr_delta": 400{}}}"#,
+            id, time_control, extra,
+        );
+        let parse = |pools: &[String]| PoolDefinition::parse_all(&format!("[{}]", pools.join(",")));
+
+        let pools = parse(&[
+            pool("blitz", "Blitz5_0", ""),
+            pool("bullet", "Bullet1_0", r#", "variant": "standard", "enabled": false"#),
+        ]).unwrap();
+        let service = QueuePoolService::with_pools(pools).unwrap();
+
+        let blitz = QueueType { game_type: GameType::Blitz5_0, ranked: true };
+        assert_eq!(service.find_enabled_pool(&blitz).map(|pool| pool.queue_config().max_mmr_delta), Some(400));
+        assert!(service.find_enabled_pool(&QueueType { game_type: GameType::Bullet1_0, ranked: true }).is_none());
+        assert!(service.find_enabled_pool(&QueueType { game_type: GameType::Blitz5_0, ranked: false }).is_none());
+        assert_eq!(service.enabled_pools().len(), 1);
+
+        assert!(parse(&[pool("blitz", "Blitz5_0", ""), pool("blitz", "Blitz3_0", "")]).is_err());
+        assert!(parse(&[pool("a", "Blitz5_0", ""), pool("b", "Blitz5_0", "")]).is_err());
+        assert!(parse(&[pool("daily", "Correspondence1d", "")]).is_err());
+        assert!(parse(&[pool("blitz", "Blitz5_0", r#", "variant": "chess960""#)]).is_err());
+
+        let narrow = PoolDefinition { max_mmr_delta: 10, ..PoolDefinition::defaults()[0].clone() };
+        assert!(PoolDefinition::validate_all(&[narrow]).is_err());
+    }
+}
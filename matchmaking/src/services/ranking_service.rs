@@ -1,29 +1,104 @@
-use std::sync::Arc;
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use chrono::{DateTime, Utc};
+use shared::{events::{EloChangeEvent, GameOverEvent}, primitives::{GameType, RatingCategory}};
 use uuid::Uuid;
 
-use crate::{error::MatchmakingServiceError, repositories::ranking_repository::RankingRepository};
+use crate::{error::MatchmakingServiceError, repositories::ranking_repository::{RankingRepository, RankingUpdateOutcome}, services::{glicko2::Rating, rating_history::{GameResult, RatingHistory}}};
 
  #[async_trait::async_trait]
 pub trait RankingServiceContract: Send + Sync {
     async fn get_or_create_ranking(&self, account_id: &str) -> Result<Ranking, MatchmakingServiceError>;
+    /// Applies the result of a finished game to both players' rankings. Returns no updates if
+    /// the game was already applied.
+    async fn apply_game_result(&self, game_over: &GameOverEvent) -> Result<Vec<RankingUpdate>, MatchmakingServiceError>;
+    /// Changes recorded when the result of a game was applied.
+    async fn find_applied_changes(&self, game_id: &str) -> Result<Vec<AppliedChange>, MatchmakingServiceError>;
+    /// Rating history of every category the player has played in a pool.
+    async fn get_rating_history(&self, account_id: &str, ranked: bool) -> Result<HashMap<RatingCategory, RatingHistory>, MatchmakingServiceError>;
+}
+
+/// Players have a separate rating for ranked and normal games of each rating category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RatingPool {
+    pub category: RatingCategory,
+    pub ranked: bool,
+}
+
+impl RatingPool {
+    pub fn of_game(game_type: &GameType, ranked: bool) -> Self {
+        Self { category: game_type.rating_category(), ranked }
+    }
 }
 
 pub struct Ranking {
     pub id: String,
     pub account_id: String,
-    pub ranked_mmr: u16,
-    pub normal_mmr: u16,
+    /// Only the pools the player has played rated games in
+    pub ratings: HashMap<RatingPool, Rating>,
+    /// Open season the ranked ratings belong to, `None` while no season is open
+    pub season_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
+impl Ranking {
+    pub fn rating(&self, pool: RatingPool) -> Rating {
+        self.ratings.get(&pool).copied().unwrap_or_default()
+    }
+}
+
+pub struct RankingUpdate {
+    pub account_id: String,
+    pub game_id: String,
+    pub game_type: GameType,
+    pub pool: RatingPool,
+    /// Season of a ranked rating
+    pub season_id: Option<String>,
+    /// When the rating being replaced was last updated
+    pub previous_rated_at: Option<DateTime<Utc>>,
+    pub rating: Rating,
+    pub mmr_change: i64,
+    pub result: GameResult,
+}
+
+impl From<&RankingUpdate> for EloChangeEvent {
+    fn from(update: &RankingUpdate) -> Self {
+        EloChangeEvent {
+            account_id: update.account_id.clone(),
+            new_elo: update.rating.mmr() as i32,
+            elo_change: update.mmr_change as i32,
+            ranked: update.pool.ranked,
+        }
+    }
+}
+
+/// MMR change of a player as recorded with the result of a game
+pub struct AppliedChange {
+    pub account_id: String,
+    pub pool: RatingPool,
+    pub mmr_change: i64,
+    /// Rating right after the game
+    pub rating: f64,
+}
+
+impl From<&AppliedChange> for EloChangeEvent {
+    fn from(change: &AppliedChange) -> Self {
+        EloChangeEvent {
+            account_id: change.account_id.clone(),
+            new_elo: Rating { rating: change.rating, ..Rating::default() }.mmr() as i32,
+            elo_change: change.mmr_change as i32,
+            ranked: change.pool.ranked,
+        }
+    }
+}
+
 pub struct MyRankingService {
     ranking_repository: Arc<dyn RankingRepository>,
 }
 
 impl MyRankingService {
-    pub const STARTING_MMR: u16 = 1000;
+    /// Attempts to apply a game result when the players' ratings keep changing concurrently
+    const MAX_UPDATE_ATTEMPTS: usize = 5;
 
     pub fn new(
         ranking_repository: Arc<dyn RankingRepository>,
@@ -44,13 +119,99 @@ impl RankingServiceContract for MyRankingService {
         let ranking = Ranking {
             account_id: account_id.to_string(),
             id: Uuid::new_v4().to_string(),
-            ranked_mmr: Self::STARTING_MMR,
-            normal_mmr: Self::STARTING_MMR,
+            ratings: HashMap::new(),
+            season_id: None,
             created_at: Utc::now(),
         };
 
         self.ranking_repository.insert_ranking(&ranking).await?;
 
-        Ok(ranking)
+        // Read back so the ranking knows the current season
+        Ok(self.ranking_repository.find_ranking(account_id).await?.unwrap_or(ranking))
+    }
+
+    async fn apply_game_result(&self, game_over: &GameOverEvent) -> Result<Vec<RankingUpdate>, MatchmakingServiceError> {
+        let game_type = GameType::from_str(&game_over.game_type)
+            .map_err(|e| MatchmakingServiceError::UnknownGameTypeError(e.to_string()))?;
+
+        let pool = RatingPool::of_game(&game_type, game_over.ranked);
+        let account_ids = [&game_over.account_id_0, &game_over.account_id_1];
+
+        let result = |account_id: &str| match &game_over.winner_account_id {
+            Some(winner_account_id) if winner_account_id == account_id => GameResult::Win,
+            Some(_) => GameResult::Loss,
+            None => GameResult::Draw,
+        };
+
+        for _ in 0..Self::MAX_UPDATE_ATTEMPTS {
+            let now = Utc::now();
+            let mut ratings = [Rating::default(); 2];
+            let mut season_ids = [None, None];
+
+            for ((rating, season_id), account_id) in ratings.iter_mut().zip(season_ids.iter_mut()).zip(account_ids) {
+                let ranking = self.get_or_create_ranking(account_id).await?;
+                *rating = ranking.rating(pool);
+                *season_id = ranking.season_id.filter(|_| pool.ranked);
+            }
+
+            // A season opened between both reads
+            if season_ids[0] != season_ids[1] {
+                continue;
+            }
+
+            // A ranked game that outlasted its season is left unrated, the season's results are final
+            if pool.ranked && season_ids[0].is_none() {
+                tracing::debug!("Game {} ended outside of a season, skipping its ranked ratings", game_over.game_id);
+                return Ok(Vec::new());
+            }
+
+            let updates = [(0, 1), (1, 0)].map(|(player, opponent)| {
+                let previous = ratings[player];
+                let result = result(account_ids[player]);
+                let rating = previous.at(now).rate(
+                    &[(ratings[opponent].at(now), result.score())],
+                    now,
+                );
+
+                RankingUpdate {
+                    account_id: account_ids[player].to_owned(),
+                    game_id: game_over.game_id.clone(),
+                    game_type,
+                    pool,
+                    season_id: season_ids[player].clone(),
+                    previous_rated_at: previous.rated_at,
+                    rating,
+                    mmr_change: rating.mmr() as i64 - previous.mmr() as i64,
+                    result,
+                }
+            });
+
+            match self.ranking_repository.update_rankings(&updates).await? {
+                RankingUpdateOutcome::Updated => return Ok(updates.into()),
+                RankingUpdateOutcome::AlreadyApplied => return Ok(Vec::new()),
+                RankingUpdateOutcome::Conflict => {
+                    tracing::debug!("Ratings of game {} changed concurrently, retrying", game_over.game_id);
+                }
+            }
+        }
+
+        Err(MatchmakingServiceError::RankingUpdateConflict(game_over.game_id.clone()))
+    }
+
+    async fn find_applied_changes(&self, game_id: &str) -> Result<Vec<AppliedChange>, MatchmakingServiceError> {
+        self.ranking_repository.find_game_changes(game_id).await
+    }
+
+    async fn get_rating_history(&self, account_id: &str, ranked: bool) -> Result<HashMap<RatingCategory, RatingHistory>, MatchmakingServiceError> {
+        let mut points_by_category: HashMap<RatingCategory, Vec<_>> = HashMap::new();
+
+        for (category, point) in self.ranking_repository.find_rating_history(account_id, ranked).await? {
+            points_by_category.entry(category).or_default().push(point);
+        }
+
+        Ok(points_by_category
+            .into_iter()
+            .map(|(category, points)| (category, RatingHistory::from_points(points)))
+            .collect())
     }
 }
@@ -0,0 +1,120 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+use crate::error::MatchmakingServiceError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl GameResult {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            GameResult::Win => "win",
+            GameResult::Draw => "draw",
+            GameResult::Loss => "loss",
+        }
+    }
+
+    /// Score of the result in rating calculations
+    pub fn score(&self) -> f64 {
+        match self {
+            GameResult::Win => 1.0,
+            GameResult::Draw => 0.5,
+            GameResult::Loss => 0.0,
+        }
+    }
+}
+
+impl FromStr for GameResult {
+    type Err = MatchmakingServiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "win" => Ok(GameResult::Win),
+            "draw" => Ok(GameResult::Draw),
+            "loss" => Ok(GameResult::Loss),
+            _ => Err(MatchmakingServiceError::InvalidGameResult(s.into())),
+        }
+    }
+}
+
+/// A player's rating after one of their games.
+#[derive(Debug, Clone)]
+pub struct RatingHistoryPoint {
+    pub game_id: String,
+    pub rating: f64,
+    pub result: GameResult,
+    pub played_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RatingHistory {
+    /// Oldest first
+    pub points: Vec<RatingHistoryPoint>,
+    pub peak_rating: Option<f64>,
+    pub lowest_rating: Option<f64>,
+    /// Result of the latest games in a row and how many there were
+    pub current_streak: Option<(GameResult, u32)>,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl RatingHistory {
+    /// Builds the history of a category from its points, oldest first.
+    pub fn from_points(points: Vec<RatingHistoryPoint>) -> Self {
+        let mut history = RatingHistory::default();
+
+        for point in &points {
+            history.peak_rating = Some(history.peak_rating.map_or(point.rating, |peak| peak.max(point.rating)));
+            history.lowest_rating = Some(history.lowest_rating.map_or(point.rating, |lowest| lowest.min(point.rating)));
+
+            match point.result {
+                GameResult::Win => history.wins += 1,
+                GameResult::Draw => history.draws += 1,
+                GameResult::Loss => history.losses += 1,
+            }
+
+            history.current_streak = match history.current_streak {
+                Some((result, length)) if result == point.result => Some((result, length + 1)),
+                _ => Some((point.result, 1)),
+            };
+        }
+
+        history.points = points;
+        history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GameResult, RatingHistory, RatingHistoryPoint};
+
+    #[test]
+    fn test_rating_history_from_points() {
+        let points = [(1010.0, GameResult::Win), (995.0, GameResult::Loss), (995.0, GameResult::Draw), (1012.0, GameResult::Win), (1030.0, GameResult::Win)]
+            .into_iter()
+            .map(|(rating, result)| RatingHistoryPoint {
+                game_id: uuid::Uuid::new_v4().to_string(),
+                rating,
+                result,
+                played_at: chrono::Utc::now(),
+            })
+            .collect();
+
+        let history = RatingHistory::from_points(points);
+
+        assert_eq!(history.points.len(), 5);
+        assert_eq!(history.peak_rating, Some(1030.0));
+        assert_eq!(history.lowest_rating, Some(995.0));
+        assert_eq!(history.current_streak, Some((GameResult::Win, 2)));
+        assert_eq!((history.wins, history.draws, history.losses), (3, 1, 1));
+
+        assert_eq!(RatingHistory::from_points(Vec::new()).current_streak, None);
+    }
+}
@@ -0,0 +1,240 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use shared::primitives::RatingCategory;
+use uuid::Uuid;
+
+use crate::{config::SeasonConfig, error::MatchmakingServiceError, repositories::season_repository::SeasonRepository};
+
+#[derive(Debug, Clone)]
+pub struct Season {
+    pub id: String,
+    pub name: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Set once the season was closed and its results were stored
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl Season {
+    /// Whether ranked games can be played in the season at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.closed_at.is_none() && self.starts_at <= now && now < self.ends_at
+    }
+}
+
+/// How ratings of the previous season carry over into a new one.
+pub struct SoftReset {
+    /// Share of the distance to the mean rating of the category that is removed
+    pub factor: f64,
+    pub min_deviation: f64,
+}
+
+/// A player's rating in a category at the end of a season.
+pub struct SeasonStanding {
+    pub account_id: String,
+    pub category: RatingCategory,
+    pub rating: f64,
+    pub deviation: f64,
+    /// Ranked games played in the category during the season
+    pub games: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeasonReward {
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+    Diamond,
+    Master,
+}
+
+impl SeasonReward {
+    /// Lowest final rating of each reward, best first
+    const TIERS: [(f64, SeasonReward); 5] = [
+        (1800.0, SeasonReward::Master),
+        (1600.0, SeasonReward::Diamond),
+        (1400.0, SeasonReward::Platinum),
+        (1200.0, SeasonReward::Gold),
+        (1000.0, SeasonReward::Silver),
+    ];
+
+    pub fn for_rating(rating: f64) -> Self {
+        Self::TIERS
+            .into_iter()
+            .find(|(min_rating, _)| rating >= *min_rating)
+            .map_or(SeasonReward::Bronze, |(_, reward)| reward)
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            SeasonReward::Bronze => "bronze",
+            SeasonReward::Silver => "silver",
+            SeasonReward::Gold => "gold",
+            SeasonReward::Platinum => "platinum",
+            SeasonReward::Diamond => "diamond",
+            SeasonReward::Master => "master",
+        }
+    }
+}
+
+/// End-of-season snapshot of a player's rating in a category.
+pub struct SeasonResult {
+    pub account_id: String,
+    pub category: RatingCategory,
+    pub rating: f64,
+    pub deviation: f64,
+    pub games: i64,
+    /// 1-based rank in the category, `None` if the player didn't play enough games
+    pub rank: Option<i32>,
+    pub reward: Option<SeasonReward>,
+}
+
+/// Ranks the players of every category who played at least `min_games`, and rewards them by
+/// their final rating.
+pub fn season_results(standings: Vec<SeasonStanding>, min_games: i64) -> Vec<SeasonResult> {
+    let mut by_category: HashMap<RatingCategory, Vec<SeasonStanding>> = HashMap::new();
+
+    for standing in standings {
+        by_category.entry(standing.category).or_default().push(standing);
+    }
+
+    by_category
+        .into_values()
+        .flat_map(|mut standings| {
+            standings.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+
+            let mut rank = 0;
+
+            standings.into_iter().map(move |standing| {
+                let eligible = standing.games >= min_games;
+
+                if eligible {
+                    rank += 1;
+                }
+
+                SeasonResult {
+                    rank: eligible.then_some(rank),
+                    reward: eligible.then(|| SeasonReward::for_rating(standing.rating)),
+                    account_id: standing.account_id,
+                    category: standing.category,
+                    rating: standing.rating,
+                    deviation: standing.deviation,
+                    games: standing.games,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Ranked ratings are kept per season. Opening a season soft resets the ratings of the previous
+/// one, closing it stores every player's final rating and reward.
+pub struct SeasonService {
+    config: SeasonConfig,
+    season_repository: Arc<dyn SeasonRepository>,
+}
+
+impl SeasonService {
+    pub fn new(
+        config: SeasonConfig,
+        season_repository: Arc<dyn SeasonRepository>,
+    ) -> Self {
+        Self { config, season_repository }
+    }
+
+    pub async fn get_active_season(&self) -> Result<Option<Season>, MatchmakingServiceError> {
+        Ok(self.season_repository
+            .find_open_season()
+            .await?
+            .filter(|season| season.is_active(Utc::now())))
+    }
+
+    pub async fn open_season(
+        &self,
+        name: String,
+        starts_at: Option<DateTime<Utc>>,
+        ends_at: DateTime<Utc>,
+    ) -> Result<Season, MatchmakingServiceError> {
+        if let Some(season) = self.season_repository.find_open_season().await? {
+            return Err(MatchmakingServiceError::SeasonError(
+                format!("Season {} has to be closed first", season.name)
+            ));
+        }
+
+        let season = Season {
+            id: Uuid::new_v4().to_string(),
+            name,
+            starts_at: starts_at.unwrap_or_else(Utc::now),
+            ends_at,
+            closed_at: None,
+        };
+
+        if season.ends_at <= season.starts_at {
+            return Err(MatchmakingServiceError::SeasonError("A season has to end after it starts".into()));
+        }
+
+        self.season_repository.insert_season(&season, &SoftReset {
+            factor: self.config.soft_reset_factor.clamp(0.0, 1.0),
+            min_deviation: self.config.reset_deviation,
+        }).await?;
+
+        tracing::info!("Opened season {} ({})", season.name, season.id);
+
+        Ok(season)
+    }
+
+    /// Closes the open season and stores its results.
+    pub async fn close_season(&self) -> Result<(Season, Vec<SeasonResult>), MatchmakingServiceError> {
+        let Some(mut season) = self.season_repository.find_open_season().await? else {
+            return Err(MatchmakingServiceError::SeasonError("No season is open".into()));
+        };
+
+        let standings = self.season_repository.find_season_standings(&season.id).await?;
+        let results = season_results(standings, self.config.reward_min_games);
+        let closed_at = Utc::now();
+
+        if !self.season_repository.close_season(&season.id, closed_at, &results).await? {
+            return Err(MatchmakingServiceError::SeasonError(format!("Season {} is already closed", season.name)));
+        }
+
+        season.closed_at = Some(closed_at);
+
+        tracing::info!("Closed season {} with {} results", season.name, results.len());
+
+        Ok((season, results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::primitives::RatingCategory;
+
+    use super::{season_results, SeasonReward, SeasonStanding};
+
+    fn standing(account_id: &str, category: RatingCategory, rating: f64, games: i64) -> SeasonStanding {
+        SeasonStanding { account_id: account_id.into(), category, rating, deviation: 60.0, games }
+    }
+
+    #[test]
+    fn test_season_results_rank_players_with_enough_games() {
+        let results = season_results(vec![
+            standing("a", RatingCategory::Blitz, 1250.0, 12),
+            standing("b", RatingCategory::Blitz, 1900.0, 3),
+            standing("c", RatingCategory::Blitz, 1650.0, 10),
+            standing("a", RatingCategory::Bullet, 900.0, 40),
+        ], 10);
+
+        let result = |account_id: &str, category| results
+            .iter()
+            .find(|result| result.account_id == account_id && result.category == category)
+            .map(|result| (result.rank, result.reward))
+            .unwrap();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(result("c", RatingCategory::Blitz), (Some(1), Some(SeasonReward::Diamond)));
+        assert_eq!(result("a", RatingCategory::Blitz), (Some(2), Some(SeasonReward::Gold)));
+        assert_eq!(result("b", RatingCategory::Blitz), (None, None));
+        assert_eq!(result("a", RatingCategory::Bullet), (Some(1), Some(SeasonReward::Bronze)));
+    }
+}
@@ -1,7 +1,29 @@
-use shared::{events::{MatchmakingEvent, PendingGameReadyEvent, PendingGameTimeoutEvent}};
+use async_nats::jetstream;
+use shared::{events::{EloChangeEvent, MatchmakingEvent, PendingGameReadyEvent, PendingGameTimeoutEvent, PlayerStatusChangedEvent}};
 
 use crate::{error::MatchmakingServiceError, state::state::AppState};
 
+/// Stream of every `chessmart.matchmaking.>` subject, consumed by the gateway.
+pub const MATCHMAKING_STREAM_NAME: &str = "matchmaking-publisher";
+
+/// Core NATS subject every matchmaking replica reloads its queue pools on, outside of the stream
+/// since only running replicas need it.
+pub const QUEUE_POOLS_RELOAD_SUBJECT: &str = "chessmart.matchmaking-admin.reload-queue-pools";
+
+pub async fn ensure_stream(state: &AppState) -> Result<(), MatchmakingServiceError> {
+    state
+        .jetstream
+        .get_or_create_stream(jetstream::stream::Config {
+            name: MATCHMAKING_STREAM_NAME.to_string(),
+            subjects: vec!["chessmart.matchmaking.>".into()],
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| MatchmakingServiceError::StreamError(e.to_string()))?;
+
+    Ok(())
+}
+
 pub async fn emit_pending_game_ready(
     state: &AppState,
     payload: PendingGameReadyEvent,
@@ -37,3 +59,59 @@ pub async fn emit_pending_game_timeout(
 
     Ok(())
 }
+
+pub async fn emit_elo_change(
+    state: &AppState,
+    payload: EloChangeEvent,
+) -> Result<(), MatchmakingServiceError> {
+    let event = MatchmakingEvent::EloChange(payload.clone());
+    let subject = event.subject();
+
+    let payload = serde_json::to_vec(&event).unwrap();
+
+    state
+        .jetstream
+        .publish(subject.to_string(), payload.into())
+        .await
+        .map_err(|e| MatchmakingServiceError::StreamError(e.to_string()))?;
+
+    Ok(())
+}
+
+pub async fn emit_player_status_changed(
+    state: &AppState,
+    payload: PlayerStatusChangedEvent,
+) -> Result<(), MatchmakingServiceError> {
+    let event = MatchmakingEvent::PlayerStatusChanged(payload.clone());
+    let subject = event.subject();
+
+    let payload = serde_json::to_vec(&event).unwrap();
+
+    state
+        .jetstream
+        .publish(subject.to_string(), payload.into())
+        .await
+        .map_err(|e| MatchmakingServiceError::StreamError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Asks every matchmaking replica to reload its queue pools.
+pub async fn emit_queue_pools_reload(
+    state: &AppState,
+) -> Result<(), MatchmakingServiceError> {
+    let client = state.jetstream.client();
+
+    client
+        .publish(QUEUE_POOLS_RELOAD_SUBJECT, Vec::new().into())
+        .await
+        .map_err(|e| MatchmakingServiceError::StreamError(e.to_string()))?;
+
+    // Published before the admin request is answered
+    client
+        .flush()
+        .await
+        .map_err(|e| MatchmakingServiceError::StreamError(e.to_string()))?;
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+/// Width of the rating bands wait times are recorded in
+pub const RATING_BAND_WIDTH: u16 = 200;
+
+/// Rating band of a player, matching the bands `create-pending-game.lua` records wait times in.
+pub fn rating_band(mmr: u16) -> u16 {
+    mmr / RATING_BAND_WIDTH
+}
+
+/// Bucket of a wait time. Bucket `b` holds waits from `2^b - 1` up to `2^(b+1) - 1` seconds, the
+/// same buckets `create-pending-game.lua` records wait times in.
+pub fn wait_time_bucket(waited_ms: u64) -> u8 {
+    (waited_ms as f64 / 1000.0 + 1.0).log2().floor() as u8
+}
+
+/// Seconds waited at the lower and upper bounds of a bucket.
+fn bucket_bounds(bucket: u8) -> (f64, f64) {
+    (2f64.powi(bucket as i32) - 1.0, 2f64.powi(bucket as i32 + 1) - 1.0)
+}
+
+/// Number of matches per wait time bucket.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WaitTimeHistogram {
+    pub counts: BTreeMap<u8, u64>,
+}
+
+impl WaitTimeHistogram {
+    pub fn add(&mut self, bucket: u8, count: u64) {
+        *self.counts.entry(bucket).or_insert(0) += count;
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Wait in seconds that `percentile` (0 to 1) of the matches didn't exceed, spreading the
+    /// matches of a bucket evenly over it. `None` without any recorded match.
+    pub fn percentile(&self, percentile: f64) -> Option<u32> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+
+        let target = percentile.clamp(0.0, 1.0) * total as f64;
+        let mut seen = 0.0;
+
+        for (bucket, count) in &self.counts {
+            let count = *count as f64;
+
+            if seen + count >= target {
+                let (lower, upper) = bucket_bounds(*bucket);
+                let within = if count > 0.0 { (target - seen) / count } else { 0.0 };
+
+                return Some((lower + (upper - lower) * within).round() as u32);
+            }
+
+            seen += count;
+        }
+
+        self.counts.keys().last().map(|bucket| bucket_bounds(*bucket).1 as u32)
+    }
+
+    /// Estimated wait in seconds, the median of the recorded waits.
+    pub fn estimated_wait_seconds(&self) -> Option<u32> {
+        self.percentile(0.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{wait_time_bucket, WaitTimeHistogram};
+
+    #[test]
+    fn test_wait_time_histogram() {
+        assert_eq!(wait_time_bucket(0), 0);
+        assert_eq!(wait_time_bucket(999), 0);
+        assert_eq!(wait_time_bucket(1_000), 1);
+        assert_eq!(wait_time_bucket(6_999), 2);
+        assert_eq!(wait_time_bucket(7_000), 3);
+
+        let mut histogram = WaitTimeHistogram::default();
+        assert_eq!(histogram.estimated_wait_seconds(), None);
+
+        // Waits of 3 to 7 seconds
+        histogram.add(2, 4);
+        assert_eq!(histogram.estimated_wait_seconds(), Some(5));
+
+        // Waits of 15 to 31 seconds
+        histogram.add(4, 4);
+        assert_eq!(histogram.estimated_wait_seconds(), Some(7));
+        assert_eq!(histogram.percentile(1.0), Some(31));
+        assert_eq!(histogram.percentile(0.75), Some(23));
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use tracing::error;
+
+use crate::{services::leaderboard_service::LeaderboardService, state::state::AppState};
+
+/// Periodically rebuilds the leaderboards from Postgres, to repair any drift of the Redis sorted
+/// sets and drop players who stopped playing.
+pub struct LeaderboardRebuilder {
+    state: AppState,
+    leaderboard_service: Arc<LeaderboardService>,
+    /// Identifies this replica as the holder of the rebuild lock
+    replica_id: String,
+}
+
+impl LeaderboardRebuilder {
+    pub fn new(
+        state: AppState,
+        leaderboard_service: Arc<LeaderboardService>,
+    ) -> Self {
+        Self {
+            state,
+            leaderboard_service,
+            replica_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    pub async fn run(self) {
+        let interval = std::time::Duration::from_secs(self.state.config.leaderboard.rebuild_interval_seconds);
+
+        loop {
+            // The lock outlives the rebuild until the next one is due, so other replicas skip it
+            match self.leaderboard_service.try_lock_rebuild(&self.replica_id, interval.as_millis() as u64).await {
+                Ok(true) => {
+                    if let Err(e) = self.leaderboard_service.rebuild_leaderboards().await {
+                        error!("Failed to rebuild leaderboards: {e}");
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => error!("Failed to lock leaderboard rebuild: {e}"),
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
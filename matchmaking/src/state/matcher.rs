@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use tracing::error;
+
+use crate::{error::MatchmakingServiceError, repositories::matchmaking_queue_repository::QueueType, services::{matchmaking_queue_service::MatchmakingQueueService, pending_game_service::PendingGameService}, state::state::AppState};
+
+/// Periodically matches the players of every queue into pending games, and times out the
+/// pending games players didn't accept in time.
+pub struct Matcher {
+    state: AppState,
+    matchmaking_queue_service: Arc<MatchmakingQueueService>,
+    pending_game_service: Arc<PendingGameService>,
+    /// Identifies this replica as the holder of a queue lock
+    replica_id: String,
+}
+
+impl Matcher {
+    pub fn new(
+        state: AppState,
+        matchmaking_queue_service: Arc<MatchmakingQueueService>,
+        pending_game_service: Arc<PendingGameService>,
+    ) -> Self {
+        Self {
+            state,
+            matchmaking_queue_service,
+            pending_game_service,
+            replica_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    pub async fn run(self) {
+        println!("The matcher started.");
+
+        let tick = std::time::Duration::from_millis(self.state.config.matcher.tick_ms);
+
+        loop {
+            for queue_type in self.matchmaking_queue_service.queue_types() {
+                if let Err(e) = self.match_queue(&queue_type).await {
+                    error!(
+                        "Failed to match {} {} queue: {e}",
+                        if queue_type.ranked { "ranked" } else { "normal" },
+                        queue_type.game_type.to_str()
+                    );
+                }
+            }
+
+            if let Err(e) = self.pending_game_service.time_out_pending_games().await {
+                error!("Failed to time out pending games: {e}");
+            }
+
+            tokio::time::sleep(tick).await;
+        }
+    }
+
+    async fn match_queue(&self, queue_type: &QueueType) -> Result<(), MatchmakingServiceError> {
+        let matcher_config = &self.state.config.matcher;
+
+        // The lock is held for the whole run, however many batches it takes
+        if !self.matchmaking_queue_service.try_lock_queue(queue_type, &self.replica_id, matcher_config.queue_lock_lease_ms).await? {
+            return Ok(());
+        }
+
+        let result = self.pending_game_service.create_pending_games(queue_type, &self.replica_id).await;
+
+        // The lock then outlives the run until the next tick, so other replicas skip the queue
+        self.matchmaking_queue_service.extend_queue_lock(queue_type, &self.replica_id, matcher_config.tick_ms).await?;
+
+        result.map(|_| ())
+    }
+}
@@ -1 +1,4 @@
+pub mod matcher;
 pub mod state;
+pub mod leaderboard_rebuilder;
+pub mod player_status_reconciler;
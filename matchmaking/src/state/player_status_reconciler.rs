@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+use crate::{services::player_status_service::PlayerStatusService, state::state::AppState};
+
+/// Periodically frees players stuck `playing` a game that is gone, whose result never arrived.
+pub struct PlayerStatusReconciler {
+    state: AppState,
+    player_status_service: Arc<PlayerStatusService>,
+    /// Identifies this replica as the holder of the reconcile lock
+    replica_id: String,
+}
+
+impl PlayerStatusReconciler {
+    pub fn new(
+        state: AppState,
+        player_status_service: Arc<PlayerStatusService>,
+    ) -> Self {
+        Self {
+            state,
+            player_status_service,
+            replica_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    pub async fn run(self) {
+        let interval = std::time::Duration::from_secs(self.state.config.player_status.reconcile_interval_seconds);
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            // The lock outlives the run until the next one is due, so other replicas skip it
+            match self.player_status_service.try_lock_reconcile(&self.replica_id, interval.as_millis() as u64).await {
+                Ok(true) => match self.player_status_service.reconcile_player_statuses().await {
+                    Ok(0) => {}
+                    Ok(freed) => info!("Freed {freed} players from games that are gone"),
+                    Err(e) => error!("Failed to reconcile player statuses: {e}"),
+                },
+                Ok(false) => {}
+                Err(e) => error!("Failed to lock player status reconciliation: {e}"),
+            }
+        }
+    }
+}
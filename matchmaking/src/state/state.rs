@@ -1,24 +1,27 @@
 use shared::error::ChessmartApiError;
 
-use crate::{client::{database::{Database, PostgresDB}, nats::{NatsDB, NatsJetstreamContext}, redis::{RedisClient, RedisDB}}, config::{ApiConfig, DatabaseConfig, NatsConfig, RedisConfig, ServerConfig, TracingConfig}};
+use crate::{client::{database::{Database, PostgresDB}, game::{GameClient, GameGrpcClient}, nats::{NatsDB, NatsJetstreamContext}, redis::{RedisClient, RedisDB}}, config::{ApiConfig, DatabaseConfig, GameServiceConfig, LeaderboardConfig, MatcherConfig, NatsConfig, PlayerStatusConfig, RedisConfig, SeasonConfig, ServerConfig, TracingConfig}};
 
+#[derive(Clone)]
 pub struct AppState<DB: Database = PostgresDB> {
     pub db: DB,
     pub config: ApiConfig,
     pub redis: RedisClient,
     pub jetstream: NatsJetstreamContext,
+    pub game_client: GameGrpcClient,
 }
 
 impl<DB> AppState<DB>
 where
     DB: Database,
 {
-    pub fn new(db: DB, config: ApiConfig, redis: RedisClient, jetstream: NatsJetstreamContext) -> Self {
+    pub fn new(db: DB, config: ApiConfig, redis: RedisClient, jetstream: NatsJetstreamContext, game_client: GameGrpcClient) -> Self {
         Self {
             db,
             config,
             redis,
             jetstream,
+            game_client,
         }
     }
 }
@@ -30,6 +33,11 @@ pub struct AppStateBuilder {
     tracing_config: Option<TracingConfig>,
     redis_config: Option<RedisConfig>,
     nats_config: Option<NatsConfig>,
+    matcher_config: Option<MatcherConfig>,
+    game_service_config: Option<GameServiceConfig>,
+    leaderboard_config: Option<LeaderboardConfig>,
+    season_config: Option<SeasonConfig>,
+    player_status_config: Option<PlayerStatusConfig>,
 }
 
 impl AppStateBuilder {
@@ -40,6 +48,11 @@ impl AppStateBuilder {
             tracing_config: None,
             redis_config: None,
             nats_config: None,
+            matcher_config: None,
+            game_service_config: None,
+            leaderboard_config: None,
+            season_config: None,
+            player_status_config: None,
         }
     }
 
@@ -58,6 +71,31 @@ impl AppStateBuilder {
         self
     }
 
+    pub fn with_matcher(mut self, matcher_config: Option<MatcherConfig>) -> Self {
+        self.matcher_config = matcher_config;
+        self
+    }
+
+    pub fn with_game_service(mut self, game_service_config: Option<GameServiceConfig>) -> Self {
+        self.game_service_config = game_service_config;
+        self
+    }
+
+    pub fn with_leaderboard(mut self, leaderboard_config: Option<LeaderboardConfig>) -> Self {
+        self.leaderboard_config = leaderboard_config;
+        self
+    }
+
+    pub fn with_season(mut self, season_config: Option<SeasonConfig>) -> Self {
+        self.season_config = season_config;
+        self
+    }
+
+    pub fn with_player_status(mut self, player_status_config: Option<PlayerStatusConfig>) -> Self {
+        self.player_status_config = player_status_config;
+        self
+    }
+
     pub fn with_server(mut self, server_config: Option<ServerConfig>) -> Self {
         self.server_config = server_config;
         self
@@ -79,6 +117,9 @@ impl AppStateBuilder {
         let nats_config = self.nats_config.expect("nats-config not set");
         let jetstream = NatsDB::new(&nats_config).await?;
 
+        let game_service_config = self.game_service_config.unwrap_or_default();
+        let game_client = GameClient::connect(&game_service_config)?;
+
         Ok(AppState::new(
             db,
             ApiConfig::new(
@@ -87,9 +128,15 @@ impl AppStateBuilder {
                 self.tracing_config,
                 redis_config,
                 nats_config,
+                self.matcher_config.unwrap_or_default(),
+                game_service_config,
+                self.leaderboard_config.unwrap_or_default(),
+                self.season_config.unwrap_or_default(),
+                self.player_status_config.unwrap_or_default(),
             ),
             redis,
             jetstream,
+            game_client,
         ))
     }
 }
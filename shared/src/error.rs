@@ -14,6 +14,9 @@ pub enum ChessmartApiError {
     #[error("{0}")]
     UnknownGameTypeError(String),
 
+    #[error("Unknown rating category {0}")]
+    UnknownRatingCategoryError(String),
+
     #[error("{0}")]
     GameNotFoundError(String),
 
@@ -5,6 +5,14 @@ pub struct GameStartEvent {
     pub account_id_0: String,
     pub account_id_1: String,
     pub game_id: String,
+    #[serde(default)]
+    pub game_type: String,
+    #[serde(default)]
+    pub ranked: bool,
+    #[serde(default)]
+    pub white_account_id: String,
+    #[serde(default)]
+    pub black_account_id: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -27,6 +35,11 @@ pub struct GameOverEvent {
     pub game_id: String,
     pub game_type: String,
     pub metadata: String,
+    #[serde(default)]
+    pub ranked: bool,
+    /// Player who ran out of time without making a move
+    #[serde(default)]
+    pub abandoned_account_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -35,11 +48,45 @@ pub struct ClockState {
     pub b: u32, // Black time in milliseconds
 }
 
+/// Emitted whenever a challenge is created or changes status.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChallengeUpdateEvent {
+    pub challenge_id: String,
+    pub challenger_account_id: String,
+    /// Unset for open challenges shared as an invite link
+    pub challenged_account_id: Option<String>,
+    /// Account that accepted the challenge
+    pub accepted_by: Option<String>,
+    pub game_type: String,
+    pub color: String,
+    pub ranked: bool,
+    pub status: String,
+    pub expires_at: i64,
+    pub game_id: Option<String>,
+}
+
+/// Emitted when a rematch is offered after a game and once it is accepted.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RematchUpdateEvent {
+    /// Game that ended
+    pub game_id: String,
+    pub account_id_0: String,
+    pub account_id_1: String,
+    pub offered_by: String,
+    /// offered or accepted
+    pub status: String,
+    pub expires_at: i64,
+    /// Game created once the rematch is accepted
+    pub new_game_id: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum GameEvent {
     GameStart(GameStartEvent),
     GameStateUpdate(GameStateUpdateEvent),
     GameOver(GameOverEvent),
+    ChallengeUpdate(ChallengeUpdateEvent),
+    RematchUpdate(RematchUpdateEvent),
 }
 
 impl GameEvent {
@@ -48,6 +95,8 @@ impl GameEvent {
             GameEvent::GameStart(_) => "chessmart.game.game-start",
             GameEvent::GameStateUpdate(_) => "chessmart.game.game-state-update",
             GameEvent::GameOver(_) => "chessmart.game.game-over",
+            GameEvent::ChallengeUpdate(_) => "chessmart.game.challenge-update",
+            GameEvent::RematchUpdate(_) => "chessmart.game.rematch-update",
         }
     }
 }
@@ -74,11 +123,23 @@ pub struct EloChangeEvent {
     pub ranked: bool,
 }
 
+/// A player moved from one matchmaking status (`undefined`, `searching`, `pending`, `playing`)
+/// to another.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlayerStatusChangedEvent {
+    pub account_id: String,
+    pub previous_status: String,
+    pub status: String,
+    /// Game the player is playing, or stopped playing
+    pub game_id: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum MatchmakingEvent {
     PendingGameReady(PendingGameReadyEvent),
     PendingGameTimeout(PendingGameTimeoutEvent),
     EloChange(EloChangeEvent),
+    PlayerStatusChanged(PlayerStatusChangedEvent),
 }
 
 impl MatchmakingEvent {
@@ -87,6 +148,7 @@ impl MatchmakingEvent {
             MatchmakingEvent::PendingGameReady(_) => "chessmart.matchmaking.pending-game-ready",
             MatchmakingEvent::PendingGameTimeout(_) => "chessmart.matchmaking.pending-game-timeout",
             MatchmakingEvent::EloChange(_) => "chessmart.matchmaking.elo-change",
+            MatchmakingEvent::PlayerStatusChanged(_) => "chessmart.matchmaking.player-status-changed",
         }
     }
 }
@@ -10,6 +10,11 @@ pub struct CreateGameRequest {
     pub game_type: ::prost::alloc::string::String,
     #[prost(string, tag = "4")]
     pub metadata: ::prost::alloc::string::String,
+    #[prost(bool, tag = "5")]
+    pub ranked: bool,
+    /// Plays white when set (one of the two accounts), otherwise colors are assigned randomly
+    #[prost(string, optional, tag = "6")]
+    pub white_account_id: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -28,12 +33,24 @@ pub struct MakeMoveRequest {
     pub game_id: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub r#move: ::prost::alloc::string::String,
+    /// Client generated id, a retried move with the same id is applied only once
+    #[prost(string, optional, tag = "4")]
+    pub client_move_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Seq of the game state the move was made on, the move is rejected if the game has moved on
+    #[prost(uint64, optional, tag = "5")]
+    pub expected_seq: ::core::option::Option<u64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MakeMoveResponse {
     #[prost(string, tag = "1")]
     pub game_repr: ::prost::alloc::string::String,
+    /// Seq of the game once the move was applied
+    #[prost(uint64, tag = "2")]
+    pub seq: u64,
+    /// Set when the move had already been applied under the same client_move_id
+    #[prost(bool, tag = "3")]
+    pub duplicate: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -70,6 +87,166 @@ pub struct ResignResponse {
     #[prost(string, tag = "1")]
     pub game_repr: ::prost::alloc::string::String,
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMyTurnGamesRequest {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMyTurnGamesResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub game_reprs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Challenge {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub challenger_account_id: ::prost::alloc::string::String,
+    /// Unset for open challenges shared as an invite link
+    #[prost(string, optional, tag = "3")]
+    pub challenged_account_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, tag = "4")]
+    pub game_type: ::prost::alloc::string::String,
+    /// Color of the challenger: white, black or random
+    #[prost(string, tag = "5")]
+    pub color: ::prost::alloc::string::String,
+    #[prost(bool, tag = "6")]
+    pub ranked: bool,
+    /// pending, accepted, declined, cancelled or expired
+    #[prost(string, tag = "7")]
+    pub status: ::prost::alloc::string::String,
+    /// Unix timestamp (ms) after which a pending challenge expires
+    #[prost(int64, tag = "8")]
+    pub expires_at: i64,
+    #[prost(string, optional, tag = "9")]
+    pub game_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateChallengeRequest {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub challenged_account_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, tag = "3")]
+    pub game_type: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub color: ::prost::alloc::string::String,
+    #[prost(bool, tag = "5")]
+    pub ranked: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateChallengeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub challenge: ::core::option::Option<Challenge>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetChallengeRequest {
+    #[prost(string, tag = "1")]
+    pub challenge_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetChallengeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub challenge: ::core::option::Option<Challenge>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetChallengesRequest {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetChallengesResponse {
+    /// Pending challenges sent or received by the account
+    #[prost(message, repeated, tag = "1")]
+    pub challenges: ::prost::alloc::vec::Vec<Challenge>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AcceptChallengeRequest {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub challenge_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AcceptChallengeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub challenge: ::core::option::Option<Challenge>,
+    #[prost(string, tag = "2")]
+    pub game_repr: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeclineChallengeRequest {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub challenge_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeclineChallengeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub challenge: ::core::option::Option<Challenge>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelChallengeRequest {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub challenge_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelChallengeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub challenge: ::core::option::Option<Challenge>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OfferRematchRequest {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+    /// Game that just ended
+    #[prost(string, tag = "2")]
+    pub game_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OfferRematchResponse {
+    /// Unix timestamp (ms) after which the offer can no longer be accepted
+    #[prost(int64, tag = "1")]
+    pub expires_at: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AcceptRematchRequest {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+    /// Game that just ended
+    #[prost(string, tag = "2")]
+    pub game_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AcceptRematchResponse {
+    #[prost(string, tag = "1")]
+    pub game_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub game_repr: ::prost::alloc::string::String,
+}
 /// Generated client implementations.
 pub mod game_service_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -273,88 +450,376 @@ pub mod game_service_client {
             req.extensions_mut().insert(GrpcMethod::new("game.GameService", "Resign"));
             self.inner.unary(req, path, codec).await
         }
-    }
-}
-/// Generated server implementations.
-pub mod game_service_server {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with GameServiceServer.
-    #[async_trait]
-    pub trait GameService: Send + Sync + 'static {
-        async fn create_game(
-            &self,
-            request: tonic::Request<super::CreateGameRequest>,
+        pub async fn get_my_turn_games(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetMyTurnGamesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateGameResponse>,
+            tonic::Response<super::GetMyTurnGamesResponse>,
             tonic::Status,
-        >;
-        async fn get_game_state(
-            &self,
-            request: tonic::Request<super::GetGameStateRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/game.GameService/GetMyTurnGames",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("game.GameService", "GetMyTurnGames"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_challenge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateChallengeRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetGameStateResponse>,
+            tonic::Response<super::CreateChallengeResponse>,
             tonic::Status,
-        >;
-        async fn check_game_result(
-            &self,
-            request: tonic::Request<super::CheckGameResultRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/game.GameService/CreateChallenge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("game.GameService", "CreateChallenge"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_challenge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetChallengeRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CheckGameResultResponse>,
+            tonic::Response<super::GetChallengeResponse>,
             tonic::Status,
-        >;
-        async fn make_move(
-            &self,
-            request: tonic::Request<super::MakeMoveRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/game.GameService/GetChallenge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("game.GameService", "GetChallenge"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_challenges(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetChallengesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::MakeMoveResponse>,
+            tonic::Response<super::GetChallengesResponse>,
             tonic::Status,
-        >;
-        async fn resign(
-            &self,
-            request: tonic::Request<super::ResignRequest>,
-        ) -> std::result::Result<tonic::Response<super::ResignResponse>, tonic::Status>;
-    }
-    #[derive(Debug)]
-    pub struct GameServiceServer<T: GameService> {
-        inner: _Inner<T>,
-        accept_compression_encodings: EnabledCompressionEncodings,
-        send_compression_encodings: EnabledCompressionEncodings,
-        max_decoding_message_size: Option<usize>,
-        max_encoding_message_size: Option<usize>,
-    }
-    struct _Inner<T>(Arc<T>);
-    impl<T: GameService> GameServiceServer<T> {
-        pub fn new(inner: T) -> Self {
-            Self::from_arc(Arc::new(inner))
-        }
-        pub fn from_arc(inner: Arc<T>) -> Self {
-            let inner = _Inner(inner);
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-                max_decoding_message_size: None,
-                max_encoding_message_size: None,
-            }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> InterceptedService<Self, F>
-        where
-            F: tonic::service::Interceptor,
-        {
-            InterceptedService::new(Self::new(inner), interceptor)
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/game.GameService/GetChallenges",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("game.GameService", "GetChallenges"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Enable decompressing requests with the given encoding.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.accept_compression_encodings.enable(encoding);
-            self
+        pub async fn accept_challenge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AcceptChallengeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptChallengeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/game.GameService/AcceptChallenge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("game.GameService", "AcceptChallenge"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Compress responses with the given encoding, if the client supports it.
-        #[must_use]
+        pub async fn decline_challenge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeclineChallengeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeclineChallengeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/game.GameService/DeclineChallenge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("game.GameService", "DeclineChallenge"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn cancel_challenge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelChallengeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelChallengeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/game.GameService/CancelChallenge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("game.GameService", "CancelChallenge"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn offer_rematch(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OfferRematchRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OfferRematchResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/game.GameService/OfferRematch",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("game.GameService", "OfferRematch"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn accept_rematch(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AcceptRematchRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptRematchResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/game.GameService/AcceptRematch",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("game.GameService", "AcceptRematch"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod game_service_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with GameServiceServer.
+    #[async_trait]
+    pub trait GameService: Send + Sync + 'static {
+        async fn create_game(
+            &self,
+            request: tonic::Request<super::CreateGameRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateGameResponse>,
+            tonic::Status,
+        >;
+        async fn get_game_state(
+            &self,
+            request: tonic::Request<super::GetGameStateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetGameStateResponse>,
+            tonic::Status,
+        >;
+        async fn check_game_result(
+            &self,
+            request: tonic::Request<super::CheckGameResultRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CheckGameResultResponse>,
+            tonic::Status,
+        >;
+        async fn make_move(
+            &self,
+            request: tonic::Request<super::MakeMoveRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::MakeMoveResponse>,
+            tonic::Status,
+        >;
+        async fn resign(
+            &self,
+            request: tonic::Request<super::ResignRequest>,
+        ) -> std::result::Result<tonic::Response<super::ResignResponse>, tonic::Status>;
+        async fn get_my_turn_games(
+            &self,
+            request: tonic::Request<super::GetMyTurnGamesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetMyTurnGamesResponse>,
+            tonic::Status,
+        >;
+        async fn create_challenge(
+            &self,
+            request: tonic::Request<super::CreateChallengeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateChallengeResponse>,
+            tonic::Status,
+        >;
+        async fn get_challenge(
+            &self,
+            request: tonic::Request<super::GetChallengeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetChallengeResponse>,
+            tonic::Status,
+        >;
+        async fn get_challenges(
+            &self,
+            request: tonic::Request<super::GetChallengesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetChallengesResponse>,
+            tonic::Status,
+        >;
+        async fn accept_challenge(
+            &self,
+            request: tonic::Request<super::AcceptChallengeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptChallengeResponse>,
+            tonic::Status,
+        >;
+        async fn decline_challenge(
+            &self,
+            request: tonic::Request<super::DeclineChallengeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeclineChallengeResponse>,
+            tonic::Status,
+        >;
+        async fn cancel_challenge(
+            &self,
+            request: tonic::Request<super::CancelChallengeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelChallengeResponse>,
+            tonic::Status,
+        >;
+        async fn offer_rematch(
+            &self,
+            request: tonic::Request<super::OfferRematchRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OfferRematchResponse>,
+            tonic::Status,
+        >;
+        async fn accept_rematch(
+            &self,
+            request: tonic::Request<super::AcceptRematchRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptRematchResponse>,
+            tonic::Status,
+        >;
+    }
+    #[derive(Debug)]
+    pub struct GameServiceServer<T: GameService> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: GameService> GameServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
         pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
             self.send_compression_encodings.enable(encoding);
             self
@@ -624,6 +1089,420 @@ pub mod game_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/game.GameService/GetMyTurnGames" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetMyTurnGamesSvc<T: GameService>(pub Arc<T>);
+                    impl<
+                        T: GameService,
+                    > tonic::server::UnaryService<super::GetMyTurnGamesRequest>
+                    for GetMyTurnGamesSvc<T> {
+                        type Response = super::GetMyTurnGamesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetMyTurnGamesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GameService>::get_my_turn_games(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetMyTurnGamesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/game.GameService/CreateChallenge" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateChallengeSvc<T: GameService>(pub Arc<T>);
+                    impl<
+                        T: GameService,
+                    > tonic::server::UnaryService<super::CreateChallengeRequest>
+                    for CreateChallengeSvc<T> {
+                        type Response = super::CreateChallengeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateChallengeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GameService>::create_challenge(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateChallengeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/game.GameService/GetChallenge" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetChallengeSvc<T: GameService>(pub Arc<T>);
+                    impl<
+                        T: GameService,
+                    > tonic::server::UnaryService<super::GetChallengeRequest>
+                    for GetChallengeSvc<T> {
+                        type Response = super::GetChallengeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetChallengeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GameService>::get_challenge(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetChallengeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/game.GameService/GetChallenges" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetChallengesSvc<T: GameService>(pub Arc<T>);
+                    impl<
+                        T: GameService,
+                    > tonic::server::UnaryService<super::GetChallengesRequest>
+                    for GetChallengesSvc<T> {
+                        type Response = super::GetChallengesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetChallengesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GameService>::get_challenges(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetChallengesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/game.GameService/AcceptChallenge" => {
+                    #[allow(non_camel_case_types)]
+                    struct AcceptChallengeSvc<T: GameService>(pub Arc<T>);
+                    impl<
+                        T: GameService,
+                    > tonic::server::UnaryService<super::AcceptChallengeRequest>
+                    for AcceptChallengeSvc<T> {
+                        type Response = super::AcceptChallengeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AcceptChallengeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GameService>::accept_challenge(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AcceptChallengeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/game.GameService/DeclineChallenge" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeclineChallengeSvc<T: GameService>(pub Arc<T>);
+                    impl<
+                        T: GameService,
+                    > tonic::server::UnaryService<super::DeclineChallengeRequest>
+                    for DeclineChallengeSvc<T> {
+                        type Response = super::DeclineChallengeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeclineChallengeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GameService>::decline_challenge(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeclineChallengeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/game.GameService/CancelChallenge" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelChallengeSvc<T: GameService>(pub Arc<T>);
+                    impl<
+                        T: GameService,
+                    > tonic::server::UnaryService<super::CancelChallengeRequest>
+                    for CancelChallengeSvc<T> {
+                        type Response = super::CancelChallengeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelChallengeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GameService>::cancel_challenge(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CancelChallengeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/game.GameService/OfferRematch" => {
+                    #[allow(non_camel_case_types)]
+                    struct OfferRematchSvc<T: GameService>(pub Arc<T>);
+                    impl<
+                        T: GameService,
+                    > tonic::server::UnaryService<super::OfferRematchRequest>
+                    for OfferRematchSvc<T> {
+                        type Response = super::OfferRematchResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::OfferRematchRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GameService>::offer_rematch(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = OfferRematchSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/game.GameService/AcceptRematch" => {
+                    #[allow(non_camel_case_types)]
+                    struct AcceptRematchSvc<T: GameService>(pub Arc<T>);
+                    impl<
+                        T: GameService,
+                    > tonic::server::UnaryService<super::AcceptRematchRequest>
+                    for AcceptRematchSvc<T> {
+                        type Response = super::AcceptRematchResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AcceptRematchRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GameService>::accept_rematch(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AcceptRematchSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -1,13 +1,25 @@
 // This file is @generated by prost-build.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueuePool {
+    #[prost(string, tag = "1")]
+    pub game_type: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub ranked: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AddToQueueRequest {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
+    /// Single queue to join, used when no pools are given
     #[prost(string, tag = "2")]
     pub game_type: ::prost::alloc::string::String,
     #[prost(bool, tag = "3")]
     pub ranked: bool,
+    /// Queues to join at once
+    #[prost(message, repeated, tag = "4")]
+    pub pools: ::prost::alloc::vec::Vec<QueuePool>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -38,6 +50,18 @@ pub struct GetAccountStatusResponse {
     pub ranked: ::core::option::Option<bool>,
     #[prost(string, optional, tag = "4")]
     pub game_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Queues the player is searching in
+    #[prost(message, repeated, tag = "5")]
+    pub pools: ::prost::alloc::vec::Vec<QueuePool>,
+    /// Estimated wait in seconds of a searching player, from joining the queues to being matched
+    #[prost(uint32, optional, tag = "6")]
+    pub estimated_wait_seconds: ::core::option::Option<u32>,
+    /// Seconds until the player can join queues again after declining or abandoning games
+    #[prost(uint32, optional, tag = "7")]
+    pub queue_cooldown_seconds: ::core::option::Option<u32>,
+    /// Recent declines, pending game timeouts and abandons, decaying over time
+    #[prost(double, tag = "8")]
+    pub queue_penalty_score: f64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -52,6 +76,17 @@ pub struct AcceptPendingGameRequest {
 pub struct AcceptPendingGameResponse {}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeclinePendingGameRequest {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub pending_game_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeclinePendingGameResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetQueueSizesRequest {}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -60,6 +95,11 @@ pub struct QueueSize {
     pub normal: u32,
     #[prost(uint32, tag = "2")]
     pub ranked: u32,
+    /// Median wait in seconds of the players matched over the last hour, unset while nobody was
+    #[prost(uint32, optional, tag = "3")]
+    pub normal_estimated_wait_seconds: ::core::option::Option<u32>,
+    #[prost(uint32, optional, tag = "4")]
+    pub ranked_estimated_wait_seconds: ::core::option::Option<u32>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -70,6 +110,35 @@ pub struct GetQueueSizesResponse {
         QueueSize,
     >,
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueuePoolDefinition {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub time_control: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub variant: ::prost::alloc::string::String,
+    #[prost(bool, tag = "4")]
+    pub rated: bool,
+    #[prost(int32, tag = "5")]
+    pub base_mmr_range: i32,
+    #[prost(int32, tag = "6")]
+    pub mmr_range_increase_per_second: i32,
+    #[prost(int32, tag = "7")]
+    pub max_mmr_delta: i32,
+    #[prost(bool, tag = "8")]
+    pub enabled: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReloadQueuePoolsRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReloadQueuePoolsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub pools: ::prost::alloc::vec::Vec<QueuePoolDefinition>,
+}
 /// Generated client implementations.
 pub mod matchmaker_service_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -207,6 +276,33 @@ pub mod matchmaker_service_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        pub async fn decline_pending_game(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeclinePendingGameRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeclinePendingGameResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/matchmaker.MatchmakerService/DeclinePendingGame",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("matchmaker.MatchmakerService", "DeclinePendingGame"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn remove_from_queue(
             &mut self,
             request: impl tonic::IntoRequest<super::RemoveFromQueueRequest>,
@@ -290,6 +386,128 @@ pub mod matchmaker_service_client {
         }
     }
 }
+/// Generated client implementations.
+pub mod matchmaking_admin_service_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// Administration of the matchmaking queues, not exposed by the gateway
+    #[derive(Debug, Clone)]
+    pub struct MatchmakingAdminServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl MatchmakingAdminServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> MatchmakingAdminServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> MatchmakingAdminServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            MatchmakingAdminServiceClient::new(
+                InterceptedService::new(inner, interceptor),
+            )
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Reloads the queue pools from the matchmaking config on every replica, the current pools stay
+        /// if it's invalid. Returns the pools of the replica that served the request.
+        pub async fn reload_queue_pools(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReloadQueuePoolsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReloadQueuePoolsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/matchmaker.MatchmakingAdminService/ReloadQueuePools",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "matchmaker.MatchmakingAdminService",
+                        "ReloadQueuePools",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
 /// Generated server implementations.
 pub mod matchmaker_service_server {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -311,6 +529,13 @@ pub mod matchmaker_service_server {
             tonic::Response<super::AcceptPendingGameResponse>,
             tonic::Status,
         >;
+        async fn decline_pending_game(
+            &self,
+            request: tonic::Request<super::DeclinePendingGameRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeclinePendingGameResponse>,
+            tonic::Status,
+        >;
         async fn remove_from_queue(
             &self,
             request: tonic::Request<super::RemoveFromQueueRequest>,
@@ -509,6 +734,56 @@ pub mod matchmaker_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/matchmaker.MatchmakerService/DeclinePendingGame" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeclinePendingGameSvc<T: MatchmakerService>(pub Arc<T>);
+                    impl<
+                        T: MatchmakerService,
+                    > tonic::server::UnaryService<super::DeclinePendingGameRequest>
+                    for DeclinePendingGameSvc<T> {
+                        type Response = super::DeclinePendingGameResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeclinePendingGameRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as MatchmakerService>::decline_pending_game(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeclinePendingGameSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/matchmaker.MatchmakerService/RemoveFromQueue" => {
                     #[allow(non_camel_case_types)]
                     struct RemoveFromQueueSvc<T: MatchmakerService>(pub Arc<T>);
@@ -695,3 +970,193 @@ pub mod matchmaker_service_server {
         const NAME: &'static str = "matchmaker.MatchmakerService";
     }
 }
+/// Generated server implementations.
+pub mod matchmaking_admin_service_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with MatchmakingAdminServiceServer.
+    #[async_trait]
+    pub trait MatchmakingAdminService: Send + Sync + 'static {
+        /// Reloads the queue pools from the matchmaking config on every replica, the current pools stay
+        /// if it's invalid. Returns the pools of the replica that served the request.
+        async fn reload_queue_pools(
+            &self,
+            request: tonic::Request<super::ReloadQueuePoolsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReloadQueuePoolsResponse>,
+            tonic::Status,
+        >;
+    }
+    /// Administration of the matchmaking queues, not exposed by the gateway
+    #[derive(Debug)]
+    pub struct MatchmakingAdminServiceServer<T: MatchmakingAdminService> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: MatchmakingAdminService> MatchmakingAdminServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>>
+    for MatchmakingAdminServiceServer<T>
+    where
+        T: MatchmakingAdminService,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/matchmaker.MatchmakingAdminService/ReloadQueuePools" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReloadQueuePoolsSvc<T: MatchmakingAdminService>(pub Arc<T>);
+                    impl<
+                        T: MatchmakingAdminService,
+                    > tonic::server::UnaryService<super::ReloadQueuePoolsRequest>
+                    for ReloadQueuePoolsSvc<T> {
+                        type Response = super::ReloadQueuePoolsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReloadQueuePoolsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as MatchmakingAdminService>::reload_queue_pools(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ReloadQueuePoolsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        Ok(
+                            http::Response::builder()
+                                .status(200)
+                                .header("grpc-status", "12")
+                                .header("content-type", "application/grpc")
+                                .body(empty_body())
+                                .unwrap(),
+                        )
+                    })
+                }
+            }
+        }
+    }
+    impl<T: MatchmakingAdminService> Clone for MatchmakingAdminServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    impl<T: MatchmakingAdminService> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(Arc::clone(&self.0))
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: MatchmakingAdminService> tonic::server::NamedService
+    for MatchmakingAdminServiceServer<T> {
+        const NAME: &'static str = "matchmaker.MatchmakingAdminService";
+    }
+}
@@ -7,11 +7,168 @@ pub struct GetAccountRankingRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetAccountRankingResponse {
+pub struct PlayerRating {
     #[prost(float, tag = "1")]
-    pub ranked_mmr: f32,
+    pub rating: f32,
+    #[prost(float, tag = "2")]
+    pub deviation: f32,
+    #[prost(bool, tag = "3")]
+    pub provisional: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CategoryRatings {
+    #[prost(message, optional, tag = "1")]
+    pub ranked: ::core::option::Option<PlayerRating>,
+    #[prost(message, optional, tag = "2")]
+    pub normal: ::core::option::Option<PlayerRating>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAccountRankingResponse {
+    /// Keyed by rating category, e.g. "blitz"
+    #[prost(map = "string, message", tag = "7")]
+    pub ratings: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        CategoryRatings,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLeaderboardRequest {
+    #[prost(string, tag = "1")]
+    pub category: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub limit: u32,
+    /// Also returns the players ranked around this account
+    #[prost(string, optional, tag = "3")]
+    pub account_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LeaderboardEntry {
+    #[prost(uint64, tag = "1")]
+    pub rank: u64,
+    #[prost(string, tag = "2")]
+    pub account_id: ::prost::alloc::string::String,
+    #[prost(float, tag = "3")]
+    pub rating: f32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLeaderboardResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub top: ::prost::alloc::vec::Vec<LeaderboardEntry>,
+    #[prost(message, repeated, tag = "2")]
+    pub around_me: ::prost::alloc::vec::Vec<LeaderboardEntry>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRatingHistoryRequest {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub ranked: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RatingHistoryPoint {
+    #[prost(string, tag = "1")]
+    pub game_id: ::prost::alloc::string::String,
+    /// Rating after the game
     #[prost(float, tag = "2")]
-    pub normal_mmr: f32,
+    pub rating: f32,
+    /// win, draw or loss
+    #[prost(string, tag = "3")]
+    pub result: ::prost::alloc::string::String,
+    /// Unix timestamp (ms)
+    #[prost(int64, tag = "4")]
+    pub played_at: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RatingHistory {
+    /// Oldest first
+    #[prost(message, repeated, tag = "1")]
+    pub points: ::prost::alloc::vec::Vec<RatingHistoryPoint>,
+    #[prost(float, optional, tag = "2")]
+    pub peak_rating: ::core::option::Option<f32>,
+    #[prost(float, optional, tag = "3")]
+    pub lowest_rating: ::core::option::Option<f32>,
+    #[prost(string, optional, tag = "4")]
+    pub current_streak_result: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, tag = "5")]
+    pub current_streak_length: u32,
+    #[prost(uint32, tag = "6")]
+    pub wins: u32,
+    #[prost(uint32, tag = "7")]
+    pub draws: u32,
+    #[prost(uint32, tag = "8")]
+    pub losses: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRatingHistoryResponse {
+    /// Keyed by rating category, only the categories the player has played
+    #[prost(map = "string, message", tag = "1")]
+    pub histories: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        RatingHistory,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Season {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    /// Unix timestamps (ms)
+    #[prost(int64, tag = "3")]
+    pub starts_at: i64,
+    #[prost(int64, tag = "4")]
+    pub ends_at: i64,
+    #[prost(int64, optional, tag = "5")]
+    pub closed_at: ::core::option::Option<i64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetActiveSeasonRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetActiveSeasonResponse {
+    /// Unset while no season is running
+    #[prost(message, optional, tag = "1")]
+    pub season: ::core::option::Option<Season>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OpenSeasonRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// Unix timestamps (ms), the season starts right away without starts_at
+    #[prost(int64, optional, tag = "2")]
+    pub starts_at: ::core::option::Option<i64>,
+    #[prost(int64, tag = "3")]
+    pub ends_at: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OpenSeasonResponse {
+    #[prost(message, optional, tag = "1")]
+    pub season: ::core::option::Option<Season>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CloseSeasonRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CloseSeasonResponse {
+    #[prost(message, optional, tag = "1")]
+    pub season: ::core::option::Option<Season>,
+    /// Number of stored end-of-season results
+    #[prost(uint32, tag = "2")]
+    pub results: u32,
 }
 /// Generated client implementations.
 pub mod ranking_service_client {
@@ -123,6 +280,222 @@ pub mod ranking_service_client {
                 .insert(GrpcMethod::new("ranking.RankingService", "GetAccountRanking"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_leaderboard(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetLeaderboardRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetLeaderboardResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ranking.RankingService/GetLeaderboard",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ranking.RankingService", "GetLeaderboard"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_rating_history(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetRatingHistoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetRatingHistoryResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ranking.RankingService/GetRatingHistory",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ranking.RankingService", "GetRatingHistory"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// The season ranked games are played in, if any
+        pub async fn get_active_season(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetActiveSeasonRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetActiveSeasonResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ranking.RankingService/GetActiveSeason",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ranking.RankingService", "GetActiveSeason"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated client implementations.
+pub mod season_admin_service_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// Administration of ranked seasons, not exposed by the gateway
+    #[derive(Debug, Clone)]
+    pub struct SeasonAdminServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl SeasonAdminServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> SeasonAdminServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> SeasonAdminServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            SeasonAdminServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Opens a season, soft resetting the ranked ratings of the previous one
+        pub async fn open_season(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OpenSeasonRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpenSeasonResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ranking.SeasonAdminService/OpenSeason",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ranking.SeasonAdminService", "OpenSeason"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Closes the open season, storing every player's final ratings and rewards
+        pub async fn close_season(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CloseSeasonRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CloseSeasonResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ranking.SeasonAdminService/CloseSeason",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ranking.SeasonAdminService", "CloseSeason"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -139,6 +512,28 @@ pub mod ranking_service_server {
             tonic::Response<super::GetAccountRankingResponse>,
             tonic::Status,
         >;
+        async fn get_leaderboard(
+            &self,
+            request: tonic::Request<super::GetLeaderboardRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetLeaderboardResponse>,
+            tonic::Status,
+        >;
+        async fn get_rating_history(
+            &self,
+            request: tonic::Request<super::GetRatingHistoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetRatingHistoryResponse>,
+            tonic::Status,
+        >;
+        /// The season ranked games are played in, if any
+        async fn get_active_season(
+            &self,
+            request: tonic::Request<super::GetActiveSeasonRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetActiveSeasonResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct RankingServiceServer<T: RankingService> {
@@ -266,13 +661,154 @@ pub mod ranking_service_server {
                     };
                     Box::pin(fut)
                 }
-                _ => {
-                    Box::pin(async move {
-                        Ok(
-                            http::Response::builder()
-                                .status(200)
-                                .header("grpc-status", "12")
-                                .header("content-type", "application/grpc")
+                "/ranking.RankingService/GetLeaderboard" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetLeaderboardSvc<T: RankingService>(pub Arc<T>);
+                    impl<
+                        T: RankingService,
+                    > tonic::server::UnaryService<super::GetLeaderboardRequest>
+                    for GetLeaderboardSvc<T> {
+                        type Response = super::GetLeaderboardResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetLeaderboardRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as RankingService>::get_leaderboard(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetLeaderboardSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ranking.RankingService/GetRatingHistory" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetRatingHistorySvc<T: RankingService>(pub Arc<T>);
+                    impl<
+                        T: RankingService,
+                    > tonic::server::UnaryService<super::GetRatingHistoryRequest>
+                    for GetRatingHistorySvc<T> {
+                        type Response = super::GetRatingHistoryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetRatingHistoryRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as RankingService>::get_rating_history(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetRatingHistorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ranking.RankingService/GetActiveSeason" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetActiveSeasonSvc<T: RankingService>(pub Arc<T>);
+                    impl<
+                        T: RankingService,
+                    > tonic::server::UnaryService<super::GetActiveSeasonRequest>
+                    for GetActiveSeasonSvc<T> {
+                        type Response = super::GetActiveSeasonResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetActiveSeasonRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as RankingService>::get_active_season(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetActiveSeasonSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        Ok(
+                            http::Response::builder()
+                                .status(200)
+                                .header("grpc-status", "12")
+                                .header("content-type", "application/grpc")
                                 .body(empty_body())
                                 .unwrap(),
                         )
@@ -307,3 +843,243 @@ pub mod ranking_service_server {
         const NAME: &'static str = "ranking.RankingService";
     }
 }
+/// Generated server implementations.
+pub mod season_admin_service_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with SeasonAdminServiceServer.
+    #[async_trait]
+    pub trait SeasonAdminService: Send + Sync + 'static {
+        /// Opens a season, soft resetting the ranked ratings of the previous one
+        async fn open_season(
+            &self,
+            request: tonic::Request<super::OpenSeasonRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpenSeasonResponse>,
+            tonic::Status,
+        >;
+        /// Closes the open season, storing every player's final ratings and rewards
+        async fn close_season(
+            &self,
+            request: tonic::Request<super::CloseSeasonRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CloseSeasonResponse>,
+            tonic::Status,
+        >;
+    }
+    /// Administration of ranked seasons, not exposed by the gateway
+    #[derive(Debug)]
+    pub struct SeasonAdminServiceServer<T: SeasonAdminService> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: SeasonAdminService> SeasonAdminServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for SeasonAdminServiceServer<T>
+    where
+        T: SeasonAdminService,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/ranking.SeasonAdminService/OpenSeason" => {
+                    #[allow(non_camel_case_types)]
+                    struct OpenSeasonSvc<T: SeasonAdminService>(pub Arc<T>);
+                    impl<
+                        T: SeasonAdminService,
+                    > tonic::server::UnaryService<super::OpenSeasonRequest>
+                    for OpenSeasonSvc<T> {
+                        type Response = super::OpenSeasonResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::OpenSeasonRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SeasonAdminService>::open_season(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = OpenSeasonSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ranking.SeasonAdminService/CloseSeason" => {
+                    #[allow(non_camel_case_types)]
+                    struct CloseSeasonSvc<T: SeasonAdminService>(pub Arc<T>);
+                    impl<
+                        T: SeasonAdminService,
+                    > tonic::server::UnaryService<super::CloseSeasonRequest>
+                    for CloseSeasonSvc<T> {
+                        type Response = super::CloseSeasonResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CloseSeasonRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SeasonAdminService>::close_season(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CloseSeasonSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        Ok(
+                            http::Response::builder()
+                                .status(200)
+                                .header("grpc-status", "12")
+                                .header("content-type", "application/grpc")
+                                .body(empty_body())
+                                .unwrap(),
+                        )
+                    })
+                }
+            }
+        }
+    }
+    impl<T: SeasonAdminService> Clone for SeasonAdminServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    impl<T: SeasonAdminService> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(Arc::clone(&self.0))
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: SeasonAdminService> tonic::server::NamedService
+    for SeasonAdminServiceServer<T> {
+        const NAME: &'static str = "ranking.SeasonAdminService";
+    }
+}
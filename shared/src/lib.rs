@@ -21,20 +21,26 @@ pub mod generated {
 }
 
 pub use generated::matchmaker_service::matchmaker_service_server::{MatchmakerService, MatchmakerServiceServer};
-pub use generated::matchmaker_service::{AddToQueueRequest, AddToQueueResponse, AcceptPendingGameRequest, AcceptPendingGameResponse, RemoveFromQueueRequest, RemoveFromQueueResponse, GetAccountStatusRequest, GetAccountStatusResponse, GetQueueSizesRequest, GetQueueSizesResponse};
+pub use generated::matchmaker_service::{AddToQueueRequest, AddToQueueResponse, AcceptPendingGameRequest, AcceptPendingGameResponse, DeclinePendingGameRequest, DeclinePendingGameResponse, RemoveFromQueueRequest, RemoveFromQueueResponse, GetAccountStatusRequest, GetAccountStatusResponse, GetQueueSizesRequest, GetQueueSizesResponse};
 
 pub type AddToQueueRequestPb = generated::matchmaker_service::AddToQueueRequest;
 pub use generated::matchmaker_service::QueueSize;
+pub use generated::matchmaker_service::QueuePool;
+pub use generated::matchmaker_service::{QueuePoolDefinition, ReloadQueuePoolsRequest, ReloadQueuePoolsResponse};
+pub use generated::matchmaker_service::matchmaking_admin_service_server::{MatchmakingAdminService, MatchmakingAdminServiceServer};
 
-pub use generated::ranking_service::{GetAccountRankingRequest, GetAccountRankingResponse};
+pub use generated::ranking_service::{GetAccountRankingRequest, GetAccountRankingResponse, CategoryRatings, PlayerRating, GetLeaderboardRequest, GetLeaderboardResponse, LeaderboardEntry, GetRatingHistoryRequest, GetRatingHistoryResponse, RatingHistory, RatingHistoryPoint, Season, GetActiveSeasonRequest, GetActiveSeasonResponse, OpenSeasonRequest, OpenSeasonResponse, CloseSeasonRequest, CloseSeasonResponse};
 pub use generated::ranking_service::ranking_service_server::{RankingService, RankingServiceServer};
+pub use generated::ranking_service::season_admin_service_server::{SeasonAdminService, SeasonAdminServiceServer};
 
 pub use generated::account_service::{RegisterRequest, Account, LoginRequest, LoginResponse, RefreshRequest, FindAccountRequest};
 pub use generated::account_service::account_service_server::{AccountService, AccountServiceServer};
 
-pub use generated::game_service::{CreateGameRequest, CreateGameResponse, MakeMoveRequest, MakeMoveResponse, GetGameStateRequest, GetGameStateResponse, CheckGameResultRequest, CheckGameResultResponse, ResignRequest, ResignResponse};
+pub use generated::game_service::{CreateGameRequest, CreateGameResponse, MakeMoveRequest, MakeMoveResponse, GetGameStateRequest, GetGameStateResponse, CheckGameResultRequest, CheckGameResultResponse, ResignRequest, ResignResponse, GetMyTurnGamesRequest, GetMyTurnGamesResponse};
+pub use generated::game_service::{Challenge, CreateChallengeRequest, CreateChallengeResponse, GetChallengeRequest, GetChallengeResponse, GetChallengesRequest, GetChallengesResponse, AcceptChallengeRequest, AcceptChallengeResponse, DeclineChallengeRequest, DeclineChallengeResponse, CancelChallengeRequest, CancelChallengeResponse, OfferRematchRequest, OfferRematchResponse, AcceptRematchRequest, AcceptRematchResponse};
 pub use generated::game_service::game_service_server::{GameService, GameServiceServer};
 
 pub mod error;
 pub mod primitives;
 pub mod events;
+pub mod redis_keys;
@@ -6,7 +6,7 @@ use prost_types::Timestamp;
 
 use crate::error::ChessmartApiError;
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum GameType {
     Rapid10_0,
     Blitz5_3,
@@ -14,6 +14,10 @@ pub enum GameType {
     Blitz3_2,
     Blitz3_0,
     Bullet1_0,
+    Correspondence1d,
+    Correspondence3d,
+    Correspondence7d,
+    Correspondence14d,
 }
 
 impl GameType {
@@ -25,8 +29,28 @@ impl GameType {
             GameType::Blitz3_2 => "Blitz3_2",
             GameType::Blitz3_0 => "Blitz3_0",
             GameType::Bullet1_0 => "Bullet1_0",
+            GameType::Correspondence1d => "Correspondence1d",
+            GameType::Correspondence3d => "Correspondence3d",
+            GameType::Correspondence7d => "Correspondence7d",
+            GameType::Correspondence14d => "Correspondence14d",
         }
     }
+
+    pub fn rating_category(&self) -> RatingCategory {
+        match self {
+            GameType::Bullet1_0 => RatingCategory::Bullet,
+            GameType::Blitz5_3 | GameType::Blitz5_0 | GameType::Blitz3_2 | GameType::Blitz3_0 => RatingCategory::Blitz,
+            GameType::Rapid10_0 => RatingCategory::Rapid,
+            GameType::Correspondence1d | GameType::Correspondence3d | GameType::Correspondence7d | GameType::Correspondence14d => RatingCategory::Correspondence,
+        }
+    }
+
+    pub fn is_correspondence(&self) -> bool {
+        matches!(
+            self,
+            GameType::Correspondence1d | GameType::Correspondence3d | GameType::Correspondence7d | GameType::Correspondence14d
+        )
+    }
 }
 
 impl FromStr for GameType {
@@ -40,11 +64,57 @@ impl FromStr for GameType {
             "Blitz3_2" => Ok(GameType::Blitz3_2),
             "Blitz3_0" => Ok(GameType::Blitz3_0),
             "Bullet1_0" => Ok(GameType::Bullet1_0),
+            "Correspondence1d" => Ok(GameType::Correspondence1d),
+            "Correspondence3d" => Ok(GameType::Correspondence3d),
+            "Correspondence7d" => Ok(GameType::Correspondence7d),
+            "Correspondence14d" => Ok(GameType::Correspondence14d),
             _ => Err(ChessmartApiError::UnknownGameTypeError(s.into())),
         }
     }
 }
 
+/// Players are rated separately for each category, as their strength varies with the time control.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RatingCategory {
+    Bullet,
+    Blitz,
+    Rapid,
+    /// No time control is classical yet
+    Classical,
+    Correspondence,
+}
+
+impl RatingCategory {
+    pub const ALL: [RatingCategory; 5] = [
+        RatingCategory::Bullet,
+        RatingCategory::Blitz,
+        RatingCategory::Rapid,
+        RatingCategory::Classical,
+        RatingCategory::Correspondence,
+    ];
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            RatingCategory::Bullet => "bullet",
+            RatingCategory::Blitz => "blitz",
+            RatingCategory::Rapid => "rapid",
+            RatingCategory::Classical => "classical",
+            RatingCategory::Correspondence => "correspondence",
+        }
+    }
+}
+
+impl FromStr for RatingCategory {
+    type Err = ChessmartApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RatingCategory::ALL
+            .into_iter()
+            .find(|category| category.to_str() == s)
+            .ok_or_else(|| ChessmartApiError::UnknownRatingCategoryError(s.into()))
+    }
+}
+
 impl ToRedisArgs for GameType {
     fn write_redis_args<W>(&self, out: &mut W)
     where
@@ -0,0 +1,75 @@
+//! Redis Cluster slots of keys.
+//!
+//! Redis Cluster only runs a script or a transaction if all of its keys hash to one slot. Every
+//! script and transaction touching several keys goes through `check_same_slot`, so a key layout
+//! that breaks this fails on a single Redis too when key slot checks are on.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Hash slots of a Redis Cluster
+const SLOT_COUNT: u16 = 16384;
+
+static CHECK_KEY_SLOTS: AtomicBool = AtomicBool::new(false);
+
+/// Turns checking the keys of every multi-key script and transaction on or off. Services turn it
+/// on from their config, tests covering key layouts turn it on themselves.
+pub fn set_key_slot_checks(enabled: bool) {
+    CHECK_KEY_SLOTS.store(enabled, Ordering::Relaxed);
+}
+
+/// CRC16/XMODEM, the checksum Redis Cluster hashes keys with.
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |crc, byte| {
+        (0..8).fold(crc ^ ((*byte as u16) << 8), |crc, _| {
+            if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 }
+        })
+    })
+}
+
+/// Cluster slot of a key. Only the hash tag is hashed if the key has one, the part between the
+/// first `{` and the next `}` when it isn't empty.
+pub fn key_slot(key: &str) -> u16 {
+    let hashed = key
+        .find('{')
+        .and_then(|open| {
+            let tag = &key[open + 1..];
+            tag.find('}').filter(|close| *close > 0).map(|close| &tag[..close])
+        })
+        .unwrap_or(key);
+
+    crc16(hashed.as_bytes()) % SLOT_COUNT
+}
+
+/// Fails as Redis Cluster would with CROSSSLOT if the keys don't share a slot, when key slot
+/// checks are on.
+pub fn check_same_slot<K: AsRef<str>>(keys: &[K]) -> redis::RedisResult<()> {
+    if !CHECK_KEY_SLOTS.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    let Some(first) = keys.first() else {
+        return Ok(());
+    };
+    let slot = key_slot(first.as_ref());
+
+    match keys.iter().find(|key| key_slot(key.as_ref()) != slot) {
+        Some(key) => Err(redis::RedisError::from((
+            redis::ErrorKind::CrossSlot,
+            "Keys don't hash to the same slot",
+            format!("{} and {}", first.as_ref(), key.as_ref()),
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Invocation of `script` with `keys`, after checking they share a slot.
+pub fn prepare_invoke<'a>(script: &'a redis::Script, keys: &[String]) -> redis::RedisResult<redis::ScriptInvocation<'a>> {
+    check_same_slot(keys)?;
+
+    let mut invocation = script.prepare_invoke();
+    for key in keys {
+        invocation.key(key);
+    }
+
+    Ok(invocation)
+}